@@ -7,6 +7,7 @@ use lemmy_api_common::{
   check_person_block,
   comment::*,
   get_local_user_view_from_jwt,
+  is_mod_or_admin,
 };
 use lemmy_apub::{
   activities::voting::{
@@ -15,7 +16,11 @@ use lemmy_apub::{
   },
   fetcher::post_or_comment::PostOrComment,
 };
-use lemmy_db_queries::{source::comment::Comment_, Likeable, Saveable};
+use lemmy_db_queries::{
+  source::comment::{CommentRevision_, Comment_},
+  Likeable,
+  Saveable,
+};
 use lemmy_db_schema::{source::comment::*, LocalUserId};
 use lemmy_db_views::{comment_view::CommentView, local_user_view::LocalUserView};
 use lemmy_utils::{ApiError, ConnectionId, LemmyError};
@@ -229,3 +234,40 @@ impl Perform for CreateCommentLike {
     .await
   }
 }
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetCommentRevisions {
+  type Response = GetCommentRevisionsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetCommentRevisionsResponse, LemmyError> {
+    let data: &GetCommentRevisions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let comment_id = data.comment_id;
+    let orig_comment = blocking(context.pool(), move |conn| {
+      CommentView::read(conn, comment_id, None)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_find_comment"))?;
+    if local_user_view.person.id != orig_comment.creator.id {
+      is_mod_or_admin(
+        context.pool(),
+        local_user_view.person.id,
+        orig_comment.community.id,
+      )
+      .await?;
+    }
+
+    let revisions = blocking(context.pool(), move |conn| {
+      CommentRevision::list_for_comment(conn, comment_id)
+    })
+    .await??;
+
+    Ok(GetCommentRevisionsResponse { revisions })
+  }
+}
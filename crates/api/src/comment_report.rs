@@ -3,12 +3,12 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_mod_permission,
   comment::*,
   get_local_user_view_from_jwt,
-  is_mod_or_admin,
 };
 use lemmy_db_queries::Reportable;
-use lemmy_db_schema::source::comment_report::*;
+use lemmy_db_schema::source::{comment_report::*, community::ModPermission};
 use lemmy_db_views::{
   comment_report_view::{CommentReportQueryBuilder, CommentReportView},
   comment_view::CommentView,
@@ -103,7 +103,13 @@ impl Perform for ResolveCommentReport {
     .await??;
 
     let person_id = local_user_view.person.id;
-    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+    check_mod_permission(
+      context.pool(),
+      person_id,
+      report.community.id,
+      ModPermission::ResolveReports,
+    )
+    .await?;
 
     let resolved = data.resolved;
     let resolve_fun = move |conn: &'_ _| {
@@ -4,9 +4,10 @@ use anyhow::Context;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_expire_time,
+  check_mod_permission,
   community::*,
   get_local_user_view_from_jwt,
-  is_mod_or_admin,
 };
 use lemmy_apub::activities::{
   community::{
@@ -40,7 +41,7 @@ use lemmy_db_views_actor::{
   community_view::CommunityView,
   person_view::PersonViewSafe,
 };
-use lemmy_utils::{location_info, utils::naive_from_unix, ApiError, ConnectionId, LemmyError};
+use lemmy_utils::{location_info, ApiError, ConnectionId, LemmyError};
 use lemmy_websocket::{messages::SendCommunityRoomMessage, LemmyContext, UserOperation};
 
 #[async_trait::async_trait(?Send)]
@@ -188,13 +189,21 @@ impl Perform for BanFromCommunity {
 
     let community_id = data.community_id;
     let banned_person_id = data.person_id;
+    let expires = check_expire_time(data.expires)?;
 
     // Verify that only mods or admins can ban
-    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    check_mod_permission(
+      context.pool(),
+      local_user_view.person.id,
+      community_id,
+      ModPermission::Ban,
+    )
+    .await?;
 
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: data.community_id,
       person_id: data.person_id,
+      expires: Some(expires),
     };
 
     let community = blocking(context.pool(), move |conn: &'_ _| {
@@ -269,9 +278,6 @@ impl Perform for BanFromCommunity {
     }
 
     // Mod tables
-    // TODO eventually do correct expires
-    let expires = data.expires.map(naive_from_unix);
-
     let form = ModBanFromCommunityForm {
       mod_person_id: local_user_view.person.id,
       other_person_id: data.person_id,
@@ -323,18 +329,70 @@ impl Perform for AddModToCommunity {
     let community_id = data.community_id;
 
     // Verify that only mods or admins can add mod
-    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    check_mod_permission(
+      context.pool(),
+      local_user_view.person.id,
+      community_id,
+      ModPermission::ManageMods,
+    )
+    .await?;
+
+    let person_id = data.person_id;
+    let mods = blocking(context.pool(), move |conn| {
+      CommunityModeratorView::for_community(conn, community_id)
+    })
+    .await??;
+    let already_mod = mods.iter().any(|m| m.moderator.id == person_id);
+
+    // Admins and the top mod can manage everyone. Other mods can only manage the mods who were
+    // added after them (or leave themselves), and only grant permissions which they have.
+    let my_person_id = local_user_view.person.id;
+    let my_index = mods.iter().position(|m| m.moderator.id == my_person_id);
+    let my_permissions = match my_index {
+      Some(i) if i > 0 && !local_user_view.person.admin => {
+        let leaving = !data.added && person_id == my_person_id;
+        let their_index = mods.iter().position(|m| m.moderator.id == person_id);
+        if !leaving && matches!(their_index, Some(t) if t <= i) {
+          return Err(ApiError::err("cant_manage_senior_mod").into());
+        }
+        mods[i].permissions
+      }
+      _ => ModPermissions::all(),
+    };
+    if let Some(permissions) = &data.permissions {
+      if !permissions.is_subset_of(&my_permissions) {
+        return Err(ApiError::err("missing_mod_permission").into());
+      }
+      // The top mod is always allowed everything
+      if mods.first().map(|m| m.moderator.id) == Some(person_id) {
+        return Err(ApiError::err("cant_restrict_top_mod").into());
+      }
+    }
 
     // Update in local database
     let community_moderator_form = CommunityModeratorForm {
       community_id: data.community_id,
       person_id: data.person_id,
     };
+    let mut granted_permissions = None;
     if data.added {
-      let join = move |conn: &'_ _| CommunityModerator::join(conn, &community_moderator_form);
-      if blocking(context.pool(), join).await?.is_err() {
+      if already_mod && data.permissions.is_none() {
         return Err(ApiError::err("community_moderator_already_exists").into());
       }
+      if !already_mod {
+        let join = move |conn: &'_ _| CommunityModerator::join(conn, &community_moderator_form);
+        if blocking(context.pool(), join).await?.is_err() {
+          return Err(ApiError::err("community_moderator_already_exists").into());
+        }
+      }
+      // New mods get the same permissions as the mod who added them, unless specified otherwise
+      let permissions = data.permissions.unwrap_or(my_permissions);
+      blocking(context.pool(), move |conn| {
+        CommunityModerator::update_permissions(conn, community_id, person_id, &permissions)
+      })
+      .await?
+      .map_err(|_| ApiError::err("couldnt_update_community"))?;
+      granted_permissions = Some(permissions);
     } else {
       let leave = move |conn: &'_ _| CommunityModerator::leave(conn, &community_moderator_form);
       if blocking(context.pool(), leave).await?.is_err() {
@@ -344,30 +402,38 @@ impl Perform for AddModToCommunity {
 
     // Mod tables
     let form = ModAddCommunityForm {
-      mod_person_id: local_user_view.person.id,
+      mod_person_id: my_person_id,
       other_person_id: data.person_id,
       community_id: data.community_id,
       removed: Some(!data.added),
+      reason: data.reason.to_owned(),
+      can_remove: granted_permissions.map(|p| p.can_remove),
+      can_ban: granted_permissions.map(|p| p.can_ban),
+      can_manage_mods: granted_permissions.map(|p| p.can_manage_mods),
+      can_resolve_reports: granted_permissions.map(|p| p.can_resolve_reports),
+      can_edit_community: granted_permissions.map(|p| p.can_edit_community),
     };
     blocking(context.pool(), move |conn| {
       ModAddCommunity::create(conn, &form)
     })
     .await??;
 
-    // Send to federated instances
-    let updated_mod_id = data.person_id;
-    let updated_mod = blocking(context.pool(), move |conn| {
-      Person::read(conn, updated_mod_id)
-    })
-    .await??;
-    let community = blocking(context.pool(), move |conn| {
-      Community::read(conn, community_id)
-    })
-    .await??;
-    if data.added {
-      AddMod::send(&community, &updated_mod, &local_user_view.person, context).await?;
-    } else {
-      RemoveMod::send(&community, &updated_mod, &local_user_view.person, context).await?;
+    // Permissions aren't federated, so only changing them for an existing mod isn't sent out
+    if !(data.added && already_mod) {
+      let updated_mod_id = data.person_id;
+      let updated_mod = blocking(context.pool(), move |conn| {
+        Person::read(conn, updated_mod_id)
+      })
+      .await??;
+      let community = blocking(context.pool(), move |conn| {
+        Community::read(conn, community_id)
+      })
+      .await??;
+      if data.added {
+        AddMod::send(&community, &updated_mod, &local_user_view.person, context).await?;
+      } else {
+        RemoveMod::send(&community, &updated_mod, &local_user_view.person, context).await?;
+      }
     }
 
     // Note: in case a remote mod is added, this returns the old moderators list, it will only get
@@ -460,7 +526,17 @@ impl Perform for TransferCommunity {
         person_id: cmod.moderator.id,
       };
 
-      let join = move |conn: &'_ _| CommunityModerator::join(conn, &community_moderator_form);
+      // Keep the permissions each mod had before
+      let permissions = cmod.permissions;
+      let join = move |conn: &'_ _| {
+        let inserted = CommunityModerator::join(conn, &community_moderator_form)?;
+        CommunityModerator::update_permissions(
+          conn,
+          inserted.community_id,
+          inserted.person_id,
+          &permissions,
+        )
+      };
       if blocking(context.pool(), join).await?.is_err() {
         return Err(ApiError::err("community_moderator_already_exists").into());
       }
@@ -472,6 +548,7 @@ impl Perform for TransferCommunity {
       other_person_id: data.person_id,
       community_id: data.community_id,
       removed: Some(false),
+      reason: data.reason.to_owned(),
     };
     blocking(context.pool(), move |conn| {
       ModTransferCommunity::create(conn, &form)
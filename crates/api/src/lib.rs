@@ -42,6 +42,9 @@ pub async fn match_websocket_operation(
     UserOperation::BlockPerson => {
       do_websocket_operation::<BlockPerson>(context, id, op, data).await
     }
+    UserOperation::BlockInstance => {
+      do_websocket_operation::<BlockInstance>(context, id, op, data).await
+    }
     UserOperation::GetPersonMentions => {
       do_websocket_operation::<GetPersonMentions>(context, id, op, data).await
     }
@@ -57,6 +60,9 @@ pub async fn match_websocket_operation(
     UserOperation::PasswordChange => {
       do_websocket_operation::<PasswordChange>(context, id, op, data).await
     }
+    UserOperation::VerifyEmail => {
+      do_websocket_operation::<VerifyEmail>(context, id, op, data).await
+    }
     UserOperation::UserJoin => do_websocket_operation::<UserJoin>(context, id, op, data).await,
     UserOperation::PostJoin => do_websocket_operation::<PostJoin>(context, id, op, data).await,
     UserOperation::CommunityJoin => {
@@ -66,12 +72,49 @@ pub async fn match_websocket_operation(
     UserOperation::SaveUserSettings => {
       do_websocket_operation::<SaveUserSettings>(context, id, op, data).await
     }
+    UserOperation::SaveKeywordFilters => {
+      do_websocket_operation::<SaveKeywordFilters>(context, id, op, data).await
+    }
+    UserOperation::GetKeywordFilters => {
+      do_websocket_operation::<GetKeywordFilters>(context, id, op, data).await
+    }
     UserOperation::ChangePassword => {
       do_websocket_operation::<ChangePassword>(context, id, op, data).await
     }
+    UserOperation::RefreshToken => {
+      do_websocket_operation::<RefreshToken>(context, id, op, data).await
+    }
+    UserOperation::ListLoginTokens => {
+      do_websocket_operation::<ListLoginTokens>(context, id, op, data).await
+    }
+    UserOperation::RevokeLoginToken => {
+      do_websocket_operation::<RevokeLoginToken>(context, id, op, data).await
+    }
     UserOperation::GetReportCount => {
       do_websocket_operation::<GetReportCount>(context, id, op, data).await
     }
+    UserOperation::CreateInviteCode => {
+      do_websocket_operation::<CreateInviteCode>(context, id, op, data).await
+    }
+    UserOperation::ListInviteCodes => {
+      do_websocket_operation::<ListInviteCodes>(context, id, op, data).await
+    }
+    UserOperation::RevokeInviteCode => {
+      do_websocket_operation::<RevokeInviteCode>(context, id, op, data).await
+    }
+    UserOperation::GenerateTotpSecret => {
+      do_websocket_operation::<GenerateTotpSecret>(context, id, op, data).await
+    }
+    UserOperation::UpdateTotp => do_websocket_operation::<UpdateTotp>(context, id, op, data).await,
+    UserOperation::MoveAccount => {
+      do_websocket_operation::<MoveAccount>(context, id, op, data).await
+    }
+    UserOperation::ExportUserData => {
+      do_websocket_operation::<ExportUserData>(context, id, op, data).await
+    }
+    UserOperation::ImportUserSettings => {
+      do_websocket_operation::<ImportUserSettings>(context, id, op, data).await
+    }
 
     // Private Message ops
     UserOperation::MarkPrivateMessageAsRead => {
@@ -86,6 +129,33 @@ pub async fn match_websocket_operation(
     UserOperation::SaveSiteConfig => {
       do_websocket_operation::<SaveSiteConfig>(context, id, op, data).await
     }
+    UserOperation::GetFederationQueue => {
+      do_websocket_operation::<GetFederationQueue>(context, id, op, data).await
+    }
+    UserOperation::AddInstance => {
+      do_websocket_operation::<AddInstance>(context, id, op, data).await
+    }
+    UserOperation::RemoveInstance => {
+      do_websocket_operation::<RemoveInstance>(context, id, op, data).await
+    }
+    UserOperation::ListInstances => {
+      do_websocket_operation::<ListInstances>(context, id, op, data).await
+    }
+    UserOperation::GetSlurFilters => {
+      do_websocket_operation::<GetSlurFilters>(context, id, op, data).await
+    }
+    UserOperation::SaveSlurFilters => {
+      do_websocket_operation::<SaveSlurFilters>(context, id, op, data).await
+    }
+    UserOperation::ListRegistrationApplications => {
+      do_websocket_operation::<ListRegistrationApplications>(context, id, op, data).await
+    }
+    UserOperation::ApproveRegistrationApplication => {
+      do_websocket_operation::<ApproveRegistrationApplication>(context, id, op, data).await
+    }
+    UserOperation::GetUnreadRegistrationApplicationCount => {
+      do_websocket_operation::<GetUnreadRegistrationApplicationCount>(context, id, op, data).await
+    }
     UserOperation::Search => do_websocket_operation::<Search>(context, id, op, data).await,
     UserOperation::ResolveObject => {
       do_websocket_operation::<ResolveObject>(context, id, op, data).await
@@ -124,6 +194,15 @@ pub async fn match_websocket_operation(
     UserOperation::ListPostReports => {
       do_websocket_operation::<ListPostReports>(context, id, op, data).await
     }
+    UserOperation::GetPostRevisions => {
+      do_websocket_operation::<GetPostRevisions>(context, id, op, data).await
+    }
+    UserOperation::ListScheduledPosts => {
+      do_websocket_operation::<ListScheduledPosts>(context, id, op, data).await
+    }
+    UserOperation::CancelScheduledPost => {
+      do_websocket_operation::<CancelScheduledPost>(context, id, op, data).await
+    }
     UserOperation::ResolvePostReport => {
       do_websocket_operation::<ResolvePostReport>(context, id, op, data).await
     }
@@ -144,6 +223,9 @@ pub async fn match_websocket_operation(
     UserOperation::CreateCommentReport => {
       do_websocket_operation::<CreateCommentReport>(context, id, op, data).await
     }
+    UserOperation::GetCommentRevisions => {
+      do_websocket_operation::<GetCommentRevisions>(context, id, op, data).await
+    }
     UserOperation::ListCommentReports => {
       do_websocket_operation::<ListCommentReports>(context, id, op, data).await
     }
@@ -187,16 +269,22 @@ pub(crate) fn captcha_as_wav_base64(captcha: &Captcha) -> String {
 
 #[cfg(test)]
 mod tests {
-  use lemmy_api_common::check_validator_time;
+  use crate::local_user::{restore_user_settings, user_settings_backup};
+  use lemmy_api_common::{check_validator_time, person::UserSettingsBackup, SlurFilters};
   use lemmy_db_queries::{
     establish_unpooled_connection,
     source::{local_user::LocalUser_, secret::Secret_},
     Crud,
+    SlurFilterMode,
   };
-  use lemmy_db_schema::source::{
-    local_user::{LocalUser, LocalUserForm},
-    person::{Person, PersonForm},
-    secret::Secret,
+  use lemmy_db_schema::{
+    naive_now,
+    source::{
+      local_user::{LocalUser, LocalUserForm},
+      person::{Person, PersonForm},
+      secret::Secret,
+      slur_filter::SlurFilter,
+    },
   };
   use lemmy_utils::{claims::Claims, settings::structs::Settings};
 
@@ -221,12 +309,7 @@ mod tests {
 
     let inserted_local_user = LocalUser::create(&conn, &local_user_form).unwrap();
 
-    let jwt = Claims::jwt(
-      inserted_local_user.id.0,
-      &secret.jwt_secret,
-      &settings.hostname,
-    )
-    .unwrap();
+    let jwt = Claims::jwt(inserted_local_user.id.0, 0, &secret.jwt_secret, &settings).unwrap();
     let claims = Claims::decode(&jwt, &secret.jwt_secret).unwrap().claims;
     let check = check_validator_time(&inserted_local_user.validator_time, &claims);
     assert!(check.is_ok());
@@ -240,4 +323,92 @@ mod tests {
     let num_deleted = Person::delete(&conn, inserted_person.id).unwrap();
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  fn test_user_settings_backup_round_trip() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "backup_round_trip".into(),
+      display_name: Some(Some("Backup".into())),
+      bio: Some(Some("A bio".into())),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "123456".to_string(),
+      show_nsfw: Some(true),
+      theme: Some("darkly".into()),
+      default_sort_type: Some(2),
+      lang: Some("de".into()),
+      show_read_posts: Some(false),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+
+    // The backup is stored by the user as json, and uploaded again for the import
+    let backup = user_settings_backup(inserted_person.clone(), inserted_local_user.clone());
+    let json = serde_json::to_string(&backup).unwrap();
+    let imported: UserSettingsBackup = serde_json::from_str(&json).unwrap();
+    let restored = restore_user_settings(&imported, "auth".into());
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(inserted_person.display_name, restored.display_name);
+    assert_eq!(inserted_person.bio, restored.bio);
+    assert_eq!(Some(inserted_person.bot_account), restored.bot_account);
+    assert_eq!(Some(true), restored.show_nsfw);
+    assert_eq!(Some("darkly".to_string()), restored.theme);
+    assert_eq!(Some(2), restored.default_sort_type);
+    assert_eq!(
+      Some(inserted_local_user.default_listing_type),
+      restored.default_listing_type
+    );
+    assert_eq!(Some("de".to_string()), restored.lang);
+    assert_eq!(Some(false), restored.show_read_posts);
+    assert_eq!(
+      Some(inserted_local_user.show_new_post_notifs),
+      restored.show_new_post_notifs
+    );
+    assert_eq!(None, restored.email);
+    assert_eq!("auth", restored.auth);
+  }
+
+  #[test]
+  fn test_slur_filters() {
+    let filter = |phrase: &str, regex, mode| SlurFilter {
+      id: 0,
+      community_id: None,
+      phrase: phrase.to_string(),
+      regex,
+      mode: mode as i16,
+      published: naive_now(),
+    };
+    let filters = SlurFilters::new(&[
+      filter("darth", false, SlurFilterMode::Reject),
+      filter("snape kills", false, SlurFilterMode::Replace),
+      filter("spoil(er|s)", true, SlurFilterMode::Flag),
+      filter("c++", false, SlurFilterMode::Flag),
+      // Invalid filters are skipped without affecting the others
+      filter("(", true, SlurFilterMode::Reject),
+    ]);
+
+    let mut flagged = vec![];
+    let replaced = filters
+      .apply("Spoilers: Snape kills Dumbledore", &mut flagged)
+      .unwrap();
+    assert_eq!("Spoilers: *removed* Dumbledore", replaced);
+    assert_eq!(vec!["spoiler".to_string()], flagged);
+
+    let mut flagged = vec![];
+    assert!(filters
+      .apply("Luke, I am your father", &mut flagged)
+      .is_ok());
+    assert!(filters.apply("Darth Vader is", &mut flagged).is_err());
+    assert!(filters
+      .apply_opt(&Some("Learning C++".into()), &mut flagged)
+      .is_ok());
+    assert_eq!(vec!["c++".to_string()], flagged);
+  }
 }
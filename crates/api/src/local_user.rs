@@ -6,10 +6,22 @@ use captcha::{gen, Difficulty};
 use chrono::Duration;
 use lemmy_api_common::{
   blocking,
+  check_expire_time,
+  check_totp_2fa,
+  check_totp_2fa_token,
+  community::{BlockCommunity, FollowCommunity},
+  create_login_session,
+  generate_totp_2fa_secret,
   get_local_user_view_from_jwt,
   is_admin,
   password_length_check,
   person::*,
+  send_verification_email,
+  totp_2fa_url,
+};
+use lemmy_apub::{
+  activities::person::move_person::{verify_move_target, MovePerson},
+  fetcher::object_id::ObjectId,
 };
 use lemmy_db_queries::{
   diesel_option_overwrite,
@@ -18,15 +30,25 @@ use lemmy_db_queries::{
   source::{
     comment::Comment_,
     community::Community_,
+    email_verification::EmailVerification_,
+    invite_code::InviteCode_,
     local_user::LocalUser_,
+    local_user_keyword_filter::LocalUserKeywordFilter_,
+    login_token::LoginToken_,
     password_reset_request::PasswordResetRequest_,
     person::Person_,
     person_mention::PersonMention_,
     post::Post_,
     private_message::PrivateMessage_,
+    registration_application::RegistrationApplication_,
+    site::Site_,
+    totp_2fa_recovery_code::Totp2faRecoveryCode_,
   },
+  Bannable,
   Blockable,
   Crud,
+  DeleteableOrRemoveable,
+  KeywordFilterAction,
   SortType,
 };
 use lemmy_db_schema::{
@@ -34,16 +56,26 @@ use lemmy_db_schema::{
   source::{
     comment::Comment,
     community::Community,
+    email_verification::EmailVerification,
+    invite_code::{InviteCode, InviteCodeForm},
     local_user::{LocalUser, LocalUserForm},
+    local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterForm},
+    login_token::LoginToken,
     moderator::*,
     password_reset_request::*,
     person::*,
+    person_ban::{PersonBan, PersonBanForm},
     person_block::{PersonBlock, PersonBlockForm},
     person_mention::*,
     post::Post,
     private_message::PrivateMessage,
+    registration_application::RegistrationApplication,
     site::*,
+    totp_2fa_recovery_code::Totp2faRecoveryCode,
   },
+  CommunityId,
+  LocalUserId,
+  PersonId,
 };
 use lemmy_db_views::{
   comment_report_view::CommentReportView,
@@ -52,7 +84,10 @@ use lemmy_db_views::{
   post_report_view::PostReportView,
 };
 use lemmy_db_views_actor::{
+  community_block_view::CommunityBlockView,
+  community_follower_view::CommunityFollowerView,
   community_moderator_view::CommunityModeratorView,
+  person_block_view::PersonBlockView,
   person_mention_view::{PersonMentionQueryBuilder, PersonMentionView},
   person_view::PersonViewSafe,
 };
@@ -60,7 +95,7 @@ use lemmy_utils::{
   claims::Claims,
   email::send_email,
   location_info,
-  utils::{generate_random_string, is_valid_display_name, is_valid_matrix_id, naive_from_unix},
+  utils::{generate_random_string, is_valid_display_name, is_valid_matrix_id},
   ApiError,
   ConnectionId,
   LemmyError,
@@ -70,6 +105,23 @@ use lemmy_websocket::{
   LemmyContext,
   UserOperation,
 };
+use regex::RegexBuilder;
+use url::Url;
+
+const MAX_KEYWORD_FILTERS: usize = 50;
+const MAX_KEYWORD_FILTER_LENGTH: usize = 200;
+const MAX_KEYWORD_FILTER_REGEX_LENGTH: usize = 100;
+/// Limit for the compiled size of a keyword filter regex, in bytes
+const KEYWORD_FILTER_REGEX_SIZE_LIMIT: usize = 1 << 16;
+const MAX_ACCOUNT_ALIASES: usize = 5;
+/// Follows and blocks are fetched one by one while handling the import request, so only few of
+/// them can be imported at once
+const MAX_IMPORT_ITEMS: usize = 50;
+/// Number of single-use recovery codes which are generated when enabling two-factor authentication
+const TOTP_RECOVERY_CODES: usize = 10;
+/// Limits for invite codes of users who aren't admins
+const MAX_USER_INVITE_CODES: i64 = 5;
+const MAX_USER_INVITE_CODE_USES: i32 = 10;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for Login {
@@ -78,7 +130,7 @@ impl Perform for Login {
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<ConnectionId>,
+    websocket_id: Option<ConnectionId>,
   ) -> Result<LoginResponse, LemmyError> {
     let data: &Login = self;
 
@@ -100,14 +152,65 @@ impl Perform for Login {
       return Err(ApiError::err("password_incorrect").into());
     }
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Claims::jwt(
-        local_user_view.local_user.id.0,
-        &context.secret().jwt_secret,
-        &context.settings().hostname,
-      )?,
-    })
+    check_totp_2fa(
+      &local_user_view,
+      &data.totp_2fa_token,
+      context.pool(),
+      &context.settings(),
+    )
+    .await?;
+
+    check_login_allowed(&local_user_view, context).await?;
+
+    // Start a new login session
+    let client_info = context.client_info(websocket_id, &data.client_info).await?;
+    create_login_session(
+      local_user_view.local_user.id,
+      client_info,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+  }
+}
+
+/// Makes sure that the user verified their email address and was accepted, if the site requires
+/// it
+async fn check_login_allowed(
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  // Admins can always log in, so that they can't lock themselves out
+  if !local_user_view.person.admin {
+    if let Ok(site) = blocking(context.pool(), Site::read_simple).await? {
+      if site.require_email_verification && !local_user_view.local_user.email_verified {
+        return Err(ApiError::err("email_not_verified").into());
+      }
+      if site.require_application && !local_user_view.local_user.accepted_application {
+        check_registration_application(local_user_view.local_user.id, context).await?;
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Returns an error explaining why the user isn't accepted yet
+async fn check_registration_application(
+  local_user_id: LocalUserId,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let application = blocking(context.pool(), move |conn| {
+    RegistrationApplication::find_by_local_user_id(conn, local_user_id)
+  })
+  .await?
+  .map_err(|_| ApiError::err("registration_application_not_found"))?;
+
+  // Applications which were decided by an admin, but not accepted, were denied
+  if application.admin_id.is_some() {
+    Err(ApiError::err("registration_denied").into())
+  } else {
+    Err(ApiError::err("registration_application_pending").into())
   }
 }
 
@@ -198,12 +301,57 @@ impl Perform for SaveUserSettings {
       }
     }
 
+    let also_known_as = match &data.also_known_as {
+      Some(also_known_as) => {
+        if also_known_as.len() > MAX_ACCOUNT_ALIASES {
+          return Err(ApiError::err("too_many_account_aliases").into());
+        }
+        let mut aliases = vec![];
+        for alias in also_known_as {
+          let alias = Url::parse(alias.trim()).map_err(|_| ApiError::err("invalid_url"))?;
+          if alias == local_user_view.person.actor_id.clone().into_inner() {
+            return Err(ApiError::err("cant_alias_yourself").into());
+          }
+          aliases.push(alias.into());
+        }
+        Some(aliases)
+      }
+      None => None,
+    };
+
     let local_user_id = local_user_view.local_user.id;
     let person_id = local_user_view.person.id;
     let default_listing_type = data.default_listing_type;
     let default_sort_type = data.default_sort_type;
     let password_encrypted = local_user_view.local_user.password_encrypted;
 
+    // A new email address is only saved once it was verified. Without an email server there is no
+    // way to verify it, so it is saved unverified.
+    let mut verify_email_sent = false;
+    let mut email_verified = None;
+    let email = match email {
+      Some(Some(new_email)) if Some(&new_email) == local_user_view.local_user.email.as_ref() => {
+        None
+      }
+      Some(Some(new_email)) if context.settings().email.is_some() => {
+        send_verification_email(
+          local_user_id,
+          &new_email,
+          &local_user_view.person.name,
+          context.pool(),
+          &context.settings(),
+        )
+        .await?;
+        verify_email_sent = true;
+        None
+      }
+      Some(new_email) => {
+        email_verified = Some(false);
+        Some(new_email)
+      }
+      None => None,
+    };
+
     let person_form = PersonForm {
       name: local_user_view.person.name,
       avatar,
@@ -224,6 +372,8 @@ impl Perform for SaveUserSettings {
       shared_inbox_url: None,
       matrix_user_id,
       bot_account,
+      moved_to: None,
+      also_known_as,
     };
 
     let person_res = blocking(context.pool(), move |conn| {
@@ -252,6 +402,10 @@ impl Perform for SaveUserSettings {
       show_read_posts: data.show_read_posts,
       show_new_post_notifs: data.show_new_post_notifs,
       send_notifications_to_email: data.send_notifications_to_email,
+      email_verified,
+      accepted_application: None,
+      totp_2fa_secret: None,
+      totp_2fa_enabled: None,
     };
 
     let local_user_res = blocking(context.pool(), move |conn| {
@@ -273,17 +427,130 @@ impl Perform for SaveUserSettings {
       }
     };
 
-    // Return the jwt
+    // Return a new access token for the same login session
+    let login_token_id = Claims::decode(&data.auth, &context.secret().jwt_secret)?
+      .claims
+      .sid;
     Ok(LoginResponse {
-      jwt: Claims::jwt(
+      jwt: Some(Claims::jwt(
         updated_local_user.id.0,
+        login_token_id,
         &context.secret().jwt_secret,
-        &context.settings().hostname,
-      )?,
+        &context.settings(),
+      )?),
+      refresh_token: None,
+      verify_email_sent,
+      registration_created: false,
     })
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for SaveKeywordFilters {
+  type Response = KeywordFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<KeywordFiltersResponse, LemmyError> {
+    let data: &SaveKeywordFilters = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if data.keyword_filters.len() > MAX_KEYWORD_FILTERS {
+      return Err(ApiError::err("too_many_keyword_filters").into());
+    }
+
+    let local_user_id = local_user_view.local_user.id;
+    let mut forms = Vec::with_capacity(data.keyword_filters.len());
+    for filter in &data.keyword_filters {
+      let phrase = filter.phrase.trim().to_string();
+      if phrase.is_empty() || phrase.chars().count() > MAX_KEYWORD_FILTER_LENGTH {
+        return Err(ApiError::err("invalid_keyword_filter").into());
+      }
+      let action = filter.action.unwrap_or(KeywordFilterAction::Hide as i16);
+      if action != KeywordFilterAction::Hide as i16 && action != KeywordFilterAction::Blur as i16 {
+        return Err(ApiError::err("invalid_keyword_filter").into());
+      }
+      let regex = filter.regex.unwrap_or(false);
+      if regex {
+        check_keyword_filter_regex(&phrase)?;
+      }
+      forms.push(LocalUserKeywordFilterForm {
+        local_user_id,
+        phrase,
+        regex,
+        scope_title: filter.scope_title.unwrap_or(true),
+        scope_body: filter.scope_body.unwrap_or(true),
+        scope_comments: filter.scope_comments.unwrap_or(true),
+        action,
+      });
+    }
+
+    // The regexes are matched by postgres, so they need to be valid in its dialect
+    let regexes = forms
+      .iter()
+      .filter(|f| f.regex)
+      .map(|f| f.phrase.to_owned())
+      .collect::<Vec<String>>();
+    let regexes_valid = blocking(context.pool(), move |conn| {
+      regexes
+        .iter()
+        .all(|r| LocalUserKeywordFilter::is_valid_regex(conn, r))
+    })
+    .await?;
+    if !regexes_valid {
+      return Err(ApiError::err("invalid_keyword_filter_regex").into());
+    }
+
+    let keyword_filters = blocking(context.pool(), move |conn| {
+      LocalUserKeywordFilter::replace_for_local_user(conn, local_user_id, &forms)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_keyword_filters"))?;
+
+    Ok(KeywordFiltersResponse { keyword_filters })
+  }
+}
+
+/// Keyword filter regexes are matched by postgres against every post and comment in a listing.
+/// Backreferences and lookaround can make its matcher take exponential time, so only patterns
+/// which the `regex` crate also accepts are allowed, as it supports neither.
+fn check_keyword_filter_regex(pattern: &str) -> Result<(), LemmyError> {
+  if pattern.chars().count() > MAX_KEYWORD_FILTER_REGEX_LENGTH {
+    return Err(ApiError::err("invalid_keyword_filter_regex").into());
+  }
+  RegexBuilder::new(pattern)
+    .size_limit(KEYWORD_FILTER_REGEX_SIZE_LIMIT)
+    .build()
+    .map_err(|_| ApiError::err("invalid_keyword_filter_regex"))?;
+  Ok(())
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetKeywordFilters {
+  type Response = KeywordFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<KeywordFiltersResponse, LemmyError> {
+    let data: &GetKeywordFilters = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let local_user_id = local_user_view.local_user.id;
+    let keyword_filters = blocking(context.pool(), move |conn| {
+      LocalUserKeywordFilter::for_local_user(conn, local_user_id)
+    })
+    .await??;
+
+    Ok(KeywordFiltersResponse { keyword_filters })
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for ChangePassword {
   type Response = LoginResponse;
@@ -291,7 +558,7 @@ impl Perform for ChangePassword {
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<ConnectionId>,
+    websocket_id: Option<ConnectionId>,
   ) -> Result<LoginResponse, LemmyError> {
     let data: &ChangePassword = self;
     let local_user_view =
@@ -316,22 +583,147 @@ impl Perform for ChangePassword {
 
     let local_user_id = local_user_view.local_user.id;
     let new_password = data.new_password.to_owned();
-    let updated_local_user = blocking(context.pool(), move |conn| {
+    blocking(context.pool(), move |conn| {
       LocalUser::update_password(conn, local_user_id, &new_password)
     })
     .await??;
 
-    // Return the jwt
+    // Log out everywhere, and start a new login session for this client
+    blocking(context.pool(), move |conn| {
+      LoginToken::revoke_all(conn, local_user_id)
+    })
+    .await??;
+    let client_info = context.client_info(websocket_id, &data.client_info).await?;
+    create_login_session(
+      local_user_id,
+      client_info,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RefreshToken {
+  type Response = LoginResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<LoginResponse, LemmyError> {
+    let data: &RefreshToken = self;
+
+    let client_info = context.client_info(websocket_id, &data.client_info).await?;
+    let old_refresh_token = data.refresh_token.to_owned();
+    let refresh_token = generate_random_string();
+    let new_refresh_token = refresh_token.to_owned();
+    let inactive_after = Duration::days(context.settings().session.inactive_days.into());
+    let login_token = blocking(context.pool(), move |conn| {
+      LoginToken::refresh(
+        conn,
+        &old_refresh_token,
+        &new_refresh_token,
+        client_info.ip,
+        client_info.user_agent,
+        inactive_after,
+      )
+    })
+    .await?
+    .map_err(|_| ApiError::err("not_logged_in"))?;
+
+    // Make sure that the user can still log in
+    let local_user_id = login_token.local_user_id;
+    let local_user_view = blocking(context.pool(), move |conn| {
+      LocalUserView::read(conn, local_user_id)
+    })
+    .await??;
+    if local_user_view.person.banned {
+      return Err(ApiError::err("site_ban").into());
+    }
+    if local_user_view.person.deleted {
+      return Err(ApiError::err("deleted").into());
+    }
+
     Ok(LoginResponse {
-      jwt: Claims::jwt(
-        updated_local_user.id.0,
+      jwt: Some(Claims::jwt(
+        local_user_id.0,
+        login_token.id,
         &context.secret().jwt_secret,
-        &context.settings().hostname,
-      )?,
+        &context.settings(),
+      )?),
+      refresh_token: Some(refresh_token),
+      verify_email_sent: false,
+      registration_created: false,
     })
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for ListLoginTokens {
+  type Response = ListLoginTokensResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListLoginTokensResponse, LemmyError> {
+    let data: &ListLoginTokens = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    list_login_tokens(local_user_view.local_user.id, &data.auth, context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RevokeLoginToken {
+  type Response = ListLoginTokensResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListLoginTokensResponse, LemmyError> {
+    let data: &RevokeLoginToken = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let local_user_id = local_user_view.local_user.id;
+    let login_token_id = data.login_token_id;
+    let revoked = blocking(context.pool(), move |conn| {
+      LoginToken::revoke(conn, login_token_id, local_user_id)
+    })
+    .await??;
+    if revoked == 0 {
+      return Err(ApiError::err("couldnt_find_login_token").into());
+    }
+
+    list_login_tokens(local_user_id, &data.auth, context).await
+  }
+}
+
+async fn list_login_tokens(
+  local_user_id: LocalUserId,
+  auth: &str,
+  context: &Data<LemmyContext>,
+) -> Result<ListLoginTokensResponse, LemmyError> {
+  let current_login_token_id = Claims::decode(auth, &context.secret().jwt_secret)?
+    .claims
+    .sid;
+  let login_tokens = blocking(context.pool(), move |conn| {
+    LoginToken::list_for_local_user(conn, local_user_id)
+  })
+  .await??;
+
+  Ok(ListLoginTokensResponse {
+    login_tokens,
+    current_login_token_id,
+  })
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for AddAdmin {
   type Response = AddAdminResponse;
@@ -350,6 +742,20 @@ impl Perform for AddAdmin {
 
     let added = data.added;
     let added_person_id = data.person_id;
+    if added {
+      let site = blocking(context.pool(), Site::read_simple).await??;
+      if site.require_totp_2fa_for_admins {
+        let added_local_user = blocking(context.pool(), move |conn| {
+          LocalUserView::read_person(conn, added_person_id)
+        })
+        .await?
+        .map_err(|_| ApiError::err("couldnt_find_that_username_or_email"))?;
+        if !added_local_user.local_user.totp_2fa_enabled {
+          return Err(ApiError::err("admin_requires_totp_2fa").into());
+        }
+      }
+    }
+
     let added_admin = match blocking(context.pool(), move |conn| {
       Person::add_admin(conn, added_person_id, added)
     })
@@ -366,6 +772,7 @@ impl Perform for AddAdmin {
       mod_person_id: local_user_view.person.id,
       other_person_id: added_admin.id,
       removed: Some(!data.added),
+      reason: data.reason.to_owned(),
     };
 
     blocking(context.pool(), move |conn| ModAdd::create(conn, &form)).await??;
@@ -413,11 +820,26 @@ impl Perform for BanPerson {
 
     let ban = data.ban;
     let banned_person_id = data.person_id;
+    let expires = check_expire_time(data.expires)?;
     let ban_person = move |conn: &'_ _| Person::ban_person(conn, banned_person_id, ban);
     if blocking(context.pool(), ban_person).await?.is_err() {
       return Err(ApiError::err("couldnt_update_user").into());
     }
 
+    // Remember when the ban expires, so that it can be lifted automatically
+    let person_ban_form = PersonBanForm {
+      person_id: banned_person_id,
+      expires: Some(expires),
+    };
+    blocking(context.pool(), move |conn| {
+      if ban {
+        PersonBan::ban(conn, &person_ban_form).map(|_| ())
+      } else {
+        PersonBan::unban(conn, &person_ban_form).map(|_| ())
+      }
+    })
+    .await??;
+
     // Remove their data if that's desired
     if data.remove_data.unwrap_or(false) {
       // Posts
@@ -455,8 +877,6 @@ impl Perform for BanPerson {
     }
 
     // Mod tables
-    let expires = data.expires.map(naive_from_unix);
-
     let form = ModBanForm {
       mod_person_id: local_user_view.person.id,
       other_person_id: data.person_id,
@@ -734,6 +1154,11 @@ impl Perform for PasswordReset {
     .await?
     .map_err(|_| ApiError::err("couldnt_find_that_username_or_email"))?;
 
+    // Reset mails are only sent to verified addresses, so that they can't be sent to strangers
+    if !local_user_view.local_user.email_verified {
+      return Err(ApiError::err("couldnt_find_that_username_or_email").into());
+    }
+
     // Generate a random token
     let token = generate_random_string();
 
@@ -764,6 +1189,41 @@ impl Perform for PasswordReset {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for VerifyEmail {
+  type Response = VerifyEmailResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<VerifyEmailResponse, LemmyError> {
+    let data: &VerifyEmail = self;
+
+    let token = data.token.clone();
+    let verification = blocking(context.pool(), move |conn| {
+      EmailVerification::read_from_token(conn, &token)
+    })
+    .await?
+    .map_err(|_| ApiError::err("token_not_found"))?;
+
+    let local_user_id = verification.local_user_id;
+    blocking(context.pool(), move |conn| {
+      LocalUser::set_verified_email(conn, local_user_id, &verification.email)
+    })
+    .await?
+    .map_err(|_| ApiError::err("email_already_exists"))?;
+
+    // Any other pending verifications are outdated now
+    blocking(context.pool(), move |conn| {
+      EmailVerification::delete_for_local_user(conn, local_user_id)
+    })
+    .await??;
+
+    Ok(VerifyEmailResponse {})
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for PasswordChange {
   type Response = LoginResponse;
@@ -771,7 +1231,7 @@ impl Perform for PasswordChange {
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<ConnectionId>,
+    websocket_id: Option<ConnectionId>,
   ) -> Result<LoginResponse, LemmyError> {
     let data: &PasswordChange = self;
 
@@ -791,20 +1251,570 @@ impl Perform for PasswordChange {
 
     // Update the user with the new password
     let password = data.password.clone();
-    let updated_local_user = blocking(context.pool(), move |conn| {
+    blocking(context.pool(), move |conn| {
       LocalUser::update_password(conn, local_user_id, &password)
     })
     .await?
     .map_err(|_| ApiError::err("couldnt_update_user"))?;
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Claims::jwt(
-        updated_local_user.id.0,
-        &context.secret().jwt_secret,
-        &context.settings().hostname,
-      )?,
+    // Log out everywhere, and start a new login session for this client
+    blocking(context.pool(), move |conn| {
+      LoginToken::revoke_all(conn, local_user_id)
+    })
+    .await??;
+
+    // The password is changed either way, but users who can't log in yet don't get a session.
+    // Neither do users with two-factor authentication, they have to log in with their code.
+    let local_user_view = blocking(context.pool(), move |conn| {
+      LocalUserView::read(conn, local_user_id)
     })
+    .await??;
+    if local_user_view.local_user.totp_2fa_enabled
+      || check_login_allowed(&local_user_view, context)
+        .await
+        .is_err()
+    {
+      return Ok(LoginResponse {
+        jwt: None,
+        refresh_token: None,
+        verify_email_sent: false,
+        registration_created: false,
+      });
+    }
+    let client_info = context.client_info(websocket_id, &data.client_info).await?;
+    create_login_session(
+      local_user_id,
+      client_info,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for CreateInviteCode {
+  type Response = InviteCodeResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<InviteCodeResponse, LemmyError> {
+    let data: &CreateInviteCode = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let max_uses = if local_user_view.person.admin {
+      data.max_uses
+    } else {
+      let valid_codes = blocking(context.pool(), move |conn| {
+        InviteCode::count_valid_for_creator(conn, person_id)
+      })
+      .await??;
+      if valid_codes >= MAX_USER_INVITE_CODES {
+        return Err(ApiError::err("too_many_invite_codes").into());
+      }
+      Some(data.max_uses.unwrap_or(1).min(MAX_USER_INVITE_CODE_USES))
+    };
+    if max_uses.map(|m| m < 1).unwrap_or(false) {
+      return Err(ApiError::err("invalid_invite_code_max_uses").into());
+    }
+    let expires =
+      check_expire_time(data.expires).map_err(|_| ApiError::err("invite_code_expires_in_past"))?;
+
+    let invite_code_form = InviteCodeForm {
+      code: generate_random_string(),
+      creator_id: person_id,
+      max_uses,
+      expires,
+    };
+    let invite_code = blocking(context.pool(), move |conn| {
+      InviteCode::create(conn, &invite_code_form)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_create_invite_code"))?;
+
+    Ok(InviteCodeResponse { invite_code })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListInviteCodes {
+  type Response = ListInviteCodesResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListInviteCodesResponse, LemmyError> {
+    let data: &ListInviteCodes = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    list_invite_codes(&local_user_view, context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RevokeInviteCode {
+  type Response = ListInviteCodesResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListInviteCodesResponse, LemmyError> {
+    let data: &RevokeInviteCode = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let invite_code_id = data.invite_code_id;
+    let invite_code = blocking(context.pool(), move |conn| {
+      InviteCode::read(conn, invite_code_id)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_find_invite_code"))?;
+
+    // Only the creator or an admin can revoke it
+    if invite_code.creator_id != local_user_view.person.id {
+      is_admin(&local_user_view)?;
+    }
+
+    blocking(context.pool(), move |conn| {
+      InviteCode::delete(conn, invite_code_id)
+    })
+    .await??;
+
+    list_invite_codes(&local_user_view, context).await
+  }
+}
+
+async fn list_invite_codes(
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<ListInviteCodesResponse, LemmyError> {
+  let creator_id = if local_user_view.person.admin {
+    None
+  } else {
+    Some(local_user_view.person.id)
+  };
+  let invite_codes = blocking(context.pool(), move |conn| {
+    InviteCode::list(conn, creator_id)
+  })
+  .await??;
+
+  Ok(ListInviteCodesResponse { invite_codes })
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GenerateTotpSecret {
+  type Response = GenerateTotpSecretResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GenerateTotpSecretResponse, LemmyError> {
+    let data: &GenerateTotpSecret = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if local_user_view.local_user.totp_2fa_enabled {
+      return Err(ApiError::err("totp_already_enabled").into());
+    }
+
+    // The secret stays disabled until it is confirmed with a valid code
+    let secret = generate_totp_2fa_secret();
+    let totp_secret_url = totp_2fa_url(&secret, &local_user_view.person.name, &context.settings())?;
+    let local_user_id = local_user_view.local_user.id;
+    blocking(context.pool(), move |conn| {
+      LocalUser::set_totp_2fa_secret(conn, local_user_id, Some(secret), false)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_user"))?;
+
+    Ok(GenerateTotpSecretResponse { totp_secret_url })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for UpdateTotp {
+  type Response = UpdateTotpResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<UpdateTotpResponse, LemmyError> {
+    let data: &UpdateTotp = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    let local_user_id = local_user_view.local_user.id;
+
+    if data.enabled {
+      if local_user_view.local_user.totp_2fa_enabled {
+        return Err(ApiError::err("totp_already_enabled").into());
+      }
+      let secret = local_user_view
+        .local_user
+        .totp_2fa_secret
+        .to_owned()
+        .ok_or_else(|| ApiError::err("missing_totp_secret"))?;
+      let valid = check_totp_2fa_token(
+        &secret,
+        &data.totp_2fa_token,
+        &local_user_view.person.name,
+        &context.settings(),
+      )?;
+      if !valid {
+        return Err(ApiError::err("incorrect_totp_token").into());
+      }
+
+      let recovery_codes: Vec<String> = (0..TOTP_RECOVERY_CODES)
+        .map(|_| generate_random_string())
+        .collect();
+      let codes = recovery_codes.to_owned();
+      blocking(context.pool(), move |conn| {
+        Totp2faRecoveryCode::replace_for_local_user(conn, local_user_id, &codes)?;
+        LocalUser::set_totp_2fa_secret(conn, local_user_id, Some(secret), true)
+      })
+      .await?
+      .map_err(|_| ApiError::err("couldnt_update_user"))?;
+
+      Ok(UpdateTotpResponse {
+        enabled: true,
+        recovery_codes: Some(recovery_codes),
+      })
+    } else {
+      if !local_user_view.local_user.totp_2fa_enabled {
+        return Err(ApiError::err("totp_not_enabled").into());
+      }
+      check_totp_2fa(
+        &local_user_view,
+        &Some(data.totp_2fa_token.to_owned()),
+        context.pool(),
+        &context.settings(),
+      )
+      .await?;
+
+      blocking(context.pool(), move |conn| {
+        Totp2faRecoveryCode::delete_for_local_user(conn, local_user_id)?;
+        LocalUser::set_totp_2fa_secret(conn, local_user_id, None, false)
+      })
+      .await?
+      .map_err(|_| ApiError::err("couldnt_update_user"))?;
+
+      Ok(UpdateTotpResponse {
+        enabled: false,
+        recovery_codes: None,
+      })
+    }
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for MoveAccount {
+  type Response = MoveAccountResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<MoveAccountResponse, LemmyError> {
+    let data: &MoveAccount = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let target = Url::parse(data.target.trim()).map_err(|_| ApiError::err("invalid_url"))?;
+    let target = verify_move_target(
+      &local_user_view.person,
+      &ObjectId::new(target),
+      context,
+      &mut 0,
+    )
+    .await
+    .map_err(|_| ApiError::err("invalid_move_target"))?;
+
+    let person_id = local_user_view.person.id;
+    let moved_to = target.actor_id.clone();
+    let person = blocking(context.pool(), move |conn| {
+      Person::set_moved_to(conn, person_id, Some(moved_to))
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_user"))?;
+
+    MovePerson::send(&person, &target, context).await?;
+
+    let person_view = blocking(context.pool(), move |conn| {
+      PersonViewSafe::read(conn, person_id)
+    })
+    .await??;
+
+    Ok(MoveAccountResponse { person_view })
+  }
+}
+
+/// The profile and settings of a user for the export, without follows and blocks.
+pub(crate) fn user_settings_backup(person: Person, local_user: LocalUser) -> UserSettingsBackup {
+  UserSettingsBackup {
+    display_name: person.display_name,
+    bio: person.bio,
+    avatar: person.avatar,
+    banner: person.banner,
+    matrix_user_id: person.matrix_user_id,
+    bot_account: Some(person.bot_account),
+    show_nsfw: Some(local_user.show_nsfw),
+    theme: Some(local_user.theme),
+    default_sort_type: Some(local_user.default_sort_type),
+    default_listing_type: Some(local_user.default_listing_type),
+    lang: Some(local_user.lang),
+    show_avatars: Some(local_user.show_avatars),
+    send_notifications_to_email: Some(local_user.send_notifications_to_email),
+    show_bot_accounts: Some(local_user.show_bot_accounts),
+    show_scores: Some(local_user.show_scores),
+    show_read_posts: Some(local_user.show_read_posts),
+    show_new_post_notifs: Some(local_user.show_new_post_notifs),
+    ..UserSettingsBackup::default()
+  }
+}
+
+/// Turns the profile and settings of a backup back into a `SaveUserSettings`. The email isn't part
+/// of the backup, it has to be verified on the new instance.
+pub(crate) fn restore_user_settings(backup: &UserSettingsBackup, auth: String) -> SaveUserSettings {
+  SaveUserSettings {
+    show_nsfw: backup.show_nsfw,
+    show_scores: backup.show_scores,
+    theme: backup.theme.to_owned(),
+    default_sort_type: backup.default_sort_type,
+    default_listing_type: backup.default_listing_type,
+    lang: backup.lang.to_owned(),
+    avatar: backup.avatar.as_ref().map(|a| a.to_string()),
+    banner: backup.banner.as_ref().map(|b| b.to_string()),
+    display_name: backup.display_name.to_owned(),
+    email: None,
+    bio: backup.bio.to_owned(),
+    matrix_user_id: backup.matrix_user_id.to_owned(),
+    show_avatars: backup.show_avatars,
+    send_notifications_to_email: backup.send_notifications_to_email,
+    bot_account: backup.bot_account,
+    show_bot_accounts: backup.show_bot_accounts,
+    show_read_posts: backup.show_read_posts,
+    show_new_post_notifs: backup.show_new_post_notifs,
+    also_known_as: None,
+    auth,
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ExportUserData {
+  type Response = ExportUserDataResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ExportUserDataResponse, LemmyError> {
+    let data: &ExportUserData = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    let person = local_user_view.person;
+    let local_user = local_user_view.local_user;
+    let person_id = person.id;
+
+    // Read the tables directly, so that nothing is filtered out like in the listings
+    let (posts, comments, private_messages, saved_posts, saved_comments) = blocking(
+      context.pool(),
+      move |conn| -> Result<_, diesel::result::Error> {
+        let posts = Post::list_for_creator(conn, person_id)?;
+        let comments = Comment::list_for_creator(conn, person_id)?;
+        let private_messages = PrivateMessage::list_for_person(conn, person_id)?;
+        // Only show what is left of saved items of others which were deleted or removed
+        let saved_posts = Post::list_saved(conn, person_id)?
+          .into_iter()
+          .map(|p| {
+            if p.creator_id != person_id && (p.deleted || p.removed) {
+              p.blank_out_deleted_or_removed_info()
+            } else {
+              p
+            }
+          })
+          .collect();
+        let saved_comments = Comment::list_saved(conn, person_id)?
+          .into_iter()
+          .map(|c| {
+            if c.creator_id != person_id && (c.deleted || c.removed) {
+              c.blank_out_deleted_or_removed_info()
+            } else {
+              c
+            }
+          })
+          .collect();
+        Ok((
+          posts,
+          comments,
+          private_messages,
+          saved_posts,
+          saved_comments,
+        ))
+      },
+    )
+    .await??;
+
+    let followed_communities = blocking(context.pool(), move |conn| {
+      CommunityFollowerView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|f| f.community.actor_id)
+    .collect();
+    let blocked_communities = blocking(context.pool(), move |conn| {
+      CommunityBlockView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|b| b.community.actor_id)
+    .collect();
+    let blocked_users = blocking(context.pool(), move |conn| {
+      PersonBlockView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|b| b.target.actor_id)
+    .collect();
+
+    let mut settings = user_settings_backup(person, local_user);
+    settings.followed_communities = followed_communities;
+    settings.blocked_communities = blocked_communities;
+    settings.blocked_users = blocked_users;
+
+    Ok(ExportUserDataResponse {
+      settings,
+      posts,
+      comments,
+      private_messages,
+      saved_posts,
+      saved_comments,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ImportUserSettings {
+  type Response = ImportUserSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<ImportUserSettingsResponse, LemmyError> {
+    let data: &ImportUserSettings = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    let backup = &data.settings;
+
+    let items = backup.followed_communities.len()
+      + backup.blocked_communities.len()
+      + backup.blocked_users.len();
+    if items > MAX_IMPORT_ITEMS {
+      return Err(ApiError::err("too_many_items_to_import").into());
+    }
+
+    restore_user_settings(backup, data.auth.to_owned())
+      .perform(context, websocket_id)
+      .await?;
+
+    // Skip everything that is already followed or blocked, so that the import can be repeated
+    let person_id = local_user_view.person.id;
+    let followed: Vec<CommunityId> = blocking(context.pool(), move |conn| {
+      CommunityFollowerView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|f| f.community.id)
+    .collect();
+    let blocked_communities: Vec<CommunityId> = blocking(context.pool(), move |conn| {
+      CommunityBlockView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|b| b.community.id)
+    .collect();
+    let blocked_users: Vec<PersonId> = blocking(context.pool(), move |conn| {
+      PersonBlockView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|b| b.target.id)
+    .collect();
+
+    let mut failed = vec![];
+    for actor_id in &backup.followed_communities {
+      let res: Result<(), LemmyError> = async {
+        let community = ObjectId::<Community>::new(actor_id.to_owned())
+          .dereference(context, &mut 0)
+          .await?;
+        if !followed.contains(&community.id) {
+          let follow = FollowCommunity {
+            community_id: community.id,
+            follow: true,
+            auth: data.auth.to_owned(),
+          };
+          follow.perform(context, websocket_id).await?;
+        }
+        Ok(())
+      }
+      .await;
+      if res.is_err() {
+        failed.push(actor_id.to_owned());
+      }
+    }
+
+    for actor_id in &backup.blocked_communities {
+      let res: Result<(), LemmyError> = async {
+        let community = ObjectId::<Community>::new(actor_id.to_owned())
+          .dereference(context, &mut 0)
+          .await?;
+        if !blocked_communities.contains(&community.id) {
+          let block = BlockCommunity {
+            community_id: community.id,
+            block: true,
+            auth: data.auth.to_owned(),
+          };
+          block.perform(context, websocket_id).await?;
+        }
+        Ok(())
+      }
+      .await;
+      if res.is_err() {
+        failed.push(actor_id.to_owned());
+      }
+    }
+
+    for actor_id in &backup.blocked_users {
+      let res: Result<(), LemmyError> = async {
+        let person = ObjectId::<Person>::new(actor_id.to_owned())
+          .dereference(context, &mut 0)
+          .await?;
+        if !blocked_users.contains(&person.id) {
+          let block = BlockPerson {
+            person_id: person.id,
+            block: true,
+            auth: data.auth.to_owned(),
+          };
+          block.perform(context, websocket_id).await?;
+        }
+        Ok(())
+      }
+      .await;
+      if res.is_err() {
+        failed.push(actor_id.to_owned());
+      }
+    }
+
+    Ok(ImportUserSettingsResponse { failed })
   }
 }
 
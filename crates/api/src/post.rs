@@ -4,8 +4,11 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_downvotes_enabled,
+  check_mod_permission,
   check_person_block,
+  check_post_published,
   get_local_user_view_from_jwt,
+  get_post,
   is_mod_or_admin,
   mark_post_as_read,
   post::*,
@@ -21,9 +24,15 @@ use lemmy_apub::{
   },
   fetcher::post_or_comment::PostOrComment,
 };
-use lemmy_db_queries::{source::post::Post_, Crud, Likeable, Saveable};
-use lemmy_db_schema::source::{moderator::*, post::*};
-use lemmy_db_views::post_view::PostView;
+use lemmy_db_queries::{
+  source::post::{PostRevision_, Post_},
+  Crud,
+  Likeable,
+  Saveable,
+  SortType,
+};
+use lemmy_db_schema::source::{community::ModPermission, moderator::*, post::*};
+use lemmy_db_views::post_view::{PostQueryBuilder, PostView};
 use lemmy_utils::{request::fetch_site_metadata, ApiError, ConnectionId, LemmyError};
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperation};
 use std::convert::TryInto;
@@ -47,6 +56,7 @@ impl Perform for CreatePostLike {
     // Check for a community ban
     let post_id = data.post_id;
     let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_post_published(&post)?;
 
     check_community_ban(local_user_view.person.id, post.community_id, context.pool()).await?;
 
@@ -126,6 +136,7 @@ impl Perform for LockPost {
 
     let post_id = data.post_id;
     let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
@@ -135,10 +146,11 @@ impl Perform for LockPost {
     .await?;
 
     // Verify that only the mods can lock
-    is_mod_or_admin(
+    check_mod_permission(
       context.pool(),
       local_user_view.person.id,
       orig_post.community_id,
+      ModPermission::Remove,
     )
     .await?;
 
@@ -155,6 +167,7 @@ impl Perform for LockPost {
       mod_person_id: local_user_view.person.id,
       post_id: data.post_id,
       locked: Some(locked),
+      reason: data.reason.to_owned(),
     };
     blocking(context.pool(), move |conn| ModLockPost::create(conn, &form)).await??;
 
@@ -193,6 +206,7 @@ impl Perform for StickyPost {
 
     let post_id = data.post_id;
     let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
@@ -202,10 +216,11 @@ impl Perform for StickyPost {
     .await?;
 
     // Verify that only the mods can sticky
-    is_mod_or_admin(
+    check_mod_permission(
       context.pool(),
       local_user_view.person.id,
       orig_post.community_id,
+      ModPermission::Remove,
     )
     .await?;
 
@@ -222,6 +237,7 @@ impl Perform for StickyPost {
       mod_person_id: local_user_view.person.id,
       post_id: data.post_id,
       stickied: Some(stickied),
+      reason: data.reason.to_owned(),
     };
     blocking(context.pool(), move |conn| {
       ModStickyPost::create(conn, &form)
@@ -262,6 +278,9 @@ impl Perform for SavePost {
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
+    let post = get_post(data.post_id, context.pool()).await?;
+    check_post_published(&post)?;
+
     let post_saved_form = PostSavedForm {
       post_id: data.post_id,
       person_id: local_user_view.person.id,
@@ -293,6 +312,105 @@ impl Perform for SavePost {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for GetPostRevisions {
+  type Response = GetPostRevisionsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetPostRevisionsResponse, LemmyError> {
+    let data: &GetPostRevisions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let orig_post = get_post(post_id, context.pool()).await?;
+    if !Post::is_post_creator(local_user_view.person.id, orig_post.creator_id) {
+      is_mod_or_admin(
+        context.pool(),
+        local_user_view.person.id,
+        orig_post.community_id,
+      )
+      .await?;
+    }
+
+    let revisions = blocking(context.pool(), move |conn| {
+      PostRevision::list_for_post(conn, post_id)
+    })
+    .await??;
+
+    Ok(GetPostRevisionsResponse { revisions })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListScheduledPosts {
+  type Response = ListScheduledPostsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListScheduledPostsResponse, LemmyError> {
+    let data: &ListScheduledPosts = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let page = data.page;
+    let limit = data.limit;
+    let posts = blocking(context.pool(), move |conn| {
+      PostQueryBuilder::create(conn)
+        .sort(SortType::New)
+        .my_person_id(person_id)
+        .creator_id(person_id)
+        .scheduled_only(true)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await??;
+
+    Ok(ListScheduledPostsResponse { posts })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for CancelScheduledPost {
+  type Response = ListScheduledPostsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<ListScheduledPostsResponse, LemmyError> {
+    let data: &CancelScheduledPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let orig_post = get_post(post_id, context.pool()).await?;
+    if !Post::is_post_creator(local_user_view.person.id, orig_post.creator_id)
+      || orig_post.scheduled_publish_time.is_none()
+    {
+      return Err(ApiError::err("couldnt_find_post").into());
+    }
+
+    blocking(context.pool(), move |conn| Post::delete(conn, post_id))
+      .await?
+      .map_err(|_| ApiError::err("couldnt_update_post"))?;
+
+    let list = ListScheduledPosts {
+      page: None,
+      limit: None,
+      auth: data.auth.to_owned(),
+    };
+    list.perform(context, websocket_id).await
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for GetSiteMetadata {
   type Response = GetSiteMetadataResponse;
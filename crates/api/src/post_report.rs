@@ -3,8 +3,9 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_mod_permission,
+  check_post_published,
   get_local_user_view_from_jwt,
-  is_mod_or_admin,
   post::{
     CreatePostReport,
     ListPostReports,
@@ -14,7 +15,10 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_queries::Reportable;
-use lemmy_db_schema::source::post_report::{PostReport, PostReportForm};
+use lemmy_db_schema::source::{
+  community::ModPermission,
+  post_report::{PostReport, PostReportForm},
+};
 use lemmy_db_views::{
   post_report_view::{PostReportQueryBuilder, PostReportView},
   post_view::PostView,
@@ -51,6 +55,7 @@ impl Perform for CreatePostReport {
       PostView::read(conn, post_id, None)
     })
     .await??;
+    check_post_published(&post_view.post)?;
 
     check_community_ban(person_id, post_view.community.id, context.pool()).await?;
 
@@ -109,7 +114,13 @@ impl Perform for ResolvePostReport {
     .await??;
 
     let person_id = local_user_view.person.id;
-    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+    check_mod_permission(
+      context.pool(),
+      person_id,
+      report.community.id,
+      ModPermission::ResolveReports,
+    )
+    .await?;
 
     let resolved = data.resolved;
     let resolve_fun = move |conn: &'_ _| {
@@ -1,14 +1,19 @@
 use crate::Perform;
 use actix_web::web::Data;
 use anyhow::Context;
+use chrono::NaiveDateTime;
 use diesel::NotFound;
 use lemmy_api_common::{
   blocking,
   build_federated_instances,
+  check_mod_permission,
+  federated_instance_lists,
   get_local_user_view_from_jwt,
   get_local_user_view_from_jwt_opt,
   is_admin,
+  refresh_instance_lists,
   site::*,
+  slur_filter_regex,
 };
 use lemmy_apub::{
   build_actor_id_from_shortname,
@@ -16,49 +21,75 @@ use lemmy_apub::{
   EndpointType,
 };
 use lemmy_db_queries::{
+  diesel_option_overwrite,
   from_opt_str_to_opt_enum,
-  source::site::Site_,
+  source::{
+    activity_queue_job::ActivityQueueJob_,
+    instance::Instance_,
+    local_user::LocalUser_,
+    login_token::LoginToken_,
+    registration_application::RegistrationApplication_,
+    site::Site_,
+    slur_filter::SlurFilter_,
+  },
+  Blockable,
   Crud,
   DbPool,
   DeleteableOrRemoveable,
   ListingType,
+  ModlogActionType,
   SearchType,
+  SlurFilterMode,
   SortType,
 };
 use lemmy_db_schema::{
-  source::{moderator::*, site::Site},
+  naive_now,
+  source::{
+    activity_queue_job::ActivityQueueJob,
+    community::ModPermission,
+    instance::{Instance, InstanceForm},
+    instance_block::{InstanceBlock, InstanceBlockForm},
+    local_user::LocalUser,
+    login_token::LoginToken,
+    moderator::*,
+    registration_application::RegistrationApplication,
+    site::Site,
+    slur_filter::{SlurFilter, SlurFilterForm},
+  },
+  CommunityId,
   PersonId,
 };
 use lemmy_db_views::{
   comment_view::{CommentQueryBuilder, CommentView},
+  local_user_view::LocalUserView,
   post_view::{PostQueryBuilder, PostView},
+  registration_application_view::{
+    RegistrationApplicationQueryBuilder,
+    RegistrationApplicationView,
+  },
   site_view::SiteView,
 };
 use lemmy_db_views_actor::{
   community_view::{CommunityQueryBuilder, CommunityView},
   person_view::{PersonQueryBuilder, PersonViewSafe},
 };
-use lemmy_db_views_moderator::{
-  mod_add_community_view::ModAddCommunityView,
-  mod_add_view::ModAddView,
-  mod_ban_from_community_view::ModBanFromCommunityView,
-  mod_ban_view::ModBanView,
-  mod_lock_post_view::ModLockPostView,
-  mod_remove_comment_view::ModRemoveCommentView,
-  mod_remove_community_view::ModRemoveCommunityView,
-  mod_remove_post_view::ModRemovePostView,
-  mod_sticky_post_view::ModStickyPostView,
-  mod_transfer_community_view::ModTransferCommunityView,
-};
+use lemmy_db_views_moderator::modlog_combined_view::{ModlogCombinedQueryBuilder, ModlogView};
 use lemmy_utils::{
+  email::send_email,
   location_info,
   settings::structs::Settings,
+  utils::escape_html,
   version,
   ApiError,
   ConnectionId,
   LemmyError,
 };
 use lemmy_websocket::LemmyContext;
+use log::error;
+use url::Url;
+
+const MAX_SLUR_FILTERS: usize = 200;
+const MAX_SLUR_FILTER_LENGTH: usize = 200;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for GetModlog {
@@ -71,72 +102,29 @@ impl Perform for GetModlog {
   ) -> Result<GetModlogResponse, LemmyError> {
     let data: &GetModlog = self;
 
+    let action_type: ModlogActionType =
+      from_opt_str_to_opt_enum(&data.action_type).unwrap_or(ModlogActionType::All);
     let community_id = data.community_id;
     let mod_person_id = data.mod_person_id;
+    let other_person_id = data.other_person_id;
     let page = data.page;
     let limit = data.limit;
-    let removed_posts = blocking(context.pool(), move |conn| {
-      ModRemovePostView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let locked_posts = blocking(context.pool(), move |conn| {
-      ModLockPostView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let stickied_posts = blocking(context.pool(), move |conn| {
-      ModStickyPostView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let removed_comments = blocking(context.pool(), move |conn| {
-      ModRemoveCommentView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let banned_from_community = blocking(context.pool(), move |conn| {
-      ModBanFromCommunityView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let added_to_community = blocking(context.pool(), move |conn| {
-      ModAddCommunityView::list(conn, community_id, mod_person_id, page, limit)
-    })
-    .await??;
-
-    let transferred_to_community = blocking(context.pool(), move |conn| {
-      ModTransferCommunityView::list(conn, community_id, mod_person_id, page, limit)
+    let modlog = blocking(context.pool(), move |conn| {
+      // Page through the mod actions of all types at once, then read the details of each action
+      // from the view for its type
+      let entries = ModlogCombinedQueryBuilder::create(conn)
+        .action_type(action_type)
+        .community_id(community_id)
+        .mod_person_id(mod_person_id)
+        .other_person_id(other_person_id)
+        .page(page)
+        .limit(limit)
+        .list()?;
+      ModlogView::for_entries(conn, &entries)
     })
     .await??;
 
-    // These arrays are only for the full modlog, when a community isn't given
-    let (removed_communities, banned, added) = if data.community_id.is_none() {
-      blocking(context.pool(), move |conn| {
-        Ok((
-          ModRemoveCommunityView::list(conn, mod_person_id, page, limit)?,
-          ModBanView::list(conn, mod_person_id, page, limit)?,
-          ModAddView::list(conn, mod_person_id, page, limit)?,
-        )) as Result<_, LemmyError>
-      })
-      .await??
-    } else {
-      (Vec::new(), Vec::new(), Vec::new())
-    };
-
-    // Return the jwt
-    Ok(GetModlogResponse {
-      removed_posts,
-      locked_posts,
-      stickied_posts,
-      removed_comments,
-      removed_communities,
-      banned_from_community,
-      banned,
-      added_to_community,
-      added,
-      transferred_to_community,
-    })
+    Ok(GetModlogResponse { modlog })
   }
 }
 
@@ -169,8 +157,6 @@ impl Perform for Search {
     let mut communities = Vec::new();
     let mut users = Vec::new();
 
-    // TODO no clean / non-nsfw searching rn
-
     let q = data.q.to_owned();
     let page = data.page;
     let limit = data.limit;
@@ -184,6 +170,11 @@ impl Perform for Search {
       .map(|t| build_actor_id_from_shortname(EndpointType::Community, t, &context.settings()).ok())
       .unwrap_or(None);
     let creator_id = data.creator_id;
+    let instance = data.instance.to_owned();
+    let published_after = search_date(data.published_after)?;
+    let published_before = search_date(data.published_before)?;
+    let has_url = data.has_url;
+    let has_body = data.has_body;
     match search_type {
       SearchType::Posts => {
         posts = blocking(context.pool(), move |conn| {
@@ -198,6 +189,11 @@ impl Perform for Search {
             .creator_id(creator_id)
             .my_person_id(person_id)
             .search_term(q)
+            .instance(instance)
+            .published_after(published_after)
+            .published_before(published_before)
+            .has_url(has_url)
+            .has_body(has_body)
             .page(page)
             .limit(limit)
             .list()
@@ -215,6 +211,11 @@ impl Perform for Search {
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
             .my_person_id(person_id)
+            .instance(instance)
+            .published_after(published_after)
+            .published_before(published_before)
+            .has_url(has_url)
+            .has_body(has_body)
             .page(page)
             .limit(limit)
             .list()
@@ -226,7 +227,9 @@ impl Perform for Search {
           CommunityQueryBuilder::create(conn)
             .sort(sort)
             .listing_type(listing_type)
+            .show_nsfw(show_nsfw)
             .search_term(q)
+            .instance(instance)
             .my_person_id(person_id)
             .page(page)
             .limit(limit)
@@ -239,6 +242,7 @@ impl Perform for Search {
           PersonQueryBuilder::create(conn)
             .sort(sort)
             .search_term(q)
+            .instance(instance)
             .page(page)
             .limit(limit)
             .list()
@@ -263,6 +267,11 @@ impl Perform for Search {
             .creator_id(creator_id)
             .my_person_id(person_id)
             .search_term(q)
+            .instance(instance)
+            .published_after(published_after)
+            .published_before(published_before)
+            .has_url(has_url)
+            .has_body(has_body)
             .page(page)
             .limit(limit)
             .list()
@@ -270,6 +279,7 @@ impl Perform for Search {
         .await??;
 
         let q = data.q.to_owned();
+        let instance = data.instance.to_owned();
         let community_actor_id = community_actor_id.to_owned();
 
         comments = blocking(context.pool(), move |conn| {
@@ -282,6 +292,11 @@ impl Perform for Search {
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
             .my_person_id(person_id)
+            .instance(instance)
+            .published_after(published_after)
+            .published_before(published_before)
+            .has_url(has_url)
+            .has_body(has_body)
             .page(page)
             .limit(limit)
             .list()
@@ -289,6 +304,7 @@ impl Perform for Search {
         .await??;
 
         let q = data.q.to_owned();
+        let instance = data.instance.to_owned();
 
         communities = if community_or_creator_included {
           vec![]
@@ -297,7 +313,9 @@ impl Perform for Search {
             CommunityQueryBuilder::create(conn)
               .sort(sort)
               .listing_type(listing_type)
+              .show_nsfw(show_nsfw)
               .search_term(q)
+              .instance(instance)
               .my_person_id(person_id)
               .page(page)
               .limit(limit)
@@ -307,6 +325,7 @@ impl Perform for Search {
         };
 
         let q = data.q.to_owned();
+        let instance = data.instance.to_owned();
 
         users = if community_or_creator_included {
           vec![]
@@ -315,6 +334,7 @@ impl Perform for Search {
             PersonQueryBuilder::create(conn)
               .sort(sort)
               .search_term(q)
+              .instance(instance)
               .page(page)
               .limit(limit)
               .list()
@@ -335,6 +355,11 @@ impl Perform for Search {
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
             .url_search(q)
+            .instance(instance)
+            .published_after(published_after)
+            .published_before(published_before)
+            .has_url(has_url)
+            .has_body(has_body)
             .page(page)
             .limit(limit)
             .list()
@@ -376,6 +401,14 @@ impl Perform for Search {
   }
 }
 
+/// Converts an optional unix timestamp from a search filter, rejecting out of range values.
+fn search_date(time: Option<i64>) -> Result<Option<NaiveDateTime>, LemmyError> {
+  time
+    .map(|t| NaiveDateTime::from_timestamp_opt(t, 0).ok_or_else(|| ApiError::err("invalid_date")))
+    .transpose()
+    .map_err(Into::into)
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for ResolveObject {
   type Response = ResolveObjectResponse;
@@ -468,6 +501,7 @@ impl Perform for TransferSite {
       mod_person_id: local_user_view.person.id,
       other_person_id: data.person_id,
       removed: Some(false),
+      reason: None,
     };
 
     blocking(context.pool(), move |conn| ModAdd::create(conn, &form)).await??;
@@ -540,6 +574,12 @@ impl Perform for SaveSiteConfig {
     // Only let admins read this
     is_admin(&local_user_view)?;
 
+    // Reject invalid configs before anything is written, the running settings stay untouched
+    Settings::parse_config(&data.config_hjson).map_err(|e| {
+      error!("Invalid site config: {}", e);
+      ApiError::err("invalid_site_config")
+    })?;
+
     // Make sure docker doesn't have :ro at the end of the volume, so its not a read-only filesystem
     let config_hjson = Settings::save_config_file(&data.config_hjson)
       .map_err(|_| ApiError::err("couldnt_update_site"))?;
@@ -547,3 +587,441 @@ impl Perform for SaveSiteConfig {
     Ok(GetSiteConfigResponse { config_hjson })
   }
 }
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetFederationQueue {
+  type Response = GetFederationQueueResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetFederationQueueResponse, LemmyError> {
+    let data: &GetFederationQueue = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Only let admins read this
+    is_admin(&local_user_view)?;
+
+    let pending = blocking(context.pool(), ActivityQueueJob::count_pending).await??;
+    let running = blocking(context.pool(), ActivityQueueJob::count_running).await??;
+    let retrying = blocking(context.pool(), ActivityQueueJob::count_retrying).await??;
+
+    Ok(GetFederationQueueResponse {
+      pending,
+      running,
+      retrying,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for AddInstance {
+  type Response = InstanceResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<InstanceResponse, LemmyError> {
+    let data: &AddInstance = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    is_admin(&local_user_view)?;
+
+    let domain = check_remote_instance_domain(&data.domain, &context.settings())?;
+
+    let allowlist_enabled = federated_instance_lists().allowed.is_some();
+    if !data.blocked && !allowlist_enabled && !data.enable_allowlist.unwrap_or(false) {
+      return Err(ApiError::err("allowlist_not_enabled").into());
+    }
+
+    let form = InstanceForm {
+      domain,
+      blocked: data.blocked,
+      reason: Some(data.reason.to_owned()),
+      updated: Some(Some(naive_now())),
+    };
+    let instance = blocking(context.pool(), move |conn| Instance::upsert(conn, &form))
+      .await?
+      .map_err(|_| ApiError::err("couldnt_update_instance"))?;
+
+    refresh_instance_lists(context.pool()).await?;
+
+    Ok(InstanceResponse { instance })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RemoveInstance {
+  type Response = InstanceResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<InstanceResponse, LemmyError> {
+    let data: &RemoveInstance = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    is_admin(&local_user_view)?;
+
+    let domain = data.domain.trim().to_lowercase();
+    let instance = blocking(context.pool(), move |conn| {
+      Instance::delete_from_domain(conn, &domain)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_find_instance"))?;
+
+    refresh_instance_lists(context.pool()).await?;
+
+    Ok(InstanceResponse { instance })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListInstances {
+  type Response = ListInstancesResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListInstancesResponse, LemmyError> {
+    let data: &ListInstances = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Only let admins read this, as it includes the reasons
+    is_admin(&local_user_view)?;
+
+    let instances = blocking(context.pool(), Instance::list).await??;
+    let allowlist_enabled = federated_instance_lists().allowed.is_some();
+
+    Ok(ListInstancesResponse {
+      instances,
+      allowlist_enabled,
+    })
+  }
+}
+
+/// Normalizes the domain of a remote instance, and makes sure that it is valid.
+fn check_remote_instance_domain(domain: &str, settings: &Settings) -> Result<String, LemmyError> {
+  let domain = domain.trim().to_lowercase();
+  let parsed_domain = Url::parse(&format!("https://{}", domain))
+    .ok()
+    .and_then(|u| u.domain().map(ToString::to_string));
+  if parsed_domain.as_ref() != Some(&domain) {
+    return Err(ApiError::err("invalid_instance_domain").into());
+  }
+  if domain == settings.get_hostname_without_port()? {
+    return Err(ApiError::err("cant_use_local_instance").into());
+  }
+  Ok(domain)
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for BlockInstance {
+  type Response = BlockInstanceResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<BlockInstanceResponse, LemmyError> {
+    let data: &BlockInstance = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let domain = check_remote_instance_domain(&data.domain, &context.settings())?;
+    let instance_block_form = InstanceBlockForm {
+      person_id: local_user_view.person.id,
+      domain: domain.to_owned(),
+    };
+
+    if data.block {
+      let block = move |conn: &'_ _| InstanceBlock::block(conn, &instance_block_form);
+      if blocking(context.pool(), block).await?.is_err() {
+        return Err(ApiError::err("instance_block_already_exists").into());
+      }
+    } else {
+      let unblock = move |conn: &'_ _| InstanceBlock::unblock(conn, &instance_block_form);
+      if blocking(context.pool(), unblock).await?.is_err() {
+        return Err(ApiError::err("instance_block_already_exists").into());
+      }
+    }
+
+    Ok(BlockInstanceResponse {
+      domain,
+      blocked: data.block,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListRegistrationApplications {
+  type Response = ListRegistrationApplicationsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListRegistrationApplicationsResponse, LemmyError> {
+    let data: &ListRegistrationApplications = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    is_admin(&local_user_view)?;
+
+    let unread_only = data.unread_only;
+    let page = data.page;
+    let limit = data.limit;
+    let registration_applications = blocking(context.pool(), move |conn| {
+      RegistrationApplicationQueryBuilder::create(conn)
+        .unread_only(unread_only)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await??;
+
+    Ok(ListRegistrationApplicationsResponse {
+      registration_applications,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ApproveRegistrationApplication {
+  type Response = RegistrationApplicationResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<RegistrationApplicationResponse, LemmyError> {
+    let data: &ApproveRegistrationApplication = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    is_admin(&local_user_view)?;
+
+    let deny_reason = if data.approve {
+      None
+    } else {
+      diesel_option_overwrite(&data.deny_reason).flatten()
+    };
+
+    // Store the decision
+    let application_id = data.id;
+    let admin_id = local_user_view.person.id;
+    let reason = deny_reason.clone();
+    let application = blocking(context.pool(), move |conn| {
+      RegistrationApplication::decide(conn, application_id, admin_id, reason)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_registration_application"))?;
+
+    let local_user_id = application.local_user_id;
+    let approve = data.approve;
+    blocking(context.pool(), move |conn| {
+      LocalUser::set_accepted_application(conn, local_user_id, approve)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_user"))?;
+
+    // A denied user can't keep using the sessions from before
+    if !approve {
+      blocking(context.pool(), move |conn| {
+        LoginToken::revoke_all(conn, local_user_id)
+      })
+      .await??;
+    }
+
+    let registration_application = blocking(context.pool(), move |conn| {
+      RegistrationApplicationView::read(conn, application_id)
+    })
+    .await??;
+
+    send_application_decision_email(
+      &registration_application,
+      approve,
+      &deny_reason,
+      &context.settings(),
+    );
+
+    Ok(RegistrationApplicationResponse {
+      registration_application,
+    })
+  }
+}
+
+/// Lets the applicant know about the decision, if they gave an email address
+fn send_application_decision_email(
+  view: &RegistrationApplicationView,
+  approve: bool,
+  deny_reason: &Option<String>,
+  settings: &Settings,
+) {
+  let email = match &view.creator_local_user.email {
+    Some(email) => email,
+    None => return,
+  };
+
+  // TODO no i18n support here.
+  let name = &view.creator.name;
+  let (subject, html) = if approve {
+    (
+      format!("Registration approved for {}", name),
+      format!(
+        "<h1>Your registration application for {} was approved</h1><br><a href={}/login>Click here to log in</a>",
+        settings.hostname,
+        settings.get_protocol_and_hostname()
+      ),
+    )
+  } else {
+    (
+      format!("Registration denied for {}", name),
+      format!(
+        "<h1>Your registration application for {} was denied</h1><br><div>{}</div>",
+        settings.hostname,
+        escape_html(deny_reason.as_deref().unwrap_or(""))
+      ),
+    )
+  };
+
+  if let Err(e) = send_email(&subject, email, name, &html, settings) {
+    error!("{}", e);
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetUnreadRegistrationApplicationCount {
+  type Response = GetUnreadRegistrationApplicationCountResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetUnreadRegistrationApplicationCountResponse, LemmyError> {
+    let data: &GetUnreadRegistrationApplicationCount = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    is_admin(&local_user_view)?;
+
+    let registration_applications = blocking(context.pool(), move |conn| {
+      RegistrationApplicationView::get_unread_count(conn)
+    })
+    .await??;
+
+    Ok(GetUnreadRegistrationApplicationCountResponse {
+      registration_applications,
+    })
+  }
+}
+
+/// Site-wide slur filters can only be managed by admins, those of a community by mods who may edit
+/// the community.
+async fn check_slur_filter_permission(
+  local_user_view: &LocalUserView,
+  community_id: Option<CommunityId>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  match community_id {
+    Some(community_id) => {
+      check_mod_permission(
+        pool,
+        local_user_view.person.id,
+        community_id,
+        ModPermission::EditCommunity,
+      )
+      .await
+    }
+    None => is_admin(local_user_view),
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetSlurFilters {
+  type Response = SlurFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<SlurFiltersResponse, LemmyError> {
+    let data: &GetSlurFilters = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let community_id = data.community_id;
+    check_slur_filter_permission(&local_user_view, community_id, context.pool()).await?;
+
+    let slur_filters = blocking(context.pool(), move |conn| {
+      SlurFilter::list(conn, community_id)
+    })
+    .await??;
+
+    Ok(SlurFiltersResponse { slur_filters })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for SaveSlurFilters {
+  type Response = SlurFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<SlurFiltersResponse, LemmyError> {
+    let data: &SaveSlurFilters = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let community_id = data.community_id;
+    check_slur_filter_permission(&local_user_view, community_id, context.pool()).await?;
+
+    if data.slur_filters.len() > MAX_SLUR_FILTERS {
+      return Err(ApiError::err("too_many_slur_filters").into());
+    }
+
+    let mut forms = Vec::with_capacity(data.slur_filters.len());
+    for filter in &data.slur_filters {
+      let phrase = filter.phrase.trim().to_string();
+      if phrase.is_empty() || phrase.chars().count() > MAX_SLUR_FILTER_LENGTH {
+        return Err(ApiError::err("invalid_slur_filter").into());
+      }
+      let regex = filter.regex.unwrap_or(false);
+      // Unlike keyword filters, these are matched by lemmy and not by postgres
+      if slur_filter_regex(&phrase, regex).is_err() {
+        return Err(ApiError::err("invalid_slur_filter_regex").into());
+      }
+      let mode = filter.mode.unwrap_or(SlurFilterMode::Reject as i16);
+      if mode != SlurFilterMode::Reject as i16
+        && mode != SlurFilterMode::Replace as i16
+        && mode != SlurFilterMode::Flag as i16
+      {
+        return Err(ApiError::err("invalid_slur_filter").into());
+      }
+      forms.push(SlurFilterForm {
+        community_id,
+        phrase,
+        regex,
+        mode,
+      });
+    }
+
+    let slur_filters = blocking(context.pool(), move |conn| {
+      SlurFilter::replace(conn, community_id, &forms)
+    })
+    .await?
+    .map_err(|_| ApiError::err("couldnt_update_slur_filters"))?;
+
+    Ok(SlurFiltersResponse { slur_filters })
+  }
+}
@@ -1,4 +1,11 @@
-use lemmy_db_schema::{CommentId, CommentReportId, CommunityId, LocalUserId, PostId};
+use lemmy_db_schema::{
+  source::comment::CommentRevision,
+  CommentId,
+  CommentReportId,
+  CommunityId,
+  LocalUserId,
+  PostId,
+};
 use lemmy_db_views::{comment_report_view::CommentReportView, comment_view::CommentView};
 use serde::{Deserialize, Serialize};
 
@@ -113,3 +120,15 @@ pub struct ListCommentReports {
 pub struct ListCommentReportsResponse {
   pub comment_reports: Vec<CommentReportView>,
 }
+
+/// Earlier versions of a comment, only visible to its creator, mods and admins
+#[derive(Deserialize)]
+pub struct GetCommentRevisions {
+  pub comment_id: CommentId,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct GetCommentRevisionsResponse {
+  pub revisions: Vec<CommentRevision>,
+}
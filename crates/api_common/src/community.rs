@@ -1,4 +1,4 @@
-use lemmy_db_schema::{CommunityId, PersonId};
+use lemmy_db_schema::{source::community::ModPermissions, CommunityId, PersonId};
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
   community_view::CommunityView,
@@ -73,6 +73,13 @@ pub struct AddModToCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub added: bool,
+  /// Limits what the mod is allowed to do. If the person is already a mod, only their permissions
+  /// are changed. New mods get the permissions of the mod who added them by default. Only admins
+  /// and the top mod can change the permissions of all mods, other mods only those of mods who
+  /// were added after them, and they can't grant permissions which they don't have themselves.
+  /// Permissions aren't federated, so they only take effect on the instance of the community.
+  pub permissions: Option<ModPermissions>,
+  pub reason: Option<String>,
   pub auth: String,
 }
 
@@ -132,5 +139,6 @@ pub struct BlockCommunityResponse {
 pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
+  pub reason: Option<String>,
   pub auth: String,
 }
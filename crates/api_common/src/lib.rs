@@ -5,24 +5,50 @@ pub mod post;
 pub mod site;
 pub mod websocket;
 
-use crate::site::FederatedInstances;
+#[macro_use]
+extern crate lazy_static;
+
+use crate::{person::LoginResponse, site::FederatedInstances};
+use chrono::{Duration, NaiveDateTime};
 use diesel::PgConnection;
 use lemmy_db_queries::{
-  source::{community::Community_, person_block::PersonBlock_, site::Site_},
+  source::{
+    comment::Comment_,
+    community::Community_,
+    email_verification::EmailVerification_,
+    instance::Instance_,
+    login_token::LoginToken_,
+    person::Person_,
+    person_block::PersonBlock_,
+    post::Post_,
+    site::Site_,
+    slur_filter::SlurFilter_,
+    totp_2fa_recovery_code::Totp2faRecoveryCode_,
+  },
   Crud,
   DbPool,
   Readable,
+  Reportable,
+  SlurFilterMode,
 };
 use lemmy_db_schema::{
+  naive_now,
   source::{
     comment::Comment,
-    community::Community,
+    comment_report::{CommentReport, CommentReportForm},
+    community::{Community, ModPermission},
+    email_verification::EmailVerification,
+    instance::Instance,
+    login_token::LoginToken,
     person::Person,
     person_block::PersonBlock,
     person_mention::{PersonMention, PersonMentionForm},
     post::{Post, PostRead, PostReadForm},
+    post_report::{PostReport, PostReportForm},
     secret::Secret,
     site::Site,
+    slur_filter::SlurFilter,
+    totp_2fa_recovery_code::Totp2faRecoveryCode,
   },
   CommunityId,
   LocalUserId,
@@ -38,13 +64,65 @@ use lemmy_utils::{
   claims::Claims,
   email::send_email,
   settings::structs::{FederationConfig, Settings},
-  utils::MentionData,
+  utils::{check_slurs, generate_random_string, naive_from_unix, remove_slurs, MentionData},
   ApiError,
+  ClientInfo,
   LemmyError,
 };
 use log::error;
+use rand::{thread_rng, Rng};
+use regex::{Regex, RegexBuilder};
+use std::{
+  collections::HashSet,
+  sync::{Arc, RwLock},
+};
+use totp_rs::{Algorithm, Secret as TotpSecret, TOTP};
 use url::Url;
 
+lazy_static! {
+  /// The federation allowlist and blocklist. These are checked for every incoming and outgoing
+  /// activity, so they are kept in memory and only rebuilt when the entries in the database or the
+  /// config file change.
+  static ref INSTANCE_LISTS: RwLock<InstanceLists> = RwLock::new(InstanceLists::default());
+}
+
+/// The allowed and blocked instances, combining the entries from the config file with those which
+/// admins added through the API. A list is `None` if it is empty in both places.
+///
+/// Note that this means a single allowed instance in the database turns on the allowlist, if the
+/// config file has none.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct FederatedInstanceLists {
+  pub allowed: Option<HashSet<String>>,
+  pub blocked: Option<HashSet<String>>,
+}
+
+#[derive(Default)]
+struct InstanceLists {
+  /// The entries from the database, kept to rebuild `combined` when the config file is reloaded
+  database: DatabaseInstanceLists,
+  combined: Arc<FederatedInstanceLists>,
+}
+
+/// Federation allowlist and blocklist entries which were added by admins
+#[derive(Clone, Default, Debug, PartialEq)]
+struct DatabaseInstanceLists {
+  allowed: Vec<String>,
+  blocked: Vec<String>,
+}
+
+impl DatabaseInstanceLists {
+  fn read(conn: &PgConnection) -> Result<Self, diesel::result::Error> {
+    let instances = Instance::list(conn)?;
+    let (blocked, allowed): (Vec<Instance>, Vec<Instance>) =
+      instances.into_iter().partition(|i| i.blocked);
+    Ok(DatabaseInstanceLists {
+      allowed: allowed.into_iter().map(|i| i.domain).collect(),
+      blocked: blocked.into_iter().map(|i| i.domain).collect(),
+    })
+  }
+}
+
 pub async fn blocking<F, T>(pool: &DbPool, f: F) -> Result<T, LemmyError>
 where
   F: FnOnce(&diesel::PgConnection) -> T + Send + 'static,
@@ -186,7 +264,10 @@ pub fn send_email_to_user(
   comment_content: &str,
   settings: &Settings,
 ) {
-  if local_user_view.person.banned || !local_user_view.local_user.send_notifications_to_email {
+  if local_user_view.person.banned
+    || !local_user_view.local_user.send_notifications_to_email
+    || !local_user_view.local_user.email_verified
+  {
     return;
   }
 
@@ -230,6 +311,22 @@ pub async fn is_mod_or_admin(
   Ok(())
 }
 
+pub async fn check_mod_permission(
+  pool: &DbPool,
+  person_id: PersonId,
+  community_id: CommunityId,
+  permission: ModPermission,
+) -> Result<(), LemmyError> {
+  let has_permission = blocking(pool, move |conn| {
+    CommunityView::has_mod_permission(conn, person_id, community_id, permission)
+  })
+  .await??;
+  if !has_permission {
+    return Err(ApiError::err("missing_mod_permission").into());
+  }
+  Ok(())
+}
+
 pub fn is_admin(local_user_view: &LocalUserView) -> Result<(), LemmyError> {
   if !local_user_view.person.admin {
     return Err(ApiError::err("not_an_admin").into());
@@ -243,6 +340,15 @@ pub async fn get_post(post_id: PostId, pool: &DbPool) -> Result<Post, LemmyError
     .map_err(|_| ApiError::err("couldnt_find_post").into())
 }
 
+/// Scheduled posts are only visible to their creator, who can edit or cancel them. Everything else
+/// has to wait until the post is published.
+pub fn check_post_published(post: &Post) -> Result<(), LemmyError> {
+  if post.scheduled_publish_time.is_some() {
+    return Err(ApiError::err("couldnt_find_post").into());
+  }
+  Ok(())
+}
+
 pub async fn mark_post_as_read(
   person_id: PersonId,
   post_id: PostId,
@@ -279,6 +385,7 @@ pub async fn get_local_user_view_from_jwt(
   }
 
   check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
+  check_login_token(&claims, pool).await?;
 
   Ok(local_user_view)
 }
@@ -296,6 +403,176 @@ pub fn check_validator_time(
   }
 }
 
+/// Checks that the login session of the token wasn't revoked, and hasn't expired.
+async fn check_login_token(claims: &Claims, pool: &DbPool) -> Result<(), LemmyError> {
+  let login_token_id = claims.sid;
+  let local_user_id = LocalUserId(claims.sub);
+  let inactive_after = Duration::days(Settings::get().session.inactive_days.into());
+  blocking(pool, move |conn| {
+    LoginToken::read_active(conn, login_token_id, local_user_id, inactive_after)
+  })
+  .await?
+  .map_err(|_| ApiError::err("not_logged_in"))?;
+  Ok(())
+}
+
+/// Starts a new login session for the user, and returns its access and refresh tokens.
+pub async fn create_login_session(
+  local_user_id: LocalUserId,
+  client_info: ClientInfo,
+  pool: &DbPool,
+  secret: &Secret,
+  settings: &Settings,
+) -> Result<LoginResponse, LemmyError> {
+  let refresh_token = generate_random_string();
+  let token = refresh_token.to_owned();
+  let login_token = blocking(pool, move |conn| {
+    LoginToken::create_session(
+      conn,
+      local_user_id,
+      &token,
+      client_info.ip,
+      client_info.user_agent,
+    )
+  })
+  .await??;
+
+  Ok(LoginResponse {
+    jwt: Some(Claims::jwt(
+      local_user_id.0,
+      login_token.id,
+      &secret.jwt_secret,
+      settings,
+    )?),
+    refresh_token: Some(refresh_token),
+    verify_email_sent: false,
+    registration_created: false,
+  })
+}
+
+/// Sends a link to verify the new email address of the user. The address is only saved once it
+/// was verified, except on registration.
+pub async fn send_verification_email(
+  local_user_id: LocalUserId,
+  new_email: &str,
+  username: &str,
+  pool: &DbPool,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  let token = generate_random_string();
+  let token2 = token.clone();
+  let email = new_email.to_owned();
+  blocking(pool, move |conn| {
+    EmailVerification::create_token(conn, local_user_id, &email, &token2)
+  })
+  .await??;
+
+  // TODO no i18n support here.
+  let subject = &format!("Verify your email address for {}", settings.hostname);
+  let html = &format!(
+    "<h1>Email Verification for {}</h1><br><a href={}/verify_email/{}>Click here to verify your email address</a>",
+    username,
+    settings.get_protocol_and_hostname(),
+    &token
+  );
+  send_email(subject, new_email, username, html, settings).map_err(|e| ApiError::err(&e))?;
+
+  Ok(())
+}
+
+/// Permanently deletes all posts and comments of the person, and clears their profile. Used both
+/// for local accounts and for remote accounts which were deleted on their instance.
+pub async fn delete_user_account(person_id: PersonId, pool: &DbPool) -> Result<(), LemmyError> {
+  let permadelete = move |conn: &'_ _| Comment::permadelete_for_creator(conn, person_id);
+  if blocking(pool, permadelete).await?.is_err() {
+    return Err(ApiError::err("couldnt_update_comment").into());
+  }
+
+  let permadelete = move |conn: &'_ _| Post::permadelete_for_creator(conn, person_id);
+  if blocking(pool, permadelete).await?.is_err() {
+    return Err(ApiError::err("couldnt_update_post").into());
+  }
+
+  blocking(pool, move |conn| Person::delete_account(conn, person_id)).await??;
+  Ok(())
+}
+
+/// Generates a new random secret for two-factor authentication, encoded as base32
+pub fn generate_totp_2fa_secret() -> String {
+  let secret: [u8; 20] = thread_rng().gen();
+  TotpSecret::Raw(secret.to_vec()).to_encoded().to_string()
+}
+
+fn build_totp_2fa(secret: &str, username: &str, settings: &Settings) -> Result<TOTP, LemmyError> {
+  let secret = TotpSecret::Encoded(secret.to_string())
+    .to_bytes()
+    .map_err(|_| ApiError::err("couldnt_parse_totp_secret"))?;
+  // The issuer can't contain a colon, so the port is left out
+  let issuer = settings.get_hostname_without_port()?;
+  TOTP::new(
+    Algorithm::SHA1,
+    6,
+    1,
+    30,
+    secret,
+    Some(issuer),
+    username.to_string(),
+  )
+  .map_err(|_| ApiError::err("couldnt_generate_totp").into())
+}
+
+/// The `otpauth://` URL which is added to the authenticator app, usually as QR code
+pub fn totp_2fa_url(
+  secret: &str,
+  username: &str,
+  settings: &Settings,
+) -> Result<String, LemmyError> {
+  Ok(build_totp_2fa(secret, username, settings)?.get_url())
+}
+
+/// Checks the current code from the authenticator app
+pub fn check_totp_2fa_token(
+  secret: &str,
+  token: &str,
+  username: &str,
+  settings: &Settings,
+) -> Result<bool, LemmyError> {
+  Ok(build_totp_2fa(secret, username, settings)?.check_current(token.trim())?)
+}
+
+/// Makes sure that a user with two-factor authentication passed a valid code, either from the
+/// authenticator app or one of the recovery codes. Recovery codes can only be used once.
+pub async fn check_totp_2fa(
+  local_user_view: &LocalUserView,
+  token: &Option<String>,
+  pool: &DbPool,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  let secret = match &local_user_view.local_user.totp_2fa_secret {
+    Some(secret) if local_user_view.local_user.totp_2fa_enabled => secret,
+    _ => return Ok(()),
+  };
+  let token = token
+    .to_owned()
+    .ok_or_else(|| ApiError::err("missing_totp_token"))?;
+
+  if check_totp_2fa_token(secret, &token, &local_user_view.person.name, settings)? {
+    return Ok(());
+  }
+
+  let local_user_id = local_user_view.local_user.id;
+  let recovery_code = token.trim().to_string();
+  let used_recovery_code = blocking(pool, move |conn| {
+    Totp2faRecoveryCode::use_code(conn, local_user_id, &recovery_code)
+  })
+  .await??;
+  if used_recovery_code {
+    Ok(())
+  } else {
+    Err(ApiError::err("incorrect_totp_token").into())
+  }
+}
+
 pub async fn get_local_user_view_from_jwt_opt(
   jwt: &Option<String>,
   pool: &DbPool,
@@ -326,6 +603,7 @@ pub async fn get_local_user_settings_view_from_jwt(
   }
 
   check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
+  check_login_token(&claims, pool).await?;
 
   Ok(local_user_view)
 }
@@ -380,20 +658,77 @@ pub async fn check_downvotes_enabled(score: i16, pool: &DbPool) -> Result<(), Le
   Ok(())
 }
 
+/// Reloads the allowed and blocked instances from the database. Needs to be called on startup,
+/// and after each change to the `instance` table.
+pub async fn refresh_instance_lists(pool: &DbPool) -> Result<(), LemmyError> {
+  let database = blocking(pool, DatabaseInstanceLists::read).await??;
+  let combined = combine_instance_lists(&Settings::get().federation, &database);
+  let mut lists = INSTANCE_LISTS.write().expect("write instance lists");
+  lists.database = database;
+  lists.combined = Arc::new(combined);
+  Ok(())
+}
+
+/// Combines the instance lists again with those from the config file. Needs to be called after
+/// the config file was reloaded.
+pub fn rebuild_instance_lists() {
+  let federation = Settings::get().federation;
+  let mut lists = INSTANCE_LISTS.write().expect("write instance lists");
+  lists.combined = Arc::new(combine_instance_lists(&federation, &lists.database));
+}
+
+/// Returns the federation allowlist and blocklist.
+pub fn federated_instance_lists() -> Arc<FederatedInstanceLists> {
+  INSTANCE_LISTS
+    .read()
+    .expect("read instance lists")
+    .combined
+    .clone()
+}
+
+fn combine_instance_lists(
+  federation: &FederationConfig,
+  lists: &DatabaseInstanceLists,
+) -> FederatedInstanceLists {
+  let combine = |config: &Option<Vec<String>>, db: &[String]| {
+    if config.is_none() && db.is_empty() {
+      return None;
+    }
+    let mut combined = config
+      .iter()
+      .flatten()
+      .cloned()
+      .collect::<HashSet<String>>();
+    combined.extend(db.iter().cloned());
+    Some(combined)
+  };
+  FederatedInstanceLists {
+    allowed: combine(&federation.allowed_instances, &lists.allowed),
+    blocked: combine(&federation.blocked_instances, &lists.blocked),
+  }
+}
+
 pub async fn build_federated_instances(
   pool: &DbPool,
   federation_config: &FederationConfig,
   hostname: &str,
 ) -> Result<Option<FederatedInstances>, LemmyError> {
-  let federation = federation_config.to_owned();
-  if federation.enabled {
+  if federation_config.enabled {
     let distinct_communities = blocking(pool, move |conn| {
       Community::distinct_federated_communities(conn)
     })
     .await??;
 
-    let allowed = federation.allowed_instances;
-    let blocked = federation.blocked_instances;
+    let lists = federated_instance_lists();
+    let sorted = |list: &Option<HashSet<String>>| {
+      list.as_ref().map(|l| {
+        let mut l = l.iter().cloned().collect::<Vec<String>>();
+        l.sort_unstable();
+        l
+      })
+    };
+    let allowed = sorted(&lists.allowed);
+    let blocked = sorted(&lists.blocked);
 
     let mut linked = distinct_communities
       .iter()
@@ -448,3 +783,325 @@ pub fn honeypot_check(honeypot: &Option<String>) -> Result<(), LemmyError> {
     Ok(())
   }
 }
+
+/// Converts the expiry time of a ban from a unix timestamp, and makes sure that it is in the future
+pub fn check_expire_time(expires: Option<i64>) -> Result<Option<NaiveDateTime>, LemmyError> {
+  match expires.map(naive_from_unix) {
+    Some(e) if e <= naive_now() => Err(ApiError::err("ban_expires_in_past").into()),
+    e => Ok(e),
+  }
+}
+
+/// Builds the regex which a slur filter is matched with, this is also used to validate the
+/// filters when saving them.
+pub fn slur_filter_regex(phrase: &str, regex: bool) -> Result<Regex, regex::Error> {
+  let pattern = if regex {
+    phrase.to_owned()
+  } else {
+    regex::escape(phrase)
+  };
+  RegexBuilder::new(&pattern).case_insensitive(true).build()
+}
+
+/// The slur filters from the database which apply to posts and comments in a community. These
+/// come on top of the built-in slur regex.
+#[derive(Default)]
+pub struct SlurFilters {
+  reject: Vec<Regex>,
+  replace: Vec<Regex>,
+  flag: Vec<Regex>,
+}
+
+impl SlurFilters {
+  pub fn new(filters: &[SlurFilter]) -> Self {
+    let mut slur_filters = SlurFilters::default();
+    for filter in filters {
+      // The regexes are validated when saving, so this only fails for filters that were
+      // inserted into the database by hand. Only that filter is skipped then.
+      let regex = match slur_filter_regex(&filter.phrase, filter.regex) {
+        Ok(regex) => regex,
+        Err(e) => {
+          error!("Invalid slur filter {}: {}", filter.id, e);
+          continue;
+        }
+      };
+      if filter.mode == SlurFilterMode::Reject as i16 {
+        slur_filters.reject.push(regex);
+      } else if filter.mode == SlurFilterMode::Replace as i16 {
+        slur_filters.replace.push(regex);
+      } else if filter.mode == SlurFilterMode::Flag as i16 {
+        slur_filters.flag.push(regex);
+      }
+    }
+    slur_filters
+  }
+
+  /// Fails if a filter in reject mode matches the text, and replaces matches of filters in replace
+  /// mode. Matches of filters in flag mode are added to `flagged`.
+  pub fn apply(&self, text: &str, flagged: &mut Vec<String>) -> Result<String, LemmyError> {
+    for reject in &self.reject {
+      check_slurs(text, reject)?;
+    }
+    let text = self.replace.iter().fold(text.to_owned(), |text, replace| {
+      remove_slurs(&text, replace)
+    });
+    for flag in &self.flag {
+      flagged.extend(flag.find_iter(&text).map(|m| m.as_str().to_lowercase()));
+    }
+    Ok(text)
+  }
+
+  pub fn apply_opt(
+    &self,
+    text: &Option<String>,
+    flagged: &mut Vec<String>,
+  ) -> Result<Option<String>, LemmyError> {
+    text.as_ref().map(|t| self.apply(t, flagged)).transpose()
+  }
+}
+
+pub async fn get_slur_filters(
+  community_id: CommunityId,
+  pool: &DbPool,
+) -> Result<SlurFilters, LemmyError> {
+  let filters = blocking(pool, move |conn| {
+    SlurFilter::for_content_in(conn, community_id)
+  })
+  .await??;
+  Ok(SlurFilters::new(&filters))
+}
+
+/// The reason for reports which are filed when content matches slur filters in flag mode
+fn slur_filter_report_reason(mut flagged: Vec<String>) -> String {
+  flagged.sort_unstable();
+  flagged.dedup();
+  format!("Matched slur filter: {}", flagged.join(", "))
+}
+
+/// Files a report in the name of the site creator when a post matched slur filters in flag mode,
+/// so that it shows up for review by the mods. If the post was flagged before, that report is
+/// updated and re-opened.
+pub async fn flag_post_for_review(
+  post: &Post,
+  flagged: Vec<String>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  if flagged.is_empty() {
+    return Ok(());
+  }
+  let post = post.to_owned();
+  let reason = slur_filter_report_reason(flagged);
+  blocking(pool, move |conn| -> Result<(), LemmyError> {
+    let form = PostReportForm {
+      creator_id: Site::read_simple(conn)?.creator_id,
+      post_id: post.id,
+      original_post_name: post.name,
+      original_post_url: post.url,
+      original_post_body: post.body,
+      reason,
+    };
+    PostReport::report_or_reopen(conn, &form)
+      .map_err(|_| ApiError::err("couldnt_create_report"))?;
+    Ok(())
+  })
+  .await?
+}
+
+/// Same as [`flag_post_for_review`], for comments.
+pub async fn flag_comment_for_review(
+  comment: &Comment,
+  flagged: Vec<String>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  if flagged.is_empty() {
+    return Ok(());
+  }
+  let comment = comment.to_owned();
+  let reason = slur_filter_report_reason(flagged);
+  blocking(pool, move |conn| -> Result<(), LemmyError> {
+    let form = CommentReportForm {
+      creator_id: Site::read_simple(conn)?.creator_id,
+      comment_id: comment.id,
+      original_comment_text: comment.content,
+      reason,
+    };
+    CommentReport::report_or_reopen(conn, &form)
+      .map_err(|_| ApiError::err("couldnt_create_report"))?;
+    Ok(())
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::*;
+  use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    QueryDsl,
+    RunQueryDsl,
+  };
+  use lemmy_db_queries::{establish_unpooled_connection, get_database_url_from_env};
+  use lemmy_db_schema::{
+    schema::person,
+    source::{
+      comment::CommentForm,
+      community::CommunityForm,
+      instance::InstanceForm,
+      local_user::{LocalUser, LocalUserForm},
+      person::PersonForm,
+      post::PostForm,
+    },
+  };
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_instance_lists() {
+    let conn = establish_unpooled_connection();
+    let manager = ConnectionManager::<PgConnection>::new(get_database_url_from_env().unwrap());
+    let pool = Pool::builder().max_size(1).build(manager).unwrap();
+
+    let instance_form = |domain: &str, blocked| InstanceForm {
+      domain: domain.into(),
+      blocked,
+      reason: None,
+      updated: None,
+    };
+    Instance::upsert(&conn, &instance_form("allowed.tld", false)).unwrap();
+    Instance::upsert(&conn, &instance_form("blocked.tld", true)).unwrap();
+    refresh_instance_lists(&pool).await.unwrap();
+    let refreshed = federated_instance_lists();
+
+    Instance::delete_from_domain(&conn, "allowed.tld").unwrap();
+    Instance::delete_from_domain(&conn, "blocked.tld").unwrap();
+    refresh_instance_lists(&pool).await.unwrap();
+    let emptied = federated_instance_lists();
+
+    // The config file used for tests has no instance lists
+    assert_eq!(
+      FederatedInstanceLists {
+        allowed: Some(vec!["allowed.tld".to_string()].into_iter().collect()),
+        blocked: Some(vec!["blocked.tld".to_string()].into_iter().collect()),
+      },
+      *refreshed
+    );
+    assert_eq!(FederatedInstanceLists::default(), *emptied);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_delete_user_account() {
+    let conn = establish_unpooled_connection();
+    let manager = ConnectionManager::<PgConnection>::new(get_database_url_from_env().unwrap());
+    let pool = Pool::builder().max_size(1).build(manager).unwrap();
+
+    let person_form = PersonForm {
+      name: "delete_account_person".into(),
+      bio: Some(Some("My bio".into())),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "123456".into(),
+      email: Some(Some("delete_account@example.com".into())),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    let community_form = CommunityForm {
+      name: "delete_account_community".into(),
+      title: "nada".into(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "A post".into(),
+      body: Some("Post body".into()),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &post_form).unwrap();
+    let comment_form = CommentForm {
+      content: "A comment".into(),
+      creator_id: inserted_person.id,
+      post_id: inserted_post.id,
+      ..CommentForm::default()
+    };
+    let inserted_comment = Comment::create(&conn, &comment_form).unwrap();
+
+    delete_user_account(inserted_person.id, &pool)
+      .await
+      .unwrap();
+    // Person::read leaves out deleted accounts
+    let deleted_person = person::table
+      .find(inserted_person.id)
+      .first::<Person>(&conn)
+      .unwrap();
+    let deleted_local_user = LocalUser::read(&conn, inserted_local_user.id).unwrap();
+    let deleted_post = Post::read(&conn, inserted_post.id).unwrap();
+    let deleted_comment = Comment::read(&conn, inserted_comment.id).unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert!(deleted_person.deleted);
+    assert_eq!(None, deleted_person.bio);
+    assert_eq!(None, deleted_local_user.email);
+    assert!(deleted_post.deleted);
+    assert_eq!("*Permananently Deleted*", deleted_post.name);
+    assert_eq!(Some("*Permananently Deleted*".into()), deleted_post.body);
+    assert!(deleted_comment.deleted);
+    assert_eq!("*Permananently Deleted*", deleted_comment.content);
+  }
+
+  #[test]
+  fn test_combine_instance_lists() {
+    let set = |domains: &[&str]| domains.iter().map(|d| d.to_string()).collect();
+    let db_lists = DatabaseInstanceLists {
+      allowed: vec!["b.tld".into()],
+      blocked: vec![],
+    };
+    let config_lists = FederationConfig {
+      allowed_instances: Some(vec!["c.tld".into(), "b.tld".into(), "a.tld".into()]),
+      blocked_instances: None,
+      ..FederationConfig::default()
+    };
+
+    // Entries from both places are merged, and a list that is empty everywhere stays inactive
+    assert_eq!(
+      FederatedInstanceLists {
+        allowed: Some(set(&["a.tld", "b.tld", "c.tld"])),
+        blocked: None,
+      },
+      combine_instance_lists(&config_lists, &db_lists)
+    );
+    // An allowed instance in the database enables the allowlist on its own
+    assert_eq!(
+      FederatedInstanceLists {
+        allowed: Some(set(&["b.tld"])),
+        blocked: None,
+      },
+      combine_instance_lists(&FederationConfig::default(), &db_lists)
+    );
+    assert_eq!(
+      FederatedInstanceLists::default(),
+      combine_instance_lists(
+        &FederationConfig::default(),
+        &DatabaseInstanceLists::default()
+      )
+    );
+    // An empty list in the config file still counts as set
+    let empty_config_lists = FederationConfig {
+      blocked_instances: Some(vec![]),
+      ..FederationConfig::default()
+    };
+    assert_eq!(
+      FederatedInstanceLists {
+        allowed: None,
+        blocked: Some(set(&[])),
+      },
+      combine_instance_lists(&empty_config_lists, &DatabaseInstanceLists::default())
+    );
+  }
+}
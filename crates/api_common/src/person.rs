@@ -8,14 +8,33 @@ use lemmy_db_views_actor::{
   person_mention_view::PersonMentionView,
   person_view::PersonViewSafe,
 };
+use lemmy_utils::ClientInfo;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
 pub struct Login {
   pub username_or_email: String,
   pub password: String,
-}
-use lemmy_db_schema::{CommunityId, PersonId, PersonMentionId, PrivateMessageId};
+  /// Required if the user enabled two-factor authentication. Can also be one of the recovery codes.
+  pub totp_2fa_token: Option<String>,
+  #[serde(skip)]
+  pub client_info: ClientInfo,
+}
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    invite_code::InviteCode,
+    local_user_keyword_filter::LocalUserKeywordFilter,
+    login_token::LoginToken,
+    post::Post,
+    private_message::PrivateMessage,
+  },
+  CommunityId,
+  DbUrl,
+  PersonId,
+  PersonMentionId,
+  PrivateMessageId,
+};
 
 #[derive(Deserialize)]
 pub struct Register {
@@ -27,6 +46,12 @@ pub struct Register {
   pub captcha_uuid: Option<String>,
   pub captcha_answer: Option<String>,
   pub honeypot: Option<String>,
+  /// Answer to the application question, if the site requires applications
+  pub answer: Option<String>,
+  /// Lets the user register even if registration is closed
+  pub invite_code: Option<String>,
+  #[serde(skip)]
+  pub client_info: ClientInfo,
 }
 
 #[derive(Deserialize)]
@@ -64,20 +89,89 @@ pub struct SaveUserSettings {
   pub show_bot_accounts: Option<bool>,
   pub show_read_posts: Option<bool>,
   pub show_new_post_notifs: Option<bool>,
+  /// Actor ids of accounts on other instances which belong to the same user. One of them can then
+  /// be moved to this account.
+  pub also_known_as: Option<Vec<String>>,
+  pub auth: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct KeywordFilter {
+  pub phrase: String,
+  pub regex: Option<bool>,
+  pub scope_title: Option<bool>,
+  pub scope_body: Option<bool>,
+  pub scope_comments: Option<bool>,
+  /// `KeywordFilterAction` as number, 0 to hide matching content (the default) or 1 to blur it
+  pub action: Option<i16>,
+}
+
+/// Replaces all keyword filters of the user
+#[derive(Deserialize)]
+pub struct SaveKeywordFilters {
+  pub keyword_filters: Vec<KeywordFilter>,
   pub auth: String,
 }
 
+#[derive(Deserialize)]
+pub struct GetKeywordFilters {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct KeywordFiltersResponse {
+  pub keyword_filters: Vec<LocalUserKeywordFilter>,
+}
+
 #[derive(Deserialize)]
 pub struct ChangePassword {
   pub new_password: String,
   pub new_password_verify: String,
   pub old_password: String,
   pub auth: String,
+  #[serde(skip)]
+  pub client_info: ClientInfo,
 }
 
 #[derive(Serialize)]
 pub struct LoginResponse {
-  pub jwt: String,
+  /// Short lived access token, which has to be passed as `auth` parameter. Not set after
+  /// registration or password reset, if the email address has to be verified or the application
+  /// approved first. Also not set after password reset with two-factor authentication enabled.
+  pub jwt: Option<String>,
+  /// Only set when a new login session was created or refreshed
+  pub refresh_token: Option<String>,
+  /// True if a verification email was sent to a new email address
+  pub verify_email_sent: bool,
+  /// True if a registration application was created, which has to be approved by an admin
+  pub registration_created: bool,
+}
+
+/// Gets a new access token for the login session of the refresh token. The refresh token can
+/// only be used once, the response contains a new one.
+#[derive(Deserialize)]
+pub struct RefreshToken {
+  pub refresh_token: String,
+  #[serde(skip)]
+  pub client_info: ClientInfo,
+}
+
+#[derive(Deserialize)]
+pub struct ListLoginTokens {
+  pub auth: String,
+}
+
+#[derive(Deserialize)]
+pub struct RevokeLoginToken {
+  pub login_token_id: i32,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ListLoginTokensResponse {
+  pub login_tokens: Vec<LoginToken>,
+  /// The login session of the access token which was used for this request
+  pub current_login_token_id: i32,
 }
 
 #[derive(Deserialize)]
@@ -120,6 +214,7 @@ pub struct MarkAllAsRead {
 pub struct AddAdmin {
   pub person_id: PersonId,
   pub added: bool,
+  pub reason: Option<String>,
   pub auth: String,
 }
 
@@ -201,11 +296,21 @@ pub struct PasswordReset {
 #[derive(Serialize, Clone)]
 pub struct PasswordResetResponse {}
 
+#[derive(Deserialize)]
+pub struct VerifyEmail {
+  pub token: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct VerifyEmailResponse {}
+
 #[derive(Deserialize)]
 pub struct PasswordChange {
   pub token: String,
   pub password: String,
   pub password_verify: String,
+  #[serde(skip)]
+  pub client_info: ClientInfo,
 }
 
 #[derive(Deserialize)]
@@ -254,6 +359,140 @@ pub struct PrivateMessageResponse {
   pub private_message_view: PrivateMessageView,
 }
 
+#[derive(Deserialize)]
+pub struct CreateInviteCode {
+  /// Only admins can create codes with unlimited uses
+  pub max_uses: Option<i32>,
+  /// Unix timestamp after which the code can't be used anymore
+  pub expires: Option<i64>,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct InviteCodeResponse {
+  pub invite_code: InviteCode,
+}
+
+/// Admins get all invite codes, other users only their own
+#[derive(Deserialize)]
+pub struct ListInviteCodes {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ListInviteCodesResponse {
+  pub invite_codes: Vec<InviteCode>,
+}
+
+#[derive(Deserialize)]
+pub struct RevokeInviteCode {
+  pub invite_code_id: i32,
+  pub auth: String,
+}
+
+/// Generates a new secret for two-factor authentication. It is only used after confirming it with
+/// `UpdateTotp`.
+#[derive(Deserialize)]
+pub struct GenerateTotpSecret {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct GenerateTotpSecretResponse {
+  pub totp_secret_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTotp {
+  /// A code from the authenticator app. For disabling, a recovery code works too.
+  pub totp_2fa_token: String,
+  pub enabled: bool,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct UpdateTotpResponse {
+  pub enabled: bool,
+  /// Only returned once, when two-factor authentication gets enabled
+  pub recovery_codes: Option<Vec<String>>,
+}
+
+/// Moves the account to another instance. The new account has to list this one in `also_known_as`
+/// first.
+#[derive(Deserialize)]
+pub struct MoveAccount {
+  /// Actor id of the new account
+  pub target: String,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct MoveAccountResponse {
+  pub person_view: PersonViewSafe,
+}
+
+#[derive(Deserialize)]
+pub struct ExportUserData {
+  pub auth: String,
+}
+
+/// All the content and settings of a user on this instance
+#[derive(Serialize)]
+pub struct ExportUserDataResponse {
+  pub settings: UserSettingsBackup,
+  pub posts: Vec<Post>,
+  pub comments: Vec<Comment>,
+  pub private_messages: Vec<PrivateMessage>,
+  pub saved_posts: Vec<Post>,
+  pub saved_comments: Vec<Comment>,
+}
+
+/// Profile, settings, follows and blocks of a user. This is the part of the export which can be
+/// restored on another instance with `ImportUserSettings`. Communities and users are referenced by
+/// their actor id, so that they can be fetched from anywhere.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct UserSettingsBackup {
+  pub display_name: Option<String>,
+  pub bio: Option<String>,
+  pub avatar: Option<DbUrl>,
+  pub banner: Option<DbUrl>,
+  pub matrix_user_id: Option<String>,
+  pub bot_account: Option<bool>,
+  pub show_nsfw: Option<bool>,
+  pub theme: Option<String>,
+  pub default_sort_type: Option<i16>,
+  pub default_listing_type: Option<i16>,
+  pub lang: Option<String>,
+  pub show_avatars: Option<bool>,
+  pub send_notifications_to_email: Option<bool>,
+  pub show_bot_accounts: Option<bool>,
+  pub show_scores: Option<bool>,
+  pub show_read_posts: Option<bool>,
+  pub show_new_post_notifs: Option<bool>,
+  #[serde(default)]
+  pub followed_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_users: Vec<DbUrl>,
+}
+
+/// Restores the profile and settings, and follows and blocks the communities and users of the
+/// backup. Remote ones are fetched while handling the request, so at most 50 follows and blocks
+/// can be imported at once. Larger backups have to be split up, those which were already imported
+/// are skipped.
+#[derive(Deserialize)]
+pub struct ImportUserSettings {
+  pub settings: UserSettingsBackup,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportUserSettingsResponse {
+  /// Communities and users which couldn't be fetched or followed / blocked
+  pub failed: Vec<DbUrl>,
+}
+
 #[derive(Deserialize)]
 pub struct GetReportCount {
   pub community_id: Option<CommunityId>,
@@ -266,3 +505,27 @@ pub struct GetReportCountResponse {
   pub comment_reports: i64,
   pub post_reports: i64,
 }
+
+/// Requests which start or refresh a login session. The client details aren't part of the request
+/// body, so the API routes fill them in from the HTTP request.
+pub trait SetClientInfo {
+  fn set_client_info(&mut self, client_info: ClientInfo);
+}
+
+macro_rules! impl_set_client_info {
+  ($($t:ty),*) => {
+    $(impl SetClientInfo for $t {
+      fn set_client_info(&mut self, client_info: ClientInfo) {
+        self.client_info = client_info;
+      }
+    })*
+  };
+}
+
+impl_set_client_info!(
+  Login,
+  Register,
+  ChangePassword,
+  PasswordChange,
+  RefreshToken
+);
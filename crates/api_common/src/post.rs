@@ -1,4 +1,4 @@
-use lemmy_db_schema::{CommunityId, PostId, PostReportId};
+use lemmy_db_schema::{source::post::PostRevision, CommunityId, PostId, PostReportId};
 use lemmy_db_views::{
   comment_view::CommentView,
   post_report_view::PostReportView,
@@ -20,6 +20,9 @@ pub struct CreatePost {
   pub body: Option<String>,
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
+  /// Unix timestamp at which the post gets published. Until then it is only visible to its creator.
+  /// If the creator is banned at that time, the post is published once the ban ends.
+  pub scheduled_publish_time: Option<i64>,
   pub auth: String,
 }
 
@@ -96,6 +99,7 @@ pub struct RemovePost {
 pub struct LockPost {
   pub post_id: PostId,
   pub locked: bool,
+  pub reason: Option<String>,
   pub auth: String,
 }
 
@@ -103,6 +107,7 @@ pub struct LockPost {
 pub struct StickyPost {
   pub post_id: PostId,
   pub stickied: bool,
+  pub reason: Option<String>,
   pub auth: String,
 }
 
@@ -148,6 +153,37 @@ pub struct ListPostReportsResponse {
   pub post_reports: Vec<PostReportView>,
 }
 
+/// Earlier versions of a post, only visible to its creator, mods and admins
+#[derive(Deserialize)]
+pub struct GetPostRevisions {
+  pub post_id: PostId,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct GetPostRevisionsResponse {
+  pub revisions: Vec<PostRevision>,
+}
+
+#[derive(Deserialize)]
+pub struct ListScheduledPosts {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ListScheduledPostsResponse {
+  pub posts: Vec<PostView>,
+}
+
+/// Deletes a post which hasn't been published yet
+#[derive(Deserialize)]
+pub struct CancelScheduledPost {
+  pub post_id: PostId,
+  pub auth: String,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct GetSiteMetadata {
   pub url: Url,
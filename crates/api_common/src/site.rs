@@ -1,8 +1,17 @@
-use lemmy_db_schema::{CommunityId, PersonId};
+use lemmy_db_schema::{
+  source::{
+    instance::Instance,
+    local_user_keyword_filter::LocalUserKeywordFilter,
+    slur_filter::SlurFilter,
+  },
+  CommunityId,
+  PersonId,
+};
 use lemmy_db_views::{
   comment_view::CommentView,
   local_user_view::LocalUserSettingsView,
   post_view::PostView,
+  registration_application_view::RegistrationApplicationView,
   site_view::SiteView,
 };
 use lemmy_db_views_actor::{
@@ -10,21 +19,11 @@ use lemmy_db_views_actor::{
   community_follower_view::CommunityFollowerView,
   community_moderator_view::CommunityModeratorView,
   community_view::CommunityView,
+  instance_block_view::InstanceBlockView,
   person_block_view::PersonBlockView,
   person_view::PersonViewSafe,
 };
-use lemmy_db_views_moderator::{
-  mod_add_community_view::ModAddCommunityView,
-  mod_add_view::ModAddView,
-  mod_ban_from_community_view::ModBanFromCommunityView,
-  mod_ban_view::ModBanView,
-  mod_lock_post_view::ModLockPostView,
-  mod_remove_comment_view::ModRemoveCommentView,
-  mod_remove_community_view::ModRemoveCommunityView,
-  mod_remove_post_view::ModRemovePostView,
-  mod_sticky_post_view::ModStickyPostView,
-  mod_transfer_community_view::ModTransferCommunityView,
-};
+use lemmy_db_views_moderator::modlog_combined_view::ModlogView;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
@@ -36,6 +35,13 @@ pub struct Search {
   pub type_: Option<String>,
   pub sort: Option<String>,
   pub listing_type: Option<String>,
+  /// Unix timestamps bounding the publish time of posts and comments.
+  pub published_after: Option<i64>,
+  pub published_before: Option<i64>,
+  /// Only return results from communities (or users) on this domain.
+  pub instance: Option<String>,
+  pub has_url: Option<bool>,
+  pub has_body: Option<bool>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub auth: Option<String>,
@@ -67,23 +73,17 @@ pub struct ResolveObjectResponse {
 #[derive(Deserialize)]
 pub struct GetModlog {
   pub mod_person_id: Option<PersonId>,
+  pub other_person_id: Option<PersonId>,
   pub community_id: Option<CommunityId>,
+  pub action_type: Option<String>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
 }
 
 #[derive(Serialize)]
 pub struct GetModlogResponse {
-  pub removed_posts: Vec<ModRemovePostView>,
-  pub locked_posts: Vec<ModLockPostView>,
-  pub stickied_posts: Vec<ModStickyPostView>,
-  pub removed_comments: Vec<ModRemoveCommentView>,
-  pub removed_communities: Vec<ModRemoveCommunityView>,
-  pub banned_from_community: Vec<ModBanFromCommunityView>,
-  pub banned: Vec<ModBanView>,
-  pub added_to_community: Vec<ModAddCommunityView>,
-  pub transferred_to_community: Vec<ModTransferCommunityView>,
-  pub added: Vec<ModAddView>,
+  /// Mod actions of all types, newest first
+  pub modlog: Vec<ModlogView>,
 }
 
 #[derive(Deserialize)]
@@ -97,6 +97,13 @@ pub struct CreateSite {
   pub open_registration: Option<bool>,
   pub enable_nsfw: Option<bool>,
   pub community_creation_admin_only: Option<bool>,
+  pub require_email_verification: Option<bool>,
+  /// New users have to answer the application question, and wait until an admin approves them.
+  /// Only applies if registration is open.
+  pub require_application: Option<bool>,
+  pub application_question: Option<String>,
+  /// Only users with two-factor authentication enabled can be added as admins
+  pub require_totp_2fa_for_admins: Option<bool>,
   pub auth: String,
 }
 
@@ -111,6 +118,13 @@ pub struct EditSite {
   pub open_registration: Option<bool>,
   pub enable_nsfw: Option<bool>,
   pub community_creation_admin_only: Option<bool>,
+  pub require_email_verification: Option<bool>,
+  /// New users have to answer the application question, and wait until an admin approves them.
+  /// Only applies if registration is open.
+  pub require_application: Option<bool>,
+  pub application_question: Option<String>,
+  /// Only users with two-factor authentication enabled can be added as admins
+  pub require_totp_2fa_for_admins: Option<bool>,
   pub auth: String,
 }
 
@@ -142,6 +156,8 @@ pub struct MyUserInfo {
   pub moderates: Vec<CommunityModeratorView>,
   pub community_blocks: Vec<CommunityBlockView>,
   pub person_blocks: Vec<PersonBlockView>,
+  pub instance_blocks: Vec<InstanceBlockView>,
+  pub keyword_filters: Vec<LocalUserKeywordFilter>,
 }
 
 #[derive(Deserialize)]
@@ -166,6 +182,132 @@ pub struct SaveSiteConfig {
   pub auth: String,
 }
 
+#[derive(Deserialize)]
+pub struct GetFederationQueue {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct GetFederationQueueResponse {
+  pub pending: i64,
+  pub running: i64,
+  pub retrying: i64,
+}
+
+#[derive(Deserialize)]
+pub struct AddInstance {
+  pub domain: String,
+  pub blocked: bool,
+  pub reason: Option<String>,
+  /// Has to be true to add the first allowed instance, as that turns on the allowlist and stops
+  /// federation with every instance which isn't on it.
+  pub enable_allowlist: Option<bool>,
+  pub auth: String,
+}
+
+#[derive(Deserialize)]
+pub struct RemoveInstance {
+  pub domain: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct InstanceResponse {
+  pub instance: Instance,
+}
+
+#[derive(Deserialize)]
+pub struct ListInstances {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ListInstancesResponse {
+  pub instances: Vec<Instance>,
+  /// Whether federation is limited to allowed instances, from the database or the config file
+  pub allowlist_enabled: bool,
+}
+
+#[derive(Deserialize)]
+pub struct BlockInstance {
+  pub domain: String,
+  pub block: bool,
+  pub auth: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BlockInstanceResponse {
+  pub domain: String,
+  pub blocked: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ListRegistrationApplications {
+  /// Only shows the applications which weren't decided yet
+  pub unread_only: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct ListRegistrationApplicationsResponse {
+  pub registration_applications: Vec<RegistrationApplicationView>,
+}
+
+#[derive(Deserialize)]
+pub struct ApproveRegistrationApplication {
+  pub id: i32,
+  pub approve: bool,
+  pub deny_reason: Option<String>,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct RegistrationApplicationResponse {
+  pub registration_application: RegistrationApplicationView,
+}
+
+#[derive(Deserialize)]
+pub struct GetUnreadRegistrationApplicationCount {
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct GetUnreadRegistrationApplicationCountResponse {
+  pub registration_applications: i64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SlurFilterEntry {
+  pub phrase: String,
+  pub regex: Option<bool>,
+  /// `SlurFilterMode` as number, 0 to reject matching content (the default), 1 to replace matches
+  /// or 2 to flag it for review
+  pub mode: Option<i16>,
+}
+
+/// Site-wide slur filters are managed by admins, those of a community (with `community_id`) by its
+/// mods.
+#[derive(Deserialize)]
+pub struct GetSlurFilters {
+  pub community_id: Option<CommunityId>,
+  pub auth: String,
+}
+
+/// Replaces all site-wide slur filters, or those of the community
+#[derive(Deserialize)]
+pub struct SaveSlurFilters {
+  pub community_id: Option<CommunityId>,
+  pub slur_filters: Vec<SlurFilterEntry>,
+  pub auth: String,
+}
+
+#[derive(Serialize)]
+pub struct SlurFiltersResponse {
+  pub slur_filters: Vec<SlurFilter>,
+}
+
 #[derive(Serialize)]
 pub struct FederatedInstances {
   pub linked: Vec<String>,
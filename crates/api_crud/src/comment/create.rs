@@ -4,9 +4,12 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_person_block,
+  check_post_published,
   comment::*,
+  flag_comment_for_review,
   get_local_user_view_from_jwt,
   get_post,
+  get_slur_filters,
   send_local_notifs,
 };
 use lemmy_apub::{
@@ -53,10 +56,15 @@ impl PerformCrud for CreateComment {
     // Check for a community ban
     let post_id = data.post_id;
     let post = get_post(post_id, context.pool()).await?;
+    check_post_published(&post)?;
     let community_id = post.community_id;
 
     check_community_ban(local_user_view.person.id, community_id, context.pool()).await?;
 
+    let slur_filters = get_slur_filters(community_id, context.pool()).await?;
+    let mut flagged = vec![];
+    let content_slurs_removed = slur_filters.apply(&content_slurs_removed, &mut flagged)?;
+
     check_person_block(local_user_view.person.id, post.creator_id, context.pool()).await?;
 
     // Check if post is locked, no new comments
@@ -111,6 +119,8 @@ impl PerformCrud for CreateComment {
       .await?
       .map_err(|_| ApiError::err("couldnt_create_comment"))?;
 
+    flag_comment_for_review(&updated_comment, flagged, context.pool()).await?;
+
     CreateOrUpdateComment::send(
       &updated_comment,
       &local_user_view.person,
@@ -3,14 +3,19 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_mod_permission,
   comment::*,
   get_local_user_view_from_jwt,
-  is_mod_or_admin,
   send_local_notifs,
 };
 use lemmy_apub::activities::deletion::{send_apub_delete, send_apub_remove};
 use lemmy_db_queries::{source::comment::Comment_, Crud};
-use lemmy_db_schema::source::{comment::*, community::Community, moderator::*, post::Post};
+use lemmy_db_schema::source::{
+  comment::*,
+  community::{Community, ModPermission},
+  moderator::*,
+  post::Post,
+};
 use lemmy_db_views::comment_view::CommentView;
 use lemmy_utils::{ApiError, ConnectionId, LemmyError};
 use lemmy_websocket::{send::send_comment_ws_message, LemmyContext, UserOperationCrud};
@@ -121,10 +126,11 @@ impl PerformCrud for RemoveComment {
     .await?;
 
     // Verify that only a mod or admin can remove
-    is_mod_or_admin(
+    check_mod_permission(
       context.pool(),
       local_user_view.person.id,
       orig_comment.community.id,
+      ModPermission::Remove,
     )
     .await?;
 
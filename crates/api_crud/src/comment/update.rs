@@ -4,14 +4,16 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   comment::*,
+  flag_comment_for_review,
   get_local_user_view_from_jwt,
+  get_slur_filters,
   send_local_notifs,
 };
 use lemmy_apub::activities::{
   comment::create_or_update::CreateOrUpdateComment,
   CreateOrUpdateType,
 };
-use lemmy_db_queries::source::comment::Comment_;
+use lemmy_db_queries::source::comment::{CommentRevision_, Comment_};
 use lemmy_db_schema::source::comment::*;
 use lemmy_db_views::comment_view::CommentView;
 use lemmy_utils::{
@@ -57,6 +59,9 @@ impl PerformCrud for EditComment {
     // Do the update
     let content_slurs_removed =
       remove_slurs(&data.content.to_owned(), &context.settings().slur_regex());
+    let slur_filters = get_slur_filters(orig_comment.community.id, context.pool()).await?;
+    let mut flagged = vec![];
+    let content_slurs_removed = slur_filters.apply(&content_slurs_removed, &mut flagged)?;
     let comment_id = data.comment_id;
     let updated_comment = blocking(context.pool(), move |conn| {
       Comment::update_content(conn, comment_id, &content_slurs_removed)
@@ -64,6 +69,15 @@ impl PerformCrud for EditComment {
     .await?
     .map_err(|_| ApiError::err("couldnt_update_comment"))?;
 
+    flag_comment_for_review(&updated_comment, flagged, context.pool()).await?;
+
+    let orig = orig_comment.comment.to_owned();
+    let revision_comment = updated_comment.clone();
+    blocking(context.pool(), move |conn| {
+      CommentRevision::create_if_changed(conn, &orig, &revision_comment)
+    })
+    .await??;
+
     // Send the apub update
     CreateOrUpdateComment::send(
       &updated_comment,
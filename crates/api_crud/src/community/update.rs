@@ -9,8 +9,7 @@ use lemmy_apub::activities::community::update::UpdateCommunity;
 use lemmy_db_queries::{diesel_option_overwrite_to_url, Crud};
 use lemmy_db_schema::{
   naive_now,
-  source::community::{Community, CommunityForm},
-  PersonId,
+  source::community::{Community, CommunityForm, ModPermission},
 };
 use lemmy_db_views_actor::community_moderator_view::CommunityModeratorView;
 use lemmy_utils::{utils::check_slurs_opt, ApiError, ConnectionId, LemmyError};
@@ -32,14 +31,17 @@ impl PerformCrud for EditCommunity {
     check_slurs_opt(&data.title, &context.settings().slur_regex())?;
     check_slurs_opt(&data.description, &context.settings().slur_regex())?;
 
-    // Verify its a mod (only mods can edit it)
+    // Verify its a mod (only mods can edit it), and that they have the permission to do so
     let community_id = data.community_id;
-    let mods: Vec<PersonId> = blocking(context.pool(), move |conn| {
+    let mods = blocking(context.pool(), move |conn| {
       CommunityModeratorView::for_community(conn, community_id)
-        .map(|v| v.into_iter().map(|m| m.moderator.id).collect())
     })
     .await??;
-    if !mods.contains(&local_user_view.person.id) {
+    let is_allowed = mods.iter().enumerate().any(|(i, m)| {
+      m.moderator.id == local_user_view.person.id
+        && (i == 0 || m.permissions.has(ModPermission::EditCommunity))
+    });
+    if !is_allowed {
       return Err(ApiError::err("not_a_moderator").into());
     }
 
@@ -3,7 +3,10 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_expire_time,
+  flag_post_for_review,
   get_local_user_view_from_jwt,
+  get_slur_filters,
   honeypot_check,
   mark_post_as_read,
   post::*,
@@ -20,6 +23,7 @@ use lemmy_apub::{
 };
 use lemmy_db_queries::{source::post::Post_, Crud, Likeable};
 use lemmy_db_schema::source::post::*;
+use lemmy_db_views::post_view::PostView;
 use lemmy_utils::{
   request::fetch_site_data,
   utils::{check_slurs, check_slurs_opt, clean_url_params, is_valid_post_title},
@@ -55,6 +59,14 @@ impl PerformCrud for CreatePost {
 
     check_community_ban(local_user_view.person.id, data.community_id, context.pool()).await?;
 
+    let slur_filters = get_slur_filters(data.community_id, context.pool()).await?;
+    let mut flagged = vec![];
+    let name = slur_filters.apply(data.name.trim(), &mut flagged)?;
+    let body = slur_filters.apply_opt(&data.body, &mut flagged)?;
+
+    let scheduled_publish_time = check_expire_time(data.scheduled_publish_time)
+      .map_err(|_| ApiError::err("scheduled_publish_time_in_past"))?;
+
     // Fetch post links and pictrs cached image
     let data_url = data.url.as_ref();
     let (metadata_res, pictrs_thumbnail) =
@@ -64,9 +76,9 @@ impl PerformCrud for CreatePost {
       .unwrap_or((None, None, None));
 
     let post_form = PostForm {
-      name: data.name.trim().to_owned(),
+      name,
       url: data_url.map(|u| clean_url_params(u.to_owned()).into()),
-      body: data.body.to_owned(),
+      body,
       community_id: data.community_id,
       creator_id: local_user_view.person.id,
       nsfw: data.nsfw,
@@ -74,6 +86,7 @@ impl PerformCrud for CreatePost {
       embed_description,
       embed_html,
       thumbnail_url: pictrs_thumbnail.map(|u| u.into()),
+      scheduled_publish_time: Some(scheduled_publish_time),
       ..PostForm::default()
     };
 
@@ -104,13 +117,7 @@ impl PerformCrud for CreatePost {
     .await?
     .map_err(|_| ApiError::err("couldnt_create_post"))?;
 
-    CreateOrUpdatePost::send(
-      &updated_post,
-      &local_user_view.person,
-      CreateOrUpdateType::Create,
-      context,
-    )
-    .await?;
+    flag_post_for_review(&updated_post, flagged, context.pool()).await?;
 
     // They like their own post by default
     let person_id = local_user_view.person.id;
@@ -129,6 +136,23 @@ impl PerformCrud for CreatePost {
     // Mark the post as read
     mark_post_as_read(person_id, post_id, context.pool()).await?;
 
+    // Scheduled posts are only federated and announced once they get published
+    if updated_post.scheduled_publish_time.is_some() {
+      let post_view = blocking(context.pool(), move |conn| {
+        PostView::read(conn, post_id, Some(person_id))
+      })
+      .await??;
+      return Ok(PostResponse { post_view });
+    }
+
+    CreateOrUpdatePost::send(
+      &updated_post,
+      &local_user_view.person,
+      CreateOrUpdateType::Create,
+      context,
+    )
+    .await?;
+
     if let Some(url) = &updated_post.url {
       let mut webmention = Webmention::new(
         updated_post.ap_id.clone().into_inner(),
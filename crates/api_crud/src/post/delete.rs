@@ -3,13 +3,18 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_mod_permission,
+  check_post_published,
   get_local_user_view_from_jwt,
-  is_mod_or_admin,
   post::*,
 };
 use lemmy_apub::activities::deletion::{send_apub_delete, send_apub_remove};
 use lemmy_db_queries::{source::post::Post_, Crud};
-use lemmy_db_schema::source::{community::Community, moderator::*, post::*};
+use lemmy_db_schema::source::{
+  community::{Community, ModPermission},
+  moderator::*,
+  post::*,
+};
 use lemmy_utils::{ApiError, ConnectionId, LemmyError};
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
 
@@ -28,6 +33,7 @@ impl PerformCrud for DeletePost {
 
     let post_id = data.post_id;
     let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
@@ -89,6 +95,7 @@ impl PerformCrud for RemovePost {
 
     let post_id = data.post_id;
     let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
@@ -98,10 +105,11 @@ impl PerformCrud for RemovePost {
     .await?;
 
     // Verify that only the mods can remove
-    is_mod_or_admin(
+    check_mod_permission(
       context.pool(),
       local_user_view.person.id,
       orig_post.community_id,
+      ModPermission::Remove,
     )
     .await?;
 
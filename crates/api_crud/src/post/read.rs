@@ -39,6 +39,13 @@ impl PerformCrud for GetPost {
     .await?
     .map_err(|_| ApiError::err("couldnt_find_post"))?;
 
+    // Scheduled posts are only visible to their creator
+    if post_view.post.scheduled_publish_time.is_some()
+      && person_id != Some(post_view.post.creator_id)
+    {
+      return Err(ApiError::err("couldnt_find_post").into());
+    }
+
     // Blank out deleted info
     if post_view.post.deleted || post_view.post.removed {
       post_view.post = post_view.post.blank_out_deleted_or_removed_info();
@@ -1,9 +1,20 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
-use lemmy_api_common::{blocking, check_community_ban, get_local_user_view_from_jwt, post::*};
+use lemmy_api_common::{
+  blocking,
+  check_community_ban,
+  flag_post_for_review,
+  get_local_user_view_from_jwt,
+  get_slur_filters,
+  post::*,
+};
 use lemmy_apub::activities::{post::create_or_update::CreateOrUpdatePost, CreateOrUpdateType};
-use lemmy_db_queries::{source::post::Post_, Crud};
+use lemmy_db_queries::{
+  source::post::{PostRevision_, Post_},
+  Crud,
+};
 use lemmy_db_schema::{naive_now, source::post::*};
+use lemmy_db_views::post_view::PostView;
 use lemmy_utils::{
   request::fetch_site_data,
   utils::{check_slurs_opt, clean_url_params, is_valid_post_title},
@@ -51,6 +62,11 @@ impl PerformCrud for EditPost {
       return Err(ApiError::err("no_post_edit_allowed").into());
     }
 
+    let slur_filters = get_slur_filters(orig_post.community_id, context.pool()).await?;
+    let mut flagged = vec![];
+    let name = slur_filters.apply_opt(&data.name, &mut flagged)?;
+    let body = slur_filters.apply_opt(&data.body, &mut flagged)?;
+
     // Fetch post links and Pictrs cached image
     let data_url = data.url.as_ref();
     let (metadata_res, pictrs_thumbnail) =
@@ -62,9 +78,9 @@ impl PerformCrud for EditPost {
     let post_form = PostForm {
       creator_id: orig_post.creator_id.to_owned(),
       community_id: orig_post.community_id,
-      name: data.name.to_owned().unwrap_or(orig_post.name),
+      name: name.unwrap_or_else(|| orig_post.name.to_owned()),
       url: data_url.map(|u| clean_url_params(u.to_owned()).into()),
-      body: data.body.to_owned(),
+      body,
       nsfw: data.nsfw,
       updated: Some(naive_now()),
       embed_title,
@@ -92,6 +108,24 @@ impl PerformCrud for EditPost {
       }
     };
 
+    flag_post_for_review(&updated_post, flagged, context.pool()).await?;
+
+    let revision_post = updated_post.clone();
+    blocking(context.pool(), move |conn| {
+      PostRevision::create_if_changed(conn, &orig_post, &revision_post)
+    })
+    .await??;
+
+    // Scheduled posts aren't federated or announced until they get published
+    if updated_post.scheduled_publish_time.is_some() {
+      let person_id = local_user_view.person.id;
+      let post_view = blocking(context.pool(), move |conn| {
+        PostView::read(conn, post_id, Some(person_id))
+      })
+      .await??;
+      return Ok(PostResponse { post_view });
+    }
+
     // Send apub update
     CreateOrUpdatePost::send(
       &updated_post,
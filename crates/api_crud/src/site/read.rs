@@ -7,15 +7,18 @@ use lemmy_api_common::{
   person::Register,
   site::*,
 };
+use lemmy_db_queries::source::local_user_keyword_filter::LocalUserKeywordFilter_;
+use lemmy_db_schema::source::local_user_keyword_filter::LocalUserKeywordFilter;
 use lemmy_db_views::site_view::SiteView;
 use lemmy_db_views_actor::{
   community_block_view::CommunityBlockView,
   community_follower_view::CommunityFollowerView,
   community_moderator_view::CommunityModeratorView,
+  instance_block_view::InstanceBlockView,
   person_block_view::PersonBlockView,
   person_view::PersonViewSafe,
 };
-use lemmy_utils::{version, ApiError, ConnectionId, LemmyError};
+use lemmy_utils::{version, ApiError, ClientInfo, ConnectionId, LemmyError};
 use lemmy_websocket::{messages::GetUsersOnline, LemmyContext};
 use log::info;
 
@@ -44,6 +47,9 @@ impl PerformCrud for GetSite {
             captcha_uuid: None,
             captcha_answer: None,
             honeypot: None,
+            answer: None,
+            invite_code: None,
+            client_info: ClientInfo::default(),
           };
           let login_response = register.perform(context, websocket_id).await?;
           info!("Admin {} created", setup.admin_username);
@@ -58,7 +64,13 @@ impl PerformCrud for GetSite {
             open_registration: setup.open_registration,
             enable_nsfw: setup.enable_nsfw,
             community_creation_admin_only: setup.community_creation_admin_only,
-            auth: login_response.jwt,
+            require_email_verification: None,
+            require_application: None,
+            application_question: None,
+            require_totp_2fa_for_admins: None,
+            auth: login_response
+              .jwt
+              .ok_or_else(|| ApiError::err("not_logged_in"))?,
           };
           create_site.perform(context, websocket_id).await?;
           info!("Site {} created", setup.site_name);
@@ -116,6 +128,19 @@ impl PerformCrud for GetSite {
       .await?
       .map_err(|_| ApiError::err("system_err_login"))?;
 
+      let instance_blocks = blocking(context.pool(), move |conn| {
+        InstanceBlockView::for_person(conn, person_id)
+      })
+      .await?
+      .map_err(|_| ApiError::err("system_err_login"))?;
+
+      let local_user_id = local_user_view.local_user.id;
+      let keyword_filters = blocking(context.pool(), move |conn| {
+        LocalUserKeywordFilter::for_local_user(conn, local_user_id)
+      })
+      .await?
+      .map_err(|_| ApiError::err("system_err_login"))?;
+
       let moderates = blocking(context.pool(), move |conn| {
         CommunityModeratorView::for_person(conn, person_id)
       })
@@ -128,6 +153,8 @@ impl PerformCrud for GetSite {
         moderates,
         community_blocks,
         person_blocks,
+        instance_blocks,
+        keyword_filters,
       })
     } else {
       None
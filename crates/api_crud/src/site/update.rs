@@ -43,6 +43,7 @@ impl PerformCrud for EditSite {
 
     let sidebar = diesel_option_overwrite(&data.sidebar);
     let description = diesel_option_overwrite(&data.description);
+    let application_question = diesel_option_overwrite(&data.application_question);
     let icon = diesel_option_overwrite_to_url(&data.icon)?;
     let banner = diesel_option_overwrite_to_url(&data.banner)?;
 
@@ -50,6 +51,11 @@ impl PerformCrud for EditSite {
       site_description_length_check(desc)?;
     }
 
+    // Verification emails can't be sent without an email server
+    if data.require_email_verification == Some(true) && context.settings().email.is_none() {
+      return Err(ApiError::err("email_not_configured").into());
+    }
+
     let site_form = SiteForm {
       creator_id: found_site.creator_id,
       name: data.name.to_owned().unwrap_or(found_site.name),
@@ -62,6 +68,10 @@ impl PerformCrud for EditSite {
       open_registration: data.open_registration,
       enable_nsfw: data.enable_nsfw,
       community_creation_admin_only: data.community_creation_admin_only,
+      require_email_verification: data.require_email_verification,
+      require_application: data.require_application,
+      application_question,
+      require_totp_2fa_for_admins: data.require_totp_2fa_for_admins,
     };
 
     let update_site = move |conn: &'_ _| Site::update(conn, 1, &site_form);
@@ -1,6 +1,13 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
-use lemmy_api_common::{blocking, honeypot_check, password_length_check, person::*};
+use lemmy_api_common::{
+  blocking,
+  create_login_session,
+  honeypot_check,
+  password_length_check,
+  person::*,
+  send_verification_email,
+};
 use lemmy_apub::{
   generate_apub_endpoint,
   generate_followers_url,
@@ -9,7 +16,7 @@ use lemmy_apub::{
   EndpointType,
 };
 use lemmy_db_queries::{
-  source::{local_user::LocalUser_, site::Site_},
+  source::{invite_code::InviteCode_, local_user::LocalUser_, site::Site_},
   Crud,
   Followable,
   Joinable,
@@ -19,8 +26,10 @@ use lemmy_db_queries::{
 use lemmy_db_schema::{
   source::{
     community::*,
+    invite_code::InviteCode,
     local_user::{LocalUser, LocalUserForm},
     person::*,
+    registration_application::{RegistrationApplication, RegistrationApplicationForm},
     site::*,
   },
   CommunityId,
@@ -28,13 +37,13 @@ use lemmy_db_schema::{
 use lemmy_db_views_actor::person_view::PersonViewSafe;
 use lemmy_utils::{
   apub::generate_actor_keypair,
-  claims::Claims,
   utils::{check_slurs, is_valid_actor_name},
   ApiError,
   ConnectionId,
   LemmyError,
 };
 use lemmy_websocket::{messages::CheckCaptcha, LemmyContext};
+use log::error;
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for Register {
@@ -43,15 +52,33 @@ impl PerformCrud for Register {
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<ConnectionId>,
+    websocket_id: Option<ConnectionId>,
   ) -> Result<LoginResponse, LemmyError> {
     let data: &Register = self;
 
     // Make sure site has open registration
+    let mut require_email_verification = false;
+    let mut require_application = false;
     if let Ok(site) = blocking(context.pool(), move |conn| Site::read_simple(conn)).await? {
-      if !site.open_registration {
+      // An invite code lets people in even if registration is closed
+      if !site.open_registration && data.invite_code.is_none() {
         return Err(ApiError::err("registration_closed").into());
       }
+      require_email_verification = site.require_email_verification;
+      require_application = site.require_application;
+    }
+
+    let email = data.email.to_owned().filter(|e| !e.is_empty());
+    if require_email_verification && email.is_none() {
+      return Err(ApiError::err("email_required").into());
+    }
+
+    if let Some(invite_code) = data.invite_code.to_owned() {
+      blocking(context.pool(), move |conn| {
+        InviteCode::read_valid(conn, &invite_code)
+      })
+      .await?
+      .map_err(|_| ApiError::err("invalid_invite_code"))?;
     }
 
     password_length_check(&data.password)?;
@@ -68,6 +95,13 @@ impl PerformCrud for Register {
     })
     .await??;
 
+    // The first admin doesn't have to apply
+    let require_application = require_application && !no_admins;
+    let answer = data.answer.to_owned().filter(|a| !a.trim().is_empty());
+    if require_application && answer.is_none() {
+      return Err(ApiError::err("registration_application_answer_required").into());
+    }
+
     // If its not the admin, check the captcha
     if !no_admins && context.settings().captcha.enabled {
       let check = context
@@ -125,7 +159,7 @@ impl PerformCrud for Register {
     // TODO some of these could probably use the DB defaults
     let local_user_form = LocalUserForm {
       person_id: inserted_person.id,
-      email: Some(data.email.to_owned()),
+      email: Some(email.to_owned()),
       password_encrypted: data.password.to_owned(),
       show_nsfw: Some(data.show_nsfw),
       show_bot_accounts: Some(true),
@@ -138,6 +172,10 @@ impl PerformCrud for Register {
       show_read_posts: Some(true),
       show_new_post_notifs: Some(false),
       send_notifications_to_email: Some(false),
+      email_verified: Some(false),
+      accepted_application: Some(!require_application),
+      totp_2fa_secret: None,
+      totp_2fa_enabled: None,
     };
 
     let inserted_local_user = match blocking(context.pool(), move |conn| {
@@ -165,6 +203,22 @@ impl PerformCrud for Register {
       }
     };
 
+    // Count the use of the invite code, now that the account exists
+    if let Some(invite_code) = data.invite_code.to_owned() {
+      let used = blocking(context.pool(), move |conn| {
+        InviteCode::use_code(conn, &invite_code)
+      })
+      .await?;
+      if used.is_err() {
+        // The code was used up in the meantime
+        blocking(context.pool(), move |conn| {
+          Person::delete(conn, inserted_person.id)
+        })
+        .await??;
+        return Err(ApiError::err("invalid_invite_code").into());
+      }
+    }
+
     let main_community_keypair = generate_actor_keypair()?;
 
     // Create the main community if it doesn't exist
@@ -226,13 +280,61 @@ impl PerformCrud for Register {
       }
     }
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Claims::jwt(
-        inserted_local_user.id.0,
-        &context.secret().jwt_secret,
-        &context.settings().hostname,
-      )?,
-    })
+    if let Some(answer) = answer.filter(|_| require_application) {
+      let application_form = RegistrationApplicationForm {
+        local_user_id: inserted_local_user.id,
+        answer,
+      };
+      blocking(context.pool(), move |conn| {
+        RegistrationApplication::create(conn, &application_form)
+      })
+      .await?
+      .map_err(|_| ApiError::err("couldnt_create_registration_application"))?;
+    }
+
+    // The address is saved right away, but it only counts as verified once the link was clicked.
+    // The account exists at this point, so a failure to send the mail mustn't fail the request.
+    let mut verify_email_sent = false;
+    if let Some(email) = &email {
+      if context.settings().email.is_some() {
+        match send_verification_email(
+          inserted_local_user.id,
+          email,
+          &inserted_person.name,
+          context.pool(),
+          &context.settings(),
+        )
+        .await
+        {
+          Ok(()) => verify_email_sent = true,
+          Err(e) => error!(
+            "Failed to send verification email to {}: {}",
+            inserted_person.name, e
+          ),
+        }
+      }
+    }
+
+    if require_email_verification || require_application {
+      return Ok(LoginResponse {
+        jwt: None,
+        refresh_token: None,
+        verify_email_sent,
+        registration_created: require_application,
+      });
+    }
+
+    // Start a new login session
+    let client_info = context.client_info(websocket_id, &data.client_info).await?;
+    let mut login_response = create_login_session(
+      inserted_local_user.id,
+      client_info,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await?;
+    login_response.verify_email_sent = verify_email_sent;
+    Ok(login_response)
   }
 }
@@ -1,9 +1,10 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
 use bcrypt::verify;
-use lemmy_api_common::{blocking, get_local_user_view_from_jwt, person::*};
-use lemmy_db_queries::source::{comment::Comment_, person::Person_, post::Post_};
-use lemmy_db_schema::source::{comment::Comment, person::*, post::Post};
+use lemmy_api_common::{blocking, delete_user_account, get_local_user_view_from_jwt, person::*};
+use lemmy_apub::activities::deletion::delete_user::DeleteUser;
+use lemmy_db_queries::source::login_token::LoginToken_;
+use lemmy_db_schema::source::login_token::LoginToken;
 use lemmy_utils::{ApiError, ConnectionId, LemmyError};
 use lemmy_websocket::LemmyContext;
 
@@ -30,26 +31,22 @@ impl PerformCrud for DeleteAccount {
       return Err(ApiError::err("password_incorrect").into());
     }
 
-    // Comments
-    let person_id = local_user_view.person.id;
-    let permadelete = move |conn: &'_ _| Comment::permadelete_for_creator(conn, person_id);
-    if blocking(context.pool(), permadelete).await?.is_err() {
-      return Err(ApiError::err("couldnt_update_comment").into());
-    }
-
-    // Posts
-    let permadelete = move |conn: &'_ _| Post::permadelete_for_creator(conn, person_id);
-    if blocking(context.pool(), permadelete).await?.is_err() {
-      return Err(ApiError::err("couldnt_update_post").into());
-    }
-
+    // Log out everywhere first, so that the account can't be used anymore even if federating the
+    // deletion fails
+    let local_user_id = local_user_view.local_user.id;
     blocking(context.pool(), move |conn| {
-      Person::delete_account(conn, person_id)
+      LoginToken::revoke_all(conn, local_user_id)
     })
     .await??;
 
+    delete_user_account(local_user_view.person.id, context.pool()).await?;
+    DeleteUser::send(&local_user_view.person, context).await?;
+
     Ok(LoginResponse {
-      jwt: data.auth.to_owned(),
+      jwt: None,
+      refresh_token: None,
+      verify_email_sent: false,
+      registration_created: false,
     })
   }
 }
@@ -10,18 +10,27 @@ use crate::{
   },
   context::lemmy_context,
   fetcher::object_id::ObjectId,
-  objects::{comment::Note, FromApub, ToApub},
+  objects::{
+    comment::{comment_to_note, Note},
+    FromApub,
+    ToApub,
+  },
 };
 use activitystreams::{base::AnyBase, link::Mention, primitives::OneOrMany, unparsed::Unparsed};
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
-  traits::{ActivityFields, ActivityHandler, ActorType},
+  traits::{ActivityFields, ActivityHandler, ActorType, ApubObject},
   values::PublicUrl,
   verify::verify_domains_match,
 };
-use lemmy_db_queries::Crud;
-use lemmy_db_schema::source::{comment::Comment, community::Community, person::Person, post::Post};
+use lemmy_db_queries::{source::comment::CommentRevision_, Crud};
+use lemmy_db_schema::source::{
+  comment::{Comment, CommentRevision},
+  community::Community,
+  person::Person,
+  post::Post,
+};
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{send::send_comment_ws_message, LemmyContext, UserOperationCrud};
 use serde::{Deserialize, Serialize};
@@ -81,6 +90,29 @@ impl CreateOrUpdateComment {
     let activity = AnnouncableActivities::CreateOrUpdateComment(create_or_update);
     send_to_community(activity, &id, actor, &community, maa.inboxes, context).await
   }
+
+  /// The `Create` activity of an existing comment, for the outbox of its creator. Its id is
+  /// derived from the comment id, so that it stays the same between requests. Mentions are left
+  /// out, as they would have to be fetched again.
+  pub(crate) fn for_outbox(
+    comment: &Comment,
+    actor: &Person,
+    post: &Post,
+    parent_comment: Option<&Comment>,
+    community: &Community,
+  ) -> Result<Self, LemmyError> {
+    Ok(CreateOrUpdateComment {
+      actor: ObjectId::new(actor.actor_id()),
+      to: [PublicUrl::Public],
+      object: comment_to_note(comment, actor, post, parent_comment),
+      cc: vec![community.actor_id()],
+      tag: vec![],
+      kind: CreateOrUpdateType::Create,
+      id: Url::parse(&format!("{}#create", comment.ap_id))?,
+      context: lemmy_context(),
+      unparsed: Default::default(),
+    })
+  }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -109,8 +141,25 @@ impl ActivityHandler for CreateOrUpdateComment {
     context: &Data<LemmyContext>,
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
+    let old_comment = match self.kind {
+      CreateOrUpdateType::Create => None,
+      CreateOrUpdateType::Update => {
+        let object_id = self.object.id_unchecked().clone();
+        blocking(context.pool(), move |conn| {
+          Comment::read_from_apub_id(conn, object_id)
+        })
+        .await??
+      }
+    };
     let comment =
       Comment::from_apub(&self.object, context, self.actor.inner(), request_counter).await?;
+    if let Some(old_comment) = old_comment {
+      let new_comment = comment.clone();
+      blocking(context.pool(), move |conn| {
+        CommentRevision::create_if_changed(conn, &old_comment, &new_comment)
+      })
+      .await??;
+    }
     let recipients = get_notif_recipients(&self.actor, &comment, context, request_counter).await?;
     let notif_type = match self.kind {
       CreateOrUpdateType::Create => UserOperationCrud::CreateComment,
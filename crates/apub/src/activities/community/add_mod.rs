@@ -17,6 +17,7 @@ use activitystreams::{
   primitives::OneOrMany,
   unparsed::Unparsed,
 };
+use anyhow::anyhow;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
@@ -25,9 +26,10 @@ use lemmy_apub_lib::{
 };
 use lemmy_db_queries::{source::community::CommunityModerator_, Joinable};
 use lemmy_db_schema::source::{
-  community::{Community, CommunityModerator, CommunityModeratorForm},
+  community::{Community, CommunityModerator, CommunityModeratorForm, ModPermission},
   person::Person,
 };
+use lemmy_db_views_actor::community_view::CommunityView;
 use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
 use serde::{Deserialize, Serialize};
@@ -90,7 +92,14 @@ impl ActivityHandler for AddMod {
   ) -> Result<(), LemmyError> {
     verify_activity(self, &context.settings())?;
     verify_person_in_community(&self.actor, &self.cc[0], context, request_counter).await?;
-    verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+    verify_mod_action(
+      &self.actor,
+      self.cc[0].clone(),
+      ModPermission::ManageMods,
+      context,
+      request_counter,
+    )
+    .await?;
     verify_add_remove_moderator_target(&self.target, &self.cc[0])?;
     Ok(())
   }
@@ -119,6 +128,21 @@ impl ActivityHandler for AddMod {
         CommunityModerator::join(conn, &form)
       })
       .await??;
+
+      // Like for local mods, a new mod gets the permissions of the mod who added them
+      if community.local {
+        let actor = self.actor.dereference(context, request_counter).await?;
+        let (actor_id, community_id) = (actor.id, community.id);
+        let permissions = blocking(context.pool(), move |conn| {
+          CommunityView::mod_permissions(conn, actor_id, community_id)
+        })
+        .await??
+        .ok_or_else(|| anyhow!("Not a mod"))?;
+        blocking(context.pool(), move |conn| {
+          CommunityModerator::update_permissions(conn, community_id, new_mod_id, &permissions)
+        })
+        .await??;
+      }
     }
     // TODO: send websocket notification about added mod
     Ok(())
@@ -29,6 +29,7 @@ use lemmy_db_schema::source::{
     CommunityFollowerForm,
     CommunityPersonBan,
     CommunityPersonBanForm,
+    ModPermission,
   },
   person::Person,
 };
@@ -100,7 +101,14 @@ impl ActivityHandler for BlockUserFromCommunity {
   ) -> Result<(), LemmyError> {
     verify_activity(self, &context.settings())?;
     verify_person_in_community(&self.actor, &self.cc[0], context, request_counter).await?;
-    verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+    verify_mod_action(
+      &self.actor,
+      self.cc[0].clone(),
+      ModPermission::Ban,
+      context,
+      request_counter,
+    )
+    .await?;
     Ok(())
   }
 
@@ -115,6 +123,7 @@ impl ActivityHandler for BlockUserFromCommunity {
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: community.id,
       person_id: blocked_user.id,
+      expires: None,
     };
 
     blocking(context.pool(), move |conn: &'_ _| {
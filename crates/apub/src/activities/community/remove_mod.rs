@@ -26,7 +26,7 @@ use lemmy_apub_lib::{
 };
 use lemmy_db_queries::Joinable;
 use lemmy_db_schema::source::{
-  community::{Community, CommunityModerator, CommunityModeratorForm},
+  community::{Community, CommunityModerator, CommunityModeratorForm, ModPermission},
   person::Person,
 };
 use lemmy_utils::LemmyError;
@@ -92,7 +92,14 @@ impl ActivityHandler for RemoveMod {
     verify_activity(self, &context.settings())?;
     if let Some(target) = &self.target {
       verify_person_in_community(&self.actor, &self.cc[0], context, request_counter).await?;
-      verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+      verify_mod_action(
+        &self.actor,
+        self.cc[0].clone(),
+        ModPermission::ManageMods,
+        context,
+        request_counter,
+      )
+      .await?;
       verify_add_remove_moderator_target(target, &self.cc[0])?;
     } else {
       verify_delete_activity(
@@ -27,7 +27,7 @@ use lemmy_apub_lib::{
 };
 use lemmy_db_queries::Bannable;
 use lemmy_db_schema::source::{
-  community::{Community, CommunityPersonBan, CommunityPersonBanForm},
+  community::{Community, CommunityPersonBan, CommunityPersonBanForm, ModPermission},
   person::Person,
 };
 use lemmy_utils::LemmyError;
@@ -91,7 +91,14 @@ impl ActivityHandler for UndoBlockUserFromCommunity {
   ) -> Result<(), LemmyError> {
     verify_activity(self, &context.settings())?;
     verify_person_in_community(&self.actor, &self.cc[0], context, request_counter).await?;
-    verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+    verify_mod_action(
+      &self.actor,
+      self.cc[0].clone(),
+      ModPermission::Ban,
+      context,
+      request_counter,
+    )
+    .await?;
     self.object.verify(context, request_counter).await?;
     Ok(())
   }
@@ -111,6 +118,7 @@ impl ActivityHandler for UndoBlockUserFromCommunity {
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: community.id,
       person_id: blocked_user.id,
+      expires: None,
     };
 
     blocking(context.pool(), move |conn: &'_ _| {
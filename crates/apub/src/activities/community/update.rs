@@ -24,7 +24,7 @@ use lemmy_apub_lib::{
 };
 use lemmy_db_queries::Crud;
 use lemmy_db_schema::source::{
-  community::{Community, CommunityForm},
+  community::{Community, CommunityForm, ModPermission},
   person::Person,
 };
 use lemmy_utils::LemmyError;
@@ -87,7 +87,14 @@ impl ActivityHandler for UpdateCommunity {
   ) -> Result<(), LemmyError> {
     verify_activity(self, &context.settings())?;
     verify_person_in_community(&self.actor, &self.cc[0], context, request_counter).await?;
-    verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+    verify_mod_action(
+      &self.actor,
+      self.cc[0].clone(),
+      ModPermission::EditCommunity,
+      context,
+      request_counter,
+    )
+    .await?;
     Ok(())
   }
 
@@ -0,0 +1,99 @@
+use crate::{
+  activities::{generate_activity_id, verify_activity},
+  context::lemmy_context,
+  fetcher::object_id::ObjectId,
+  send_lemmy_activity,
+};
+use activitystreams::{
+  activity::kind::DeleteType,
+  base::AnyBase,
+  primitives::OneOrMany,
+  unparsed::Unparsed,
+};
+use itertools::Itertools;
+use lemmy_api_common::{blocking, delete_user_account};
+use lemmy_apub_lib::{
+  data::Data,
+  traits::{ActivityFields, ActivityHandler, ActorType},
+  values::PublicUrl,
+  verify::verify_urls_match,
+};
+use lemmy_db_queries::source::{community::Community_, person::Person_};
+use lemmy_db_schema::{
+  source::{community::Community, person::Person},
+  DbUrl,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A user deleted their own account. Unlike `Delete`, this isn't tied to any community, and is sent
+/// to all instances which we know about.
+#[derive(Clone, Debug, Deserialize, Serialize, ActivityFields)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteUser {
+  actor: ObjectId<Person>,
+  to: [PublicUrl; 1],
+  object: ObjectId<Person>,
+  #[serde(rename = "type")]
+  kind: DeleteType,
+  id: Url,
+  #[serde(rename = "@context")]
+  context: OneOrMany<AnyBase>,
+  #[serde(flatten)]
+  unparsed: Unparsed,
+}
+
+impl DeleteUser {
+  pub async fn send(actor: &Person, context: &LemmyContext) -> Result<(), LemmyError> {
+    let delete = DeleteUser {
+      actor: ObjectId::new(actor.actor_id()),
+      to: [PublicUrl::Public],
+      object: ObjectId::new(actor.actor_id()),
+      kind: DeleteType::Delete,
+      id: generate_activity_id(
+        DeleteType::Delete,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      context: lemmy_context(),
+      unparsed: Default::default(),
+    };
+
+    let inboxes = blocking(context.pool(), move |conn| {
+      let mut inboxes = Community::remote_inboxes(conn)?;
+      inboxes.append(&mut Person::remote_inboxes(conn)?);
+      Ok::<_, diesel::result::Error>(inboxes)
+    })
+    .await??
+    .into_iter()
+    .map(|i: DbUrl| i.into())
+    .unique()
+    .collect();
+    send_lemmy_activity(context, &delete, &delete.id, actor, inboxes, true).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for DeleteUser {
+  type DataType = LemmyContext;
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_activity(self, &context.settings())?;
+    verify_urls_match(self.actor(), self.object.inner())?;
+    Ok(())
+  }
+
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    // The account is gone, so don't try to fetch it
+    let actor = self.actor.dereference_local(context).await?;
+    delete_user_account(actor.id, context.pool()).await
+  }
+}
@@ -13,7 +13,12 @@ use lemmy_apub_lib::{
   verify::verify_domains_match,
 };
 use lemmy_db_queries::source::{comment::Comment_, community::Community_, post::Post_};
-use lemmy_db_schema::source::{comment::Comment, community::Community, person::Person, post::Post};
+use lemmy_db_schema::source::{
+  comment::Comment,
+  community::{Community, ModPermission},
+  person::Person,
+  post::Post,
+};
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{
   send::{send_comment_ws_message_simple, send_community_ws_message, send_post_ws_message},
@@ -23,6 +28,7 @@ use lemmy_websocket::{
 use url::Url;
 
 pub mod delete;
+pub mod delete_user;
 pub mod undo_delete;
 
 pub async fn send_apub_delete(
@@ -117,6 +123,7 @@ pub(in crate::activities) async fn verify_delete_activity(
       verify_mod_action(
         &actor,
         ObjectId::new(c.actor_id()),
+        ModPermission::EditCommunity,
         context,
         request_counter,
       )
@@ -159,7 +166,14 @@ async fn verify_delete_activity_post_or_comment(
   let actor = ObjectId::new(activity.actor().clone());
   verify_person_in_community(&actor, community_id, context, request_counter).await?;
   if is_mod_action {
-    verify_mod_action(&actor, community_id.clone(), context, request_counter).await?;
+    verify_mod_action(
+      &actor,
+      community_id.clone(),
+      ModPermission::Remove,
+      context,
+      request_counter,
+    )
+    .await?;
   } else {
     // domain of post ap_id and post.creator ap_id are identical, so we just check the former
     verify_domains_match(activity.actor(), object_id)?;
@@ -7,7 +7,10 @@ use crate::{
 use anyhow::anyhow;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{traits::ActivityFields, verify::verify_domains_match};
-use lemmy_db_schema::source::{community::Community, person::Person};
+use lemmy_db_schema::source::{
+  community::{Community, ModPermission},
+  person::Person,
+};
 use lemmy_db_views_actor::community_view::CommunityView;
 use lemmy_utils::{settings::structs::Settings, LemmyError};
 use lemmy_websocket::LemmyContext;
@@ -20,6 +23,7 @@ pub mod comment;
 pub mod community;
 pub mod deletion;
 pub mod following;
+pub mod person;
 pub mod post;
 pub mod private_message;
 pub mod undo_remove;
@@ -95,9 +99,12 @@ fn verify_activity(activity: &dyn ActivityFields, settings: &Settings) -> Result
 /// Verify that the actor is a community mod. This check is only run if the community is local,
 /// because in case of remote communities, admins can also perform mod actions. As admin status
 /// is not federated, we cant verify their actions remotely.
+/// Mod permissions aren't federated. They can only be checked for local communities, remote ones
+/// are trusted to check them.
 pub(crate) async fn verify_mod_action(
   actor_id: &ObjectId<Person>,
   community_id: ObjectId<Community>,
+  permission: ModPermission,
   context: &LemmyContext,
   request_counter: &mut i32,
 ) -> Result<(), LemmyError> {
@@ -110,12 +117,12 @@ pub(crate) async fn verify_mod_action(
     //       remote admins, it doesnt make any difference.
     let community_id = community.id;
     let actor_id = actor.id;
-    let is_mod_or_admin = blocking(context.pool(), move |conn| {
-      CommunityView::is_mod_or_admin(conn, actor_id, community_id)
+    let has_permission = blocking(context.pool(), move |conn| {
+      CommunityView::has_mod_permission(conn, actor_id, community_id, permission)
     })
-    .await?;
-    if !is_mod_or_admin {
-      return Err(anyhow!("Not a mod").into());
+    .await??;
+    if !has_permission {
+      return Err(anyhow!("Not a mod, or missing mod permission").into());
     }
   }
   Ok(())
@@ -0,0 +1,381 @@
+use crate::{
+  activities::{
+    following::follow::FollowCommunity,
+    generate_activity_id,
+    verify_activity,
+    verify_person,
+  },
+  check_community_or_site_ban,
+  context::lemmy_context,
+  fetcher::object_id::ObjectId,
+  send_lemmy_activity,
+};
+use activitystreams::{
+  activity::kind::MoveType,
+  base::AnyBase,
+  primitives::OneOrMany,
+  unparsed::Unparsed,
+};
+use anyhow::anyhow;
+use itertools::Itertools;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  traits::{ActivityFields, ActivityHandler, ActorType},
+  values::PublicUrl,
+  verify::verify_urls_match,
+};
+use lemmy_db_queries::{source::person::Person_, Followable};
+use lemmy_db_schema::source::{
+  community::{Community, CommunityFollower, CommunityFollowerForm},
+  person::Person,
+};
+use lemmy_db_views_actor::community_follower_view::CommunityFollowerView;
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Upper limit for the number of communities which the new account follows after a move. Each of
+/// them may have to be fetched, and gets a follow activity.
+const MAX_MOVED_FOLLOWS: usize = 1000;
+
+/// Announces that an account moved to a different instance. The new account has to list the old
+/// one in `alsoKnownAs`.
+#[derive(Clone, Debug, Deserialize, Serialize, ActivityFields)]
+#[serde(rename_all = "camelCase")]
+pub struct MovePerson {
+  actor: ObjectId<Person>,
+  to: [PublicUrl; 1],
+  object: ObjectId<Person>,
+  target: ObjectId<Person>,
+  /// Lemmy extension: the communities followed by the old account, so that the new account can
+  /// follow them too.
+  #[serde(default)]
+  followed_communities: Vec<ObjectId<Community>>,
+  #[serde(rename = "type")]
+  kind: MoveType,
+  id: Url,
+  #[serde(rename = "@context")]
+  context: OneOrMany<AnyBase>,
+  #[serde(flatten)]
+  unparsed: Unparsed,
+}
+
+impl MovePerson {
+  /// Sends the move to the new account's instance, and to the instances of all communities which
+  /// the old account follows.
+  pub async fn send(
+    actor: &Person,
+    target: &Person,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let person_id = actor.id;
+    let followed_communities: Vec<ObjectId<Community>> = blocking(context.pool(), move |conn| {
+      CommunityFollowerView::for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .take(MAX_MOVED_FOLLOWS)
+    .map(|f| ObjectId::new(f.community.actor_id))
+    .collect();
+
+    let move_person = MovePerson {
+      actor: ObjectId::new(actor.actor_id()),
+      to: [PublicUrl::Public],
+      object: ObjectId::new(actor.actor_id()),
+      target: ObjectId::new(target.actor_id()),
+      followed_communities,
+      kind: MoveType::Move,
+      id: generate_activity_id(
+        MoveType::Move,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      context: lemmy_context(),
+      unparsed: Default::default(),
+    };
+
+    // Local inboxes are skipped when sending, so a move within this instance is handled here
+    if target.local {
+      refollow_communities(target, &move_person.followed_communities, context, &mut 0).await;
+    }
+
+    let mut inboxes = vec![target.shared_inbox_or_inbox_url()];
+    for community_id in &move_person.followed_communities {
+      let community = community_id.dereference_local(context).await?;
+      inboxes.push(community.shared_inbox_or_inbox_url());
+    }
+    let inboxes = inboxes.into_iter().unique().collect();
+    send_lemmy_activity(
+      context,
+      &move_person,
+      &move_person.id,
+      actor,
+      inboxes,
+      false,
+    )
+    .await
+  }
+}
+
+/// Checks that the new account lists the old one as alias. The new account is always refetched, in
+/// case the alias was added recently.
+pub async fn verify_move_target(
+  actor: &Person,
+  target: &ObjectId<Person>,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<Person, LemmyError> {
+  let target = target.dereference_refresh(context, request_counter).await?;
+  if target.id == actor.id || !target.also_known_as.contains(&actor.actor_id) {
+    return Err(
+      anyhow!(
+        "Move target {} doesn't have alias {}",
+        target.actor_id,
+        actor.actor_id
+      )
+      .into(),
+    );
+  }
+  Ok(target)
+}
+
+/// Lets the new account follow the given communities. Communities which can't be fetched, or where
+/// the new account is banned, are skipped. All fetches count towards the same `request_counter`,
+/// so once its limit is reached only communities which are already known get followed.
+async fn refollow_communities(
+  person: &Person,
+  communities: &[ObjectId<Community>],
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) {
+  for community_id in communities {
+    let res: Result<(), LemmyError> = async {
+      let community = community_id.dereference(context, request_counter).await?;
+      check_community_or_site_ban(person, community.id, context.pool()).await?;
+      if community.local {
+        let community_follower_form = CommunityFollowerForm {
+          community_id: community.id,
+          person_id: person.id,
+          pending: false,
+        };
+        // This will fail if they're already a follower, but ignore the error.
+        blocking(context.pool(), move |conn| {
+          CommunityFollower::follow(conn, &community_follower_form).ok()
+        })
+        .await?;
+      } else {
+        FollowCommunity::send(person, &community, context).await?;
+      }
+      Ok(())
+    }
+    .await;
+    if let Err(e) = res {
+      warn!(
+        "Failed to follow {} after account move: {}",
+        community_id, e
+      );
+    }
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for MovePerson {
+  type DataType = LemmyContext;
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_activity(self, &context.settings())?;
+    verify_urls_match(self.actor(), self.object.inner())?;
+    if self.followed_communities.len() > MAX_MOVED_FOLLOWS {
+      return Err(anyhow!("Move has too many followed communities").into());
+    }
+    verify_person(&self.actor, context, request_counter).await?;
+    let actor = self.actor.dereference(context, request_counter).await?;
+    verify_move_target(&actor, &self.target, context, request_counter).await?;
+    Ok(())
+  }
+
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let actor = self.actor.dereference(context, request_counter).await?;
+    let target = self.target.dereference(context, request_counter).await?;
+
+    let person_id = actor.id;
+    let moved_to = target.actor_id.clone();
+    blocking(context.pool(), move |conn| {
+      Person::set_moved_to(conn, person_id, Some(moved_to))
+    })
+    .await??;
+
+    // One of our users is the new account, so they take over the follows of the old one
+    if target.local {
+      refollow_communities(
+        &target,
+        &self.followed_communities,
+        context,
+        request_counter,
+      )
+      .await;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::activity_queue::InstanceHealthTracker;
+  use actix::Actor;
+  use background_jobs::memory_storage::Storage;
+  use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    PgConnection,
+  };
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_queries::{
+    establish_unpooled_connection,
+    get_database_url_from_env,
+    source::secret::Secret_,
+    Crud,
+  };
+  use lemmy_db_schema::source::{community::CommunityForm, person::PersonForm, secret::Secret};
+  use lemmy_utils::{
+    rate_limit::{rate_limiter::RateLimiter, RateLimit},
+    settings::structs::Settings,
+  };
+  use lemmy_websocket::chat_server::ChatServer;
+  use reqwest::Client;
+  use serial_test::serial;
+  use std::sync::Arc;
+  use tokio::sync::Mutex;
+
+  fn init_context() -> LemmyContext {
+    let manager = ConnectionManager::<PgConnection>::new(get_database_url_from_env().unwrap());
+    let pool = Pool::builder().max_size(1).build(manager).unwrap();
+    let secret = Secret::init(&establish_unpooled_connection()).unwrap();
+    let rate_limiter = RateLimit {
+      rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
+    };
+    let client = Client::new();
+    let activity_queue =
+      create_activity_queue(Storage::new(), InstanceHealthTracker::new(pool.clone()));
+    let chat_server = ChatServer::startup(
+      pool.clone(),
+      rate_limiter,
+      |_, _, _, _| Box::pin(async { Ok(String::new()) }),
+      |_, _, _, _| Box::pin(async { Ok(String::new()) }),
+      client.clone(),
+      activity_queue.clone(),
+      secret.clone(),
+    )
+    .start();
+    LemmyContext::create(pool, chat_server, client, activity_queue, secret)
+  }
+
+  /// Actor id on this instance, so that nothing is fetched over http
+  fn local_actor_id(path: &str) -> Url {
+    Url::parse(&format!(
+      "{}/{}",
+      Settings::get().get_protocol_and_hostname(),
+      path
+    ))
+    .unwrap()
+  }
+
+  fn create_person(conn: &PgConnection, name: &str, also_known_as: Vec<Url>) -> Person {
+    let person_form = PersonForm {
+      name: name.into(),
+      actor_id: Some(local_actor_id(&format!("u/{}", name)).into()),
+      also_known_as: Some(also_known_as.into_iter().map(Into::into).collect()),
+      ..PersonForm::default()
+    };
+    Person::create(conn, &person_form).unwrap()
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_verify_move_target() {
+    let conn = establish_unpooled_connection();
+    let context = init_context();
+
+    let old_account = create_person(&conn, "move_old", vec![]);
+    let no_alias = create_person(&conn, "move_no_alias", vec![]);
+    let new_account = create_person(&conn, "move_new", vec![old_account.actor_id.clone().into()]);
+    let self_alias = create_person(&conn, "move_self", vec![local_actor_id("u/move_self")]);
+
+    let to_no_alias = verify_move_target(
+      &old_account,
+      &ObjectId::new(no_alias.actor_id.clone()),
+      &context,
+      &mut 0,
+    )
+    .await;
+    let to_self = verify_move_target(
+      &self_alias,
+      &ObjectId::new(self_alias.actor_id.clone()),
+      &context,
+      &mut 0,
+    )
+    .await;
+    let to_new_account = verify_move_target(
+      &old_account,
+      &ObjectId::new(new_account.actor_id.clone()),
+      &context,
+      &mut 0,
+    )
+    .await;
+
+    for person in [&old_account, &no_alias, &new_account, &self_alias] {
+      Person::delete(&conn, person.id).unwrap();
+    }
+
+    assert!(to_no_alias.is_err());
+    assert!(to_self.is_err());
+    assert_eq!(new_account.id, to_new_account.unwrap().id);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_refollow_local_communities() {
+    let conn = establish_unpooled_connection();
+    let context = init_context();
+
+    let new_account = create_person(&conn, "refollow_new", vec![]);
+    let community_form = CommunityForm {
+      name: "refollow_community".into(),
+      title: "Refollow".into(),
+      actor_id: Some(local_actor_id("c/refollow_community").into()),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let missing_community = ObjectId::new(local_actor_id("c/refollow_missing"));
+
+    // The community which doesn't exist is skipped, the others are still followed
+    let mut request_counter = 0;
+    refollow_communities(
+      &new_account,
+      &[missing_community, ObjectId::new(community.actor_id.clone())],
+      &context,
+      &mut request_counter,
+    )
+    .await;
+    let follows = CommunityFollowerView::for_person(&conn, new_account.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, new_account.id).unwrap();
+
+    assert_eq!(
+      vec![community.id],
+      follows.iter().map(|f| f.community.id).collect::<Vec<_>>()
+    );
+    // Local communities are never fetched
+    assert_eq!(0, request_counter);
+  }
+}
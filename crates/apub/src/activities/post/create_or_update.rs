@@ -9,19 +9,27 @@ use crate::{
   },
   context::lemmy_context,
   fetcher::object_id::ObjectId,
-  objects::{post::Page, FromApub, ToApub},
+  objects::{
+    post::{post_to_page, Page},
+    FromApub,
+    ToApub,
+  },
 };
 use activitystreams::{base::AnyBase, primitives::OneOrMany, unparsed::Unparsed};
 use anyhow::anyhow;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
-  traits::{ActivityFields, ActivityHandler, ActorType},
+  traits::{ActivityFields, ActivityHandler, ActorType, ApubObject},
   values::PublicUrl,
   verify::{verify_domains_match, verify_urls_match},
 };
-use lemmy_db_queries::Crud;
-use lemmy_db_schema::source::{community::Community, person::Person, post::Post};
+use lemmy_db_queries::{source::post::PostRevision_, Crud};
+use lemmy_db_schema::source::{
+  community::{Community, ModPermission},
+  person::Person,
+  post::{Post, PostRevision},
+};
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
 use serde::{Deserialize, Serialize};
@@ -44,6 +52,45 @@ pub struct CreateOrUpdatePost {
 }
 
 impl CreateOrUpdatePost {
+  async fn new(
+    post: &Post,
+    actor: &Person,
+    community: &Community,
+    kind: CreateOrUpdateType,
+    id: Url,
+    context: &LemmyContext,
+  ) -> Result<Self, LemmyError> {
+    Ok(CreateOrUpdatePost {
+      actor: ObjectId::new(actor.actor_id()),
+      to: [PublicUrl::Public],
+      object: post.to_apub(context.pool()).await?,
+      cc: [ObjectId::new(community.actor_id())],
+      kind,
+      id,
+      context: lemmy_context(),
+      unparsed: Default::default(),
+    })
+  }
+
+  /// The `Create` activity of an existing post, for the outbox of its creator. Its id is derived
+  /// from the post id, so that it stays the same between requests.
+  pub(crate) fn for_outbox(
+    post: &Post,
+    actor: &Person,
+    community: &Community,
+  ) -> Result<Self, LemmyError> {
+    Ok(CreateOrUpdatePost {
+      actor: ObjectId::new(actor.actor_id()),
+      to: [PublicUrl::Public],
+      object: post_to_page(post, actor, community),
+      cc: [ObjectId::new(community.actor_id())],
+      kind: CreateOrUpdateType::Create,
+      id: Url::parse(&format!("{}#create", post.ap_id))?,
+      context: lemmy_context(),
+      unparsed: Default::default(),
+    })
+  }
+
   pub async fn send(
     post: &Post,
     actor: &Person,
@@ -60,16 +107,8 @@ impl CreateOrUpdatePost {
       kind.clone(),
       &context.settings().get_protocol_and_hostname(),
     )?;
-    let create_or_update = CreateOrUpdatePost {
-      actor: ObjectId::new(actor.actor_id()),
-      to: [PublicUrl::Public],
-      object: post.to_apub(context.pool()).await?,
-      cc: [ObjectId::new(community.actor_id())],
-      kind,
-      id: id.clone(),
-      context: lemmy_context(),
-      unparsed: Default::default(),
-    };
+    let create_or_update =
+      CreateOrUpdatePost::new(post, actor, &community, kind, id.clone(), context).await?;
 
     let activity = AnnouncableActivities::CreateOrUpdatePost(Box::new(create_or_update));
     send_to_community(activity, &id, actor, &community, vec![], context).await
@@ -104,7 +143,14 @@ impl ActivityHandler for CreateOrUpdatePost {
       CreateOrUpdateType::Update => {
         let is_mod_action = self.object.is_mod_action(context).await?;
         if is_mod_action {
-          verify_mod_action(&self.actor, self.cc[0].clone(), context, request_counter).await?;
+          verify_mod_action(
+            &self.actor,
+            self.cc[0].clone(),
+            ModPermission::Remove,
+            context,
+            request_counter,
+          )
+          .await?;
         } else {
           verify_domains_match(self.actor.inner(), self.object.id_unchecked())?;
           verify_urls_match(self.actor(), self.object.attributed_to.inner())?;
@@ -121,7 +167,24 @@ impl ActivityHandler for CreateOrUpdatePost {
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
     let actor = self.actor.dereference(context, request_counter).await?;
+    let old_post = match self.kind {
+      CreateOrUpdateType::Create => None,
+      CreateOrUpdateType::Update => {
+        let object_id = self.object.id_unchecked().clone();
+        blocking(context.pool(), move |conn| {
+          Post::read_from_apub_id(conn, object_id)
+        })
+        .await??
+      }
+    };
     let post = Post::from_apub(&self.object, context, &actor.actor_id(), request_counter).await?;
+    if let Some(old_post) = old_post {
+      let new_post = post.clone();
+      blocking(context.pool(), move |conn| {
+        PostRevision::create_if_changed(conn, &old_post, &new_post)
+      })
+      .await??;
+    }
 
     let notif_type = match self.kind {
       CreateOrUpdateType::Create => UserOperationCrud::CreatePost,
@@ -0,0 +1,227 @@
+use anyhow::Context;
+use background_jobs::{
+  dev::{JobInfo, Storage},
+  Stats,
+};
+use chrono::Duration;
+use diesel::{OptionalExtension, PgConnection};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::activity_queue::DeliveryTracker;
+use lemmy_db_queries::{
+  source::{activity_queue_job::ActivityQueueJob_, instance_health::InstanceHealth_},
+  DbPool,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::{
+    activity_queue_job::{ActivityQueueJob, ActivityQueueJobForm},
+    instance_health::InstanceHealth,
+  },
+};
+use lemmy_utils::{location_info, settings::structs::Settings, LemmyError};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ActivityQueueStorageError(LemmyError);
+
+impl ActivityQueueStorageError {
+  fn new<E: Into<LemmyError>>(e: E) -> Self {
+    ActivityQueueStorageError(e.into())
+  }
+}
+
+/// Job storage for the activity queue, which keeps outgoing activities in the database. This way
+/// pending deliveries and their retry state are not lost when the server restarts.
+#[derive(Clone)]
+pub struct ActivityQueueStorage {
+  pool: DbPool,
+  stats: Arc<Mutex<Stats>>,
+}
+
+impl ActivityQueueStorage {
+  /// Jobs which were taken out of the queue, but never finished (because the server was stopped
+  /// in the meantime) are put back into their queue, so that they get delivered.
+  pub async fn init(pool: DbPool) -> Result<Self, LemmyError> {
+    blocking(&pool, ActivityQueueJob::requeue_unfinished).await??;
+    Ok(ActivityQueueStorage {
+      pool,
+      stats: Arc::new(Mutex::new(Stats::default())),
+    })
+  }
+
+  async fn run<F, T>(&self, f: F) -> Result<T, ActivityQueueStorageError>
+  where
+    F: FnOnce(&PgConnection) -> Result<T, diesel::result::Error> + Send + 'static,
+    T: Send + 'static,
+  {
+    blocking(&self.pool, f)
+      .await
+      .map_err(ActivityQueueStorageError)?
+      .map_err(ActivityQueueStorageError::new)
+  }
+}
+
+fn job_from_db(job: ActivityQueueJob) -> Result<JobInfo, ActivityQueueStorageError> {
+  serde_json::from_value(job.data).map_err(ActivityQueueStorageError::new)
+}
+
+#[async_trait::async_trait]
+impl Storage for ActivityQueueStorage {
+  type Error = ActivityQueueStorageError;
+
+  async fn generate_id(&self) -> Result<Uuid, Self::Error> {
+    Ok(Uuid::new_v4())
+  }
+
+  async fn save_job(&self, job: JobInfo) -> Result<(), Self::Error> {
+    let data = serde_json::to_value(&job).map_err(ActivityQueueStorageError::new)?;
+    let form = ActivityQueueJobForm {
+      job_id: job.id().to_string(),
+      queue: job.queue().to_string(),
+      status: data["status"].as_str().unwrap_or_default().to_string(),
+      retry_count: data["retry_count"].as_i64().unwrap_or_default() as i32,
+      next_queue: Some(job.next_queue().map(|t| t.naive_utc())),
+      data,
+      updated: Some(naive_now()),
+    };
+    self
+      .run(move |conn| ActivityQueueJob::upsert(conn, &form))
+      .await?;
+    Ok(())
+  }
+
+  async fn fetch_job(&self, id: Uuid) -> Result<Option<JobInfo>, Self::Error> {
+    let job_id = id.to_string();
+    let job = self
+      .run(move |conn| ActivityQueueJob::read_from_job_id(conn, &job_id).optional())
+      .await?;
+    job.map(job_from_db).transpose()
+  }
+
+  async fn fetch_job_from_queue(&self, queue: &str) -> Result<Option<JobInfo>, Self::Error> {
+    let queue = queue.to_string();
+    let job = self
+      .run(move |conn| ActivityQueueJob::claim_from_queue(conn, &queue))
+      .await?;
+    job.map(job_from_db).transpose()
+  }
+
+  async fn queue_job(&self, queue: &str, id: Uuid) -> Result<(), Self::Error> {
+    let queue = queue.to_string();
+    let job_id = id.to_string();
+    self
+      .run(move |conn| ActivityQueueJob::enqueue(conn, &job_id, &queue))
+      .await?;
+    Ok(())
+  }
+
+  async fn run_job(&self, id: Uuid, runner_id: Uuid) -> Result<(), Self::Error> {
+    let job_id = id.to_string();
+    let runner_id = runner_id.to_string();
+    self
+      .run(move |conn| ActivityQueueJob::set_runner(conn, &job_id, &runner_id))
+      .await?;
+    Ok(())
+  }
+
+  async fn delete_job(&self, id: Uuid) -> Result<(), Self::Error> {
+    let job_id = id.to_string();
+    self
+      .run(move |conn| ActivityQueueJob::delete_from_job_id(conn, &job_id))
+      .await?;
+    Ok(())
+  }
+
+  /// The number of pending and running jobs is read from the database, the rest of the stats only
+  /// cover the time since the last restart.
+  async fn get_stats(&self) -> Result<Stats, Self::Error> {
+    let mut stats = self.stats.lock().await.clone();
+    let (pending, running) = self
+      .run(move |conn| {
+        Ok((
+          ActivityQueueJob::count_pending(conn)?,
+          ActivityQueueJob::count_running(conn)?,
+        ))
+      })
+      .await?;
+    stats.pending = pending as usize;
+    stats.running = running as usize;
+    Ok(stats)
+  }
+
+  async fn update_stats<F>(&self, f: F) -> Result<(), Self::Error>
+  where
+    F: Fn(Stats) -> Stats + Send + 'static,
+  {
+    let mut stats = self.stats.lock().await;
+    *stats = (f)(stats.clone());
+    Ok(())
+  }
+}
+
+/// Stores the delivery results for each remote instance in the database. Instances which couldn't
+/// be reached for longer than `federation.dead_instance_days` only receive an occasional probe,
+/// until one of them succeeds.
+pub struct InstanceHealthTracker {
+  pool: DbPool,
+}
+
+impl InstanceHealthTracker {
+  pub fn new(pool: DbPool) -> Self {
+    InstanceHealthTracker { pool }
+  }
+}
+
+/// Host of the inbox including a non-default port, eg `lemmy-alpha:8541`. Unlike `Url::domain()`
+/// this also works for IP literals.
+fn inbox_domain(inbox: &Url) -> Result<String, LemmyError> {
+  let host = inbox.host_str().context(location_info!())?;
+  Ok(match inbox.port() {
+    Some(port) => format!("{}:{}", host, port),
+    None => host.to_string(),
+  })
+}
+
+#[async_trait::async_trait(?Send)]
+impl DeliveryTracker for InstanceHealthTracker {
+  async fn should_deliver(&self, inbox: &Url) -> Result<bool, LemmyError> {
+    let domain = inbox_domain(inbox)?;
+    let federation = Settings::get().federation;
+    let dead_after = Duration::days(federation.dead_instance_days.into());
+    let probe_interval = Duration::minutes(federation.dead_instance_probe_interval.into());
+    blocking(&self.pool, move |conn| {
+      let health = InstanceHealth::read_from_domain(conn, &domain).optional()?;
+      match health {
+        Some(h) if h.is_dead(dead_after) => {
+          InstanceHealth::claim_probe(conn, &domain, probe_interval)
+        }
+        _ => Ok(true),
+      }
+    })
+    .await?
+    .map_err(LemmyError::from)
+  }
+
+  async fn delivery_succeeded(&self, inbox: &Url) -> Result<(), LemmyError> {
+    let domain = inbox_domain(inbox)?;
+    blocking(&self.pool, move |conn| {
+      InstanceHealth::record_success(conn, &domain)
+    })
+    .await??;
+    Ok(())
+  }
+
+  async fn delivery_failed(&self, inbox: &Url) -> Result<(), LemmyError> {
+    let domain = inbox_domain(inbox)?;
+    blocking(&self.pool, move |conn| {
+      InstanceHealth::record_failure(conn, &domain)
+    })
+    .await??;
+    Ok(())
+  }
+}
@@ -18,6 +18,14 @@ pub(crate) fn lemmy_context() -> OneOrMany<AnyBase> {
       "type": "sc:Text",
       "id": "as:alsoKnownAs"
     },
+    "alsoKnownAs": {
+      "type": "@id",
+      "id": "as:alsoKnownAs"
+    },
+    "movedTo": {
+      "type": "@id",
+      "id": "as:movedTo"
+    },
   }))
   .expect("parse context");
   OneOrMany::from(vec![
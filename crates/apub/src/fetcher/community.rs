@@ -3,8 +3,11 @@ use crate::{
   fetcher::{fetch::fetch_remote_object, object_id::ObjectId},
   objects::community::Group,
 };
-use activitystreams::collection::{CollectionExt, OrderedCollection};
-use anyhow::Context;
+use activitystreams::{
+  base::AnyBase,
+  collection::{CollectionExt, CollectionPageExt, OrderedCollection, OrderedCollectionPage},
+  primitives::OneOrMany,
+};
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{data::Data, traits::ActivityHandler};
 use lemmy_db_queries::Joinable;
@@ -13,7 +16,7 @@ use lemmy_db_schema::source::{
   person::Person,
 };
 use lemmy_db_views_actor::community_moderator_view::CommunityModeratorView;
-use lemmy_utils::{location_info, LemmyError};
+use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
 use url::Url;
 
@@ -73,18 +76,7 @@ pub(crate) async fn fetch_community_outbox(
   outbox: &Url,
   recursion_counter: &mut i32,
 ) -> Result<(), LemmyError> {
-  let outbox = fetch_remote_object::<OrderedCollection>(
-    context.client(),
-    &context.settings(),
-    outbox,
-    recursion_counter,
-  )
-  .await?;
-  let outbox_activities = outbox.items().context(location_info!())?.clone();
-  let mut outbox_activities = outbox_activities.many().context(location_info!())?;
-  if outbox_activities.len() > 20 {
-    outbox_activities = outbox_activities[0..20].to_vec();
-  }
+  let outbox_activities = fetch_collection_items(context, outbox, 20, recursion_counter).await?;
 
   for announce in outbox_activities {
     // TODO: instead of converting like this, we should create a struct CommunityOutbox with
@@ -105,18 +97,8 @@ async fn fetch_community_mods(
   recursion_counter: &mut i32,
 ) -> Result<Vec<Url>, LemmyError> {
   if let Some(mods_url) = &group.moderators {
-    let mods = fetch_remote_object::<OrderedCollection>(
-      context.client(),
-      &context.settings(),
-      mods_url,
-      recursion_counter,
-    )
-    .await?;
-    let mods = mods
-      .items()
-      .map(|i| i.as_many())
-      .flatten()
-      .context(location_info!())?
+    let mods = fetch_collection_items(context, mods_url, MAX_MODERATORS, recursion_counter)
+      .await?
       .iter()
       .filter_map(|i| i.as_xsd_any_uri())
       .map(|u| u.to_owned())
@@ -126,3 +108,60 @@ async fn fetch_community_mods(
     Ok(vec![])
   }
 }
+
+/// Upper limit for the number of moderators which are read from a remote community
+const MAX_MODERATORS: usize = 100;
+
+/// Reads up to `max_items` items from a remote collection. Lemmy includes the first page of items
+/// in the collection itself. If that isn't enough, the pages are fetched one by one, starting from
+/// `first` and following the `next` links.
+async fn fetch_collection_items(
+  context: &LemmyContext,
+  url: &Url,
+  max_items: usize,
+  recursion_counter: &mut i32,
+) -> Result<Vec<AnyBase>, LemmyError> {
+  let collection = fetch_remote_object::<OrderedCollection>(
+    context.client(),
+    &context.settings(),
+    url,
+    recursion_counter,
+  )
+  .await?;
+  let mut items = collection_items(collection.items().or_else(|| collection.ordered_items()));
+  let wanted_items = collection
+    .total_items()
+    .map(|t| t as usize)
+    .unwrap_or_default()
+    .min(max_items);
+
+  let first_page = collection.first().and_then(|f| f.as_xsd_any_uri()).cloned();
+  // Without a link to the pages, the inline items are all there is
+  if items.len() < wanted_items && first_page.is_some() {
+    items.clear();
+    let mut next_page = first_page;
+    while let Some(page_url) = next_page {
+      if items.len() >= max_items {
+        break;
+      }
+      let page = fetch_remote_object::<OrderedCollectionPage>(
+        context.client(),
+        &context.settings(),
+        &page_url,
+        recursion_counter,
+      )
+      .await?;
+      items.append(&mut collection_items(page.ordered_items()));
+      next_page = page.next().and_then(|n| n.as_xsd_any_uri()).cloned();
+    }
+  }
+
+  items.truncate(max_items);
+  Ok(items)
+}
+
+fn collection_items(items: Option<&OneOrMany<AnyBase>>) -> Vec<AnyBase> {
+  items
+    .map(|i| i.iter().cloned().collect())
+    .unwrap_or_default()
+}
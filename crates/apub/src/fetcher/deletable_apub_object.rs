@@ -1,11 +1,6 @@
 use crate::fetcher::post_or_comment::PostOrComment;
-use lemmy_api_common::blocking;
-use lemmy_db_queries::source::{
-  comment::Comment_,
-  community::Community_,
-  person::Person_,
-  post::Post_,
-};
+use lemmy_api_common::{blocking, delete_user_account};
+use lemmy_db_queries::source::{comment::Comment_, community::Community_, post::Post_};
 use lemmy_db_schema::source::{
   comment::Comment,
   community::Community,
@@ -38,9 +33,7 @@ impl DeletableApubObject for Community {
 #[async_trait::async_trait(?Send)]
 impl DeletableApubObject for Person {
   async fn delete(self, context: &LemmyContext) -> Result<(), LemmyError> {
-    let id = self.id;
-    blocking(context.pool(), move |conn| Person::delete_account(conn, id)).await??;
-    Ok(())
+    delete_user_account(self.id, context.pool()).await
   }
 }
 
@@ -78,6 +78,22 @@ where
     }
   }
 
+  /// Like `dereference()`, but always fetches remote objects over http, so that they are up to
+  /// date.
+  pub async fn dereference_refresh(
+    &self,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<Kind, LemmyError> {
+    let db_object = self.dereference_from_db(context.pool()).await?;
+    if self.0.domain() == Some(&Settings::get().get_hostname_without_port()?) {
+      return db_object.ok_or_else(|| NotFound {}.into());
+    }
+    self
+      .dereference_from_http(context, request_counter, db_object)
+      .await
+  }
+
   /// Fetch an object from the local db. Instead of falling back to http, this throws an error if
   /// the object is not found in the database.
   pub async fn dereference_local(&self, context: &LemmyContext) -> Result<Kind, LemmyError> {
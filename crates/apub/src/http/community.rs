@@ -8,17 +8,18 @@ use crate::{
   generate_moderators_url,
   generate_outbox_url,
   http::{
+    create_apub_collection_response,
     create_apub_response,
     create_apub_tombstone_response,
     payload_to_string,
     receive_activity,
+    CollectionQuery,
   },
   objects::ToApub,
 };
 use activitystreams::{
   base::{AnyBase, BaseExt},
   collection::{CollectionExt, OrderedCollection, UnorderedCollection},
-  url::Url,
 };
 use actix_web::{body::Body, web, web::Payload, HttpRequest, HttpResponse};
 use lemmy_api_common::blocking;
@@ -108,8 +109,8 @@ pub(crate) async fn get_apub_community_followers(
   .await??;
 
   let community_id = community.id;
-  let community_followers = blocking(context.pool(), move |conn| {
-    CommunityFollowerView::for_community(conn, community_id)
+  let total_items = blocking(context.pool(), move |conn| {
+    CommunityFollowerView::count_for_community(conn, community_id)
   })
   .await??;
 
@@ -117,14 +118,15 @@ pub(crate) async fn get_apub_community_followers(
   collection
     .set_many_contexts(lemmy_context())
     .set_id(community.followers_url.into())
-    .set_total_items(community_followers.len() as u64);
+    .set_total_items(total_items as u64);
   Ok(create_apub_response(&collection))
 }
 
-/// Returns the community outbox, which is populated by a maximum of 20 posts (but no other
+/// Returns the community outbox, which is populated with the posts of the community (but no other
 /// activites like votes or comments).
 pub(crate) async fn get_apub_community_outbox(
   info: web::Path<CommunityQuery>,
+  query: web::Query<CollectionQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse<Body>, LemmyError> {
   let community = blocking(context.pool(), move |conn| {
@@ -133,23 +135,29 @@ pub(crate) async fn get_apub_community_outbox(
   .await??;
 
   let community_actor_id = community.actor_id.to_owned();
-  let activities = blocking(context.pool(), move |conn| {
-    Activity::read_community_outbox(conn, &community_actor_id)
+  let total_items = blocking(context.pool(), move |conn| {
+    Activity::count_community_outbox(conn, &community_actor_id)
   })
   .await??;
 
-  let activities = activities
-    .iter()
-    .map(AnyBase::from_arbitrary_json)
-    .collect::<Result<Vec<AnyBase>, serde_json::Error>>()?;
-  let len = activities.len();
-  let mut collection = OrderedCollection::new();
-  collection
-    .set_many_items(activities)
-    .set_many_contexts(lemmy_context())
-    .set_id(generate_outbox_url(&community.actor_id)?.into())
-    .set_total_items(len as u64);
-  Ok(create_apub_response(&collection))
+  create_apub_collection_response(
+    generate_outbox_url(&community.actor_id)?.into(),
+    query.into_inner(),
+    total_items,
+    |limit, offset| async move {
+      let activities = blocking(context.pool(), move |conn| {
+        Activity::read_community_outbox(conn, &community.actor_id, limit, offset)
+      })
+      .await??;
+      Ok(
+        activities
+          .iter()
+          .map(AnyBase::from_arbitrary_json)
+          .collect::<Result<Vec<AnyBase>, serde_json::Error>>()?,
+      )
+    },
+  )
+  .await
 }
 
 pub(crate) async fn get_apub_community_inbox(
@@ -170,6 +178,7 @@ pub(crate) async fn get_apub_community_inbox(
 
 pub(crate) async fn get_apub_community_moderators(
   info: web::Path<CommunityQuery>,
+  query: web::Query<CollectionQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse<Body>, LemmyError> {
   let community = blocking(context.pool(), move |conn| {
@@ -182,20 +191,27 @@ pub(crate) async fn get_apub_community_moderators(
   // TODO Technically the instance admins can mod the community, but lets
   // ignore that for now
   let cid = community.id;
-  let moderators = blocking(context.pool(), move |conn| {
-    CommunityModeratorView::for_community(conn, cid)
+  let total_items = blocking(context.pool(), move |conn| {
+    CommunityModeratorView::count_for_community(conn, cid)
   })
   .await??;
 
-  let moderators: Vec<Url> = moderators
-    .into_iter()
-    .map(|m| m.moderator.actor_id.into())
-    .collect();
-  let mut collection = OrderedCollection::new();
-  collection
-    .set_id(generate_moderators_url(&community.actor_id)?.into())
-    .set_total_items(moderators.len() as u64)
-    .set_many_items(moderators)
-    .set_many_contexts(lemmy_context());
-  Ok(create_apub_response(&collection))
+  create_apub_collection_response(
+    generate_moderators_url(&community.actor_id)?.into(),
+    query.into_inner(),
+    total_items,
+    |limit, offset| async move {
+      let moderators = blocking(context.pool(), move |conn| {
+        CommunityModeratorView::for_community_paged(conn, cid, limit, offset)
+      })
+      .await??;
+      Ok(
+        moderators
+          .into_iter()
+          .map(|m| AnyBase::from_xsd_any_uri(m.moderator.actor_id.into()))
+          .collect(),
+      )
+    },
+  )
+  .await
 }
@@ -1,5 +1,6 @@
 use crate::{
   check_is_apub_id_valid,
+  context::lemmy_context,
   fetcher::get_or_fetch_and_upsert_actor,
   http::{
     community::{receive_group_inbox, GroupInboxActivities},
@@ -7,6 +8,10 @@ use crate::{
   },
   insert_activity,
 };
+use activitystreams::{
+  base::{AnyBase, BaseExt},
+  collection::{CollectionExt, CollectionPageExt, OrderedCollection, OrderedCollectionPage},
+};
 use actix_web::{
   body::Body,
   web,
@@ -24,13 +29,13 @@ use lemmy_apub_lib::{
   traits::{ActivityFields, ActivityHandler},
   APUB_JSON_CONTENT_TYPE,
 };
-use lemmy_db_queries::{source::activity::Activity_, DbPool};
+use lemmy_db_queries::{limit_and_offset, source::activity::Activity_, DbPool};
 use lemmy_db_schema::source::activity::Activity;
 use lemmy_utils::{location_info, LemmyError};
 use lemmy_websocket::LemmyContext;
 use log::{info, trace};
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, io::Read};
+use std::{fmt::Debug, future::Future, io::Read};
 use url::Url;
 
 mod comment;
@@ -148,6 +153,63 @@ where
     .json(data)
 }
 
+/// Number of items on each page of a paginated collection
+const COLLECTION_PAGE_SIZE: i64 = 20;
+
+#[derive(Deserialize)]
+pub(crate) struct CollectionQuery {
+  page: Option<i64>,
+}
+
+/// Returns a paginated `OrderedCollection`. Without a page number, the collection itself is
+/// returned with the total number of items and a link to the first page. For compatibility with
+/// older Lemmy versions, which don't follow pages, it also contains the items of the first page.
+///
+/// `read_page` is called with limit and offset to load the items of the requested page.
+async fn create_apub_collection_response<F, Fut>(
+  id: Url,
+  query: CollectionQuery,
+  total_items: i64,
+  read_page: F,
+) -> Result<HttpResponse<Body>, LemmyError>
+where
+  F: FnOnce(i64, i64) -> Fut,
+  Fut: Future<Output = Result<Vec<AnyBase>, LemmyError>>,
+{
+  // Requests for pages beyond the end return the last page, which also keeps the offset from
+  // overflowing
+  let last_page = (total_items - 1).max(0) / COLLECTION_PAGE_SIZE + 1;
+  let page = query.page.unwrap_or(1).clamp(1, last_page);
+  let (limit, offset) = limit_and_offset(Some(page), Some(COLLECTION_PAGE_SIZE));
+  let items = read_page(limit, offset).await?;
+
+  if query.page.is_none() {
+    let mut collection = OrderedCollection::new();
+    collection
+      .set_many_contexts(lemmy_context())
+      .set_id(id.clone())
+      .set_total_items(total_items as u64)
+      .set_first(collection_page_url(&id, 1)?)
+      .set_many_items(items);
+    return Ok(create_apub_response(&collection));
+  }
+
+  let mut collection_page = OrderedCollectionPage::new();
+  collection_page
+    .set_many_contexts(lemmy_context())
+    .set_id(collection_page_url(&id, page)?)
+    .set_part_of(id.clone())
+    .set_many_ordered_items(items);
+  if offset + limit < total_items {
+    collection_page.set_next(collection_page_url(&id, page + 1)?);
+  }
+  Ok(create_apub_response(&collection_page))
+}
+
+fn collection_page_url(id: &Url, page: i64) -> Result<Url, LemmyError> {
+  Ok(Url::parse(&format!("{}?page={}", id, page))?)
+}
+
 #[derive(Deserialize)]
 pub struct ActivityQuery {
   type_: String,
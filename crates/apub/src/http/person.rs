@@ -1,7 +1,11 @@
 use crate::{
   activities::{
+    comment::create_or_update::CreateOrUpdateComment,
     community::announce::{AnnouncableActivities, AnnounceActivity},
+    deletion::delete_user::DeleteUser,
     following::accept::AcceptFollowCommunity,
+    person::move_person::MovePerson,
+    post::create_or_update::CreateOrUpdatePost,
     private_message::{
       create_or_update::CreateOrUpdatePrivateMessage,
       delete::DeletePrivateMessage,
@@ -11,27 +15,42 @@ use crate::{
   context::lemmy_context,
   generate_outbox_url,
   http::{
+    create_apub_collection_response,
     create_apub_response,
     create_apub_tombstone_response,
     payload_to_string,
     receive_activity,
+    CollectionQuery,
   },
   objects::ToApub,
 };
 use activitystreams::{
-  base::BaseExt,
-  collection::{CollectionExt, OrderedCollection},
+  base::{AnyBase, BaseExt},
+  collection::OrderedCollection,
 };
 use actix_web::{body::Body, web, web::Payload, HttpRequest, HttpResponse};
+use anyhow::Context;
+use diesel::PgConnection;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::traits::{ActivityFields, ActivityHandler};
-use lemmy_db_queries::source::person::Person_;
-use lemmy_db_schema::source::person::Person;
-use lemmy_utils::LemmyError;
+use lemmy_db_queries::source::{
+  comment::Comment_,
+  community::Community_,
+  person::{PersonOutboxItem, Person_},
+  post::Post_,
+};
+use lemmy_db_schema::{
+  source::{comment::Comment, community::Community, person::Person, post::Post},
+  CommentId,
+  CommunityId,
+  PersonId,
+  PostId,
+};
+use lemmy_utils::{location_info, LemmyError};
 use lemmy_websocket::LemmyContext;
 use log::trace;
 use serde::{Deserialize, Serialize};
-use url::Url;
+use std::collections::HashMap;
 
 #[derive(Deserialize)]
 pub struct PersonQuery {
@@ -44,9 +63,8 @@ pub(crate) async fn get_apub_person_http(
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse<Body>, LemmyError> {
   let user_name = info.into_inner().user_name;
-  // TODO: this needs to be able to read deleted persons, so that it can send tombstones
   let person = blocking(context.pool(), move |conn| {
-    Person::find_by_name(conn, &user_name)
+    Person::find_by_name_include_deleted(conn, &user_name)
   })
   .await??;
 
@@ -69,6 +87,8 @@ pub enum PersonInboxActivities {
   CreateOrUpdatePrivateMessage(CreateOrUpdatePrivateMessage),
   DeletePrivateMessage(DeletePrivateMessage),
   UndoDeletePrivateMessage(UndoDeletePrivateMessage),
+  MovePerson(MovePerson),
+  DeleteUser(DeleteUser),
   AnnounceActivity(Box<AnnounceActivity>),
 }
 
@@ -92,22 +112,135 @@ pub(in crate::http) async fn receive_person_inbox(
   receive_activity(request, activity, context).await
 }
 
+/// Returns the posts and comments created by the person, as `Create` activities.
 pub(crate) async fn get_apub_person_outbox(
   info: web::Path<PersonQuery>,
+  query: web::Query<CollectionQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse<Body>, LemmyError> {
   let person = blocking(context.pool(), move |conn| {
     Person::find_by_name(conn, &info.user_name)
   })
   .await??;
-  // TODO: populate the person outbox
-  let mut collection = OrderedCollection::new();
-  collection
-    .set_many_items(Vec::<Url>::new())
-    .set_many_contexts(lemmy_context())
-    .set_id(generate_outbox_url(&person.actor_id)?.into())
-    .set_total_items(0_u64);
-  Ok(create_apub_response(&collection))
+
+  let person_id = person.id;
+  let total_items = blocking(context.pool(), move |conn| {
+    Person::count_outbox(conn, person_id)
+  })
+  .await??;
+
+  let outbox_url = generate_outbox_url(&person.actor_id)?.into();
+  create_apub_collection_response(
+    outbox_url,
+    query.into_inner(),
+    total_items,
+    |limit, offset| async move {
+      let page = blocking(context.pool(), move |conn| {
+        OutboxPage::read(conn, person_id, limit, offset)
+      })
+      .await??;
+      page.to_activities(&person)
+    },
+  )
+  .await
+}
+
+/// A page of a person's outbox, with the posts, comments and communities it refers to read in
+/// one query each.
+struct OutboxPage {
+  items: Vec<PersonOutboxItem>,
+  posts: HashMap<PostId, Post>,
+  comments: HashMap<CommentId, Comment>,
+  communities: HashMap<CommunityId, Community>,
+}
+
+impl OutboxPage {
+  fn read(
+    conn: &PgConnection,
+    person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Self, LemmyError> {
+    let items = Person::read_outbox(conn, person_id, limit, offset)?;
+    let mut post_ids = vec![];
+    let mut comment_ids = vec![];
+    for item in &items {
+      match item {
+        PersonOutboxItem::Post(post_id) => post_ids.push(*post_id),
+        PersonOutboxItem::Comment(comment_id) => comment_ids.push(*comment_id),
+      }
+    }
+
+    let mut comments = Comment::read_many(conn, &comment_ids)?;
+    // The parent comments are needed for `inReplyTo`, they are in the same post
+    let parent_ids = comments
+      .iter()
+      .filter_map(|c| c.parent_id)
+      .collect::<Vec<CommentId>>();
+    comments.extend(Comment::read_many(conn, &parent_ids)?);
+    post_ids.extend(comments.iter().map(|c| c.post_id));
+    let posts = Post::read_many(conn, &post_ids)?;
+    let community_ids = posts
+      .iter()
+      .map(|p| p.community_id)
+      .collect::<Vec<CommunityId>>();
+    let communities = Community::read_many(conn, &community_ids)?;
+
+    Ok(OutboxPage {
+      items,
+      posts: posts.into_iter().map(|p| (p.id, p)).collect(),
+      comments: comments.into_iter().map(|c| (c.id, c)).collect(),
+      communities: communities.into_iter().map(|c| (c.id, c)).collect(),
+    })
+  }
+
+  fn to_activities(&self, person: &Person) -> Result<Vec<AnyBase>, LemmyError> {
+    let mut activities = vec![];
+    for item in &self.items {
+      let activity = match item {
+        PersonOutboxItem::Post(post_id) => {
+          let post = self.post(*post_id)?;
+          let community = self.community(post.community_id)?;
+          serde_json::to_value(CreateOrUpdatePost::for_outbox(post, person, community)?)?
+        }
+        PersonOutboxItem::Comment(comment_id) => {
+          let comment = self.comment(*comment_id)?;
+          let post = self.post(comment.post_id)?;
+          let parent_comment = comment
+            .parent_id
+            .map(|parent_id| self.comment(parent_id))
+            .transpose()?;
+          let community = self.community(post.community_id)?;
+          serde_json::to_value(CreateOrUpdateComment::for_outbox(
+            comment,
+            person,
+            post,
+            parent_comment,
+            community,
+          )?)?
+        }
+      };
+      activities.push(AnyBase::from_arbitrary_json(activity)?);
+    }
+    Ok(activities)
+  }
+
+  fn post(&self, post_id: PostId) -> Result<&Post, LemmyError> {
+    Ok(self.posts.get(&post_id).context(location_info!())?)
+  }
+
+  fn comment(&self, comment_id: CommentId) -> Result<&Comment, LemmyError> {
+    Ok(self.comments.get(&comment_id).context(location_info!())?)
+  }
+
+  fn community(&self, community_id: CommunityId) -> Result<&Community, LemmyError> {
+    Ok(
+      self
+        .communities
+        .get(&community_id)
+        .context(location_info!())?,
+    )
+  }
 }
 
 pub(crate) async fn get_apub_person_inbox(
@@ -23,7 +23,7 @@ pub(crate) async fn get_apub_post(
 ) -> Result<HttpResponse<Body>, LemmyError> {
   let id = PostId(info.post_id.parse::<i32>()?);
   let post = blocking(context.pool(), move |conn| Post::read(conn, id)).await??;
-  if !post.local {
+  if !post.local || post.scheduled_publish_time.is_some() {
     return Err(NotFound.into());
   }
 
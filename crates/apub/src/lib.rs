@@ -1,4 +1,5 @@
 pub mod activities;
+pub mod activity_queue;
 mod context;
 pub mod fetcher;
 pub mod http;
@@ -7,7 +8,7 @@ pub mod objects;
 
 use crate::fetcher::post_or_comment::PostOrComment;
 use anyhow::{anyhow, Context};
-use lemmy_api_common::blocking;
+use lemmy_api_common::{blocking, federated_instance_lists};
 use lemmy_apub_lib::{activity_queue::send_activity, traits::ActorType};
 use lemmy_db_queries::{source::activity::Activity_, DbPool};
 use lemmy_db_schema::{
@@ -66,21 +67,20 @@ pub(crate) fn check_is_apub_id_valid(
   // TODO: might be good to put the part above in one method, and below in another
   //       (which only gets called in apub::objects)
   //        -> no that doesnt make sense, we still need the code below for blocklist and strict allowlist
-  if let Some(blocked) = settings.to_owned().federation.blocked_instances {
+  let lists = federated_instance_lists();
+  if let Some(blocked) = &lists.blocked {
     if blocked.contains(&domain) {
       return Err(anyhow!("{} is in federation blocklist", domain).into());
     }
   }
 
-  if let Some(mut allowed) = settings.to_owned().federation.allowed_instances {
+  if let Some(allowed) = &lists.allowed {
     // Only check allowlist if this is a community, or strict allowlist is enabled.
-    let strict_allowlist = settings.to_owned().federation.strict_allowlist;
+    let strict_allowlist = settings.federation.strict_allowlist;
     if use_strict_allowlist || strict_allowlist {
       // need to allow this explicitly because apub receive might contain objects from our local
       // instance.
-      allowed.push(local_instance);
-
-      if !allowed.contains(&domain) {
+      if domain != local_instance && !allowed.contains(&domain) {
         return Err(anyhow!("{} not in federation allowlist", domain).into());
       }
     }
@@ -249,7 +249,7 @@ pub(crate) async fn send_lemmy_activity<T: Serialize>(
 
   insert_activity(
     activity_id,
-    serialised_activity.clone(),
+    serde_json::to_value(activity)?,
     true,
     sensitive,
     context.pool(),
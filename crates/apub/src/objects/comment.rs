@@ -14,7 +14,7 @@ use activitystreams::{
 };
 use anyhow::{anyhow, Context};
 use chrono::{DateTime, FixedOffset};
-use lemmy_api_common::blocking;
+use lemmy_api_common::{blocking, flag_comment_for_review, get_slur_filters};
 use lemmy_apub_lib::{
   traits::ActorType,
   values::{MediaTypeHtml, MediaTypeMarkdown, PublicUrl},
@@ -146,6 +146,40 @@ impl Note {
   }
 }
 
+/// Turns a comment into a note, with its creator, post and parent comment already read from the
+/// database.
+pub(crate) fn comment_to_note(
+  comment: &Comment,
+  creator: &Person,
+  post: &Post,
+  parent_comment: Option<&Comment>,
+) -> Note {
+  // Add a vector containing some important info to the "in_reply_to" field
+  // [post_ap_id, Option(parent_comment_ap_id)]
+  let mut in_reply_to_vec = vec![post.ap_id.clone().into_inner()];
+  if let Some(parent_comment) = parent_comment {
+    in_reply_to_vec.push(parent_comment.ap_id.clone().into_inner());
+  }
+
+  Note {
+    context: lemmy_context(),
+    r#type: NoteType::Note,
+    id: comment.ap_id.to_owned().into_inner(),
+    attributed_to: ObjectId::new(creator.actor_id.clone()),
+    to: PublicUrl::Public,
+    content: comment.content.clone(),
+    media_type: MediaTypeHtml::Html,
+    source: Source {
+      content: comment.content.clone(),
+      media_type: MediaTypeMarkdown::Markdown,
+    },
+    in_reply_to: CommentInReplyToMigration::Old(in_reply_to_vec),
+    published: convert_datetime(comment.published),
+    updated: comment.updated.map(convert_datetime),
+    unparsed: Default::default(),
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl ToApub for Comment {
   type ApubType = Note;
@@ -157,35 +191,17 @@ impl ToApub for Comment {
     let post_id = self.post_id;
     let post = blocking(pool, move |conn| Post::read(conn, post_id)).await??;
 
-    // Add a vector containing some important info to the "in_reply_to" field
-    // [post_ap_id, Option(parent_comment_ap_id)]
-    let mut in_reply_to_vec = vec![post.ap_id.into_inner()];
-
-    if let Some(parent_id) = self.parent_id {
-      let parent_comment = blocking(pool, move |conn| Comment::read(conn, parent_id)).await??;
-
-      in_reply_to_vec.push(parent_comment.ap_id.into_inner());
-    }
-
-    let note = Note {
-      context: lemmy_context(),
-      r#type: NoteType::Note,
-      id: self.ap_id.to_owned().into_inner(),
-      attributed_to: ObjectId::new(creator.actor_id),
-      to: PublicUrl::Public,
-      content: self.content.clone(),
-      media_type: MediaTypeHtml::Html,
-      source: Source {
-        content: self.content.clone(),
-        media_type: MediaTypeMarkdown::Markdown,
-      },
-      in_reply_to: CommentInReplyToMigration::Old(in_reply_to_vec),
-      published: convert_datetime(self.published),
-      updated: self.updated.map(convert_datetime),
-      unparsed: Default::default(),
+    let parent_comment = match self.parent_id {
+      Some(parent_id) => Some(blocking(pool, move |conn| Comment::read(conn, parent_id)).await??),
+      None => None,
     };
 
-    Ok(note)
+    Ok(comment_to_note(
+      self,
+      &creator,
+      &post,
+      parent_comment.as_ref(),
+    ))
   }
 
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
@@ -223,6 +239,9 @@ impl FromApub for Comment {
 
     let content = &note.source.content;
     let content_slurs_removed = remove_slurs(content, &context.settings().slur_regex());
+    let slur_filters = get_slur_filters(post.community_id, context.pool()).await?;
+    let mut flagged = vec![];
+    let content_slurs_removed = slur_filters.apply(&content_slurs_removed, &mut flagged)?;
 
     let form = CommentForm {
       creator_id: creator.id,
@@ -237,6 +256,8 @@ impl FromApub for Comment {
       ap_id,
       local: Some(false),
     };
-    Ok(blocking(context.pool(), move |conn| Comment::upsert(conn, &form)).await??)
+    let comment = blocking(context.pool(), move |conn| Comment::upsert(conn, &form)).await??;
+    flag_comment_for_review(&comment, flagged, context.pool()).await?;
+    Ok(comment)
   }
 }
@@ -2,10 +2,10 @@ use crate::{
   check_is_apub_id_valid,
   context::lemmy_context,
   generate_outbox_url,
-  objects::{FromApub, ImageObject, Source, ToApub},
+  objects::{create_tombstone, FromApub, ImageObject, Source, ToApub},
 };
 use activitystreams::{
-  actor::Endpoints,
+  actor::{kind::PersonType, Endpoints},
   base::AnyBase,
   chrono::{DateTime, FixedOffset},
   object::{kind::ImageType, Tombstone},
@@ -60,6 +60,10 @@ pub struct Person {
   /// user banner
   image: Option<ImageObject>,
   matrix_user_id: Option<String>,
+  /// Other accounts of the same user, which are allowed to move to this account
+  also_known_as: Option<Vec<Url>>,
+  /// The account was moved to a different instance
+  moved_to: Option<Url>,
   inbox: Url,
   /// mandatory field in activitypub, currently empty in lemmy
   outbox: Url,
@@ -101,6 +105,11 @@ impl ToApub for DbPerson {
       kind: ImageType::Image,
       url: url.into(),
     });
+    let also_known_as: Vec<Url> = self
+      .also_known_as
+      .iter()
+      .map(|a| a.to_owned().into())
+      .collect();
 
     let person = Person {
       context: lemmy_context(),
@@ -114,6 +123,8 @@ impl ToApub for DbPerson {
       icon,
       image,
       matrix_user_id: self.matrix_user_id.clone(),
+      also_known_as: Some(also_known_as).filter(|a| !a.is_empty()),
+      moved_to: self.moved_to.clone().map(|m| m.into()),
       published: convert_datetime(self.published),
       outbox: generate_outbox_url(&self.actor_id)?.into(),
       endpoints: Endpoints {
@@ -128,7 +139,12 @@ impl ToApub for DbPerson {
     Ok(person)
   }
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
-    unimplemented!()
+    create_tombstone(
+      self.deleted,
+      self.actor_id.to_owned().into(),
+      self.updated,
+      PersonType::Person,
+    )
   }
 }
 
@@ -179,6 +195,16 @@ impl FromApub for DbPerson {
       inbox_url: Some(person.inbox.to_owned().into()),
       shared_inbox_url: Some(shared_inbox),
       matrix_user_id: Some(person.matrix_user_id.clone()),
+      moved_to: Some(person.moved_to.clone().map(|m| m.into())),
+      also_known_as: Some(
+        person
+          .also_known_as
+          .clone()
+          .unwrap_or_default()
+          .into_iter()
+          .map(|a| a.into())
+          .collect(),
+      ),
     };
     let person = blocking(context.pool(), move |conn| {
       DbPerson::upsert(conn, &person_form)
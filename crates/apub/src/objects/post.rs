@@ -15,7 +15,7 @@ use activitystreams::{
   unparsed::Unparsed,
 };
 use chrono::{DateTime, FixedOffset};
-use lemmy_api_common::blocking;
+use lemmy_api_common::{blocking, flag_post_for_review, get_slur_filters};
 use lemmy_apub_lib::{
   traits::ActorType,
   values::{MediaTypeHtml, MediaTypeMarkdown},
@@ -111,6 +111,38 @@ impl Page {
   }
 }
 
+/// Turns a post into a page, with its creator and community already read from the database.
+pub(crate) fn post_to_page(post: &Post, creator: &Person, community: &Community) -> Page {
+  let source = post.body.clone().map(|body| Source {
+    content: body,
+    media_type: MediaTypeMarkdown::Markdown,
+  });
+  let image = post.thumbnail_url.clone().map(|thumb| ImageObject {
+    kind: ImageType::Image,
+    url: thumb.into(),
+  });
+
+  Page {
+    context: lemmy_context(),
+    r#type: PageType::Page,
+    id: post.ap_id.clone().into(),
+    attributed_to: ObjectId::new(creator.actor_id.clone()),
+    to: [community.actor_id.clone().into(), public()],
+    name: post.name.clone(),
+    content: post.body.as_ref().map(|b| markdown_to_html(b)),
+    media_type: MediaTypeHtml::Html,
+    source,
+    url: post.url.clone().map(|u| u.into()),
+    image,
+    comments_enabled: Some(!post.locked),
+    sensitive: Some(post.nsfw),
+    stickied: Some(post.stickied),
+    published: convert_datetime(post.published),
+    updated: post.updated.map(convert_datetime),
+    unparsed: Default::default(),
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl ToApub for Post {
   type ApubType = Page;
@@ -121,36 +153,7 @@ impl ToApub for Post {
     let creator = blocking(pool, move |conn| Person::read(conn, creator_id)).await??;
     let community_id = self.community_id;
     let community = blocking(pool, move |conn| Community::read(conn, community_id)).await??;
-
-    let source = self.body.clone().map(|body| Source {
-      content: body,
-      media_type: MediaTypeMarkdown::Markdown,
-    });
-    let image = self.thumbnail_url.clone().map(|thumb| ImageObject {
-      kind: ImageType::Image,
-      url: thumb.into(),
-    });
-
-    let page = Page {
-      context: lemmy_context(),
-      r#type: PageType::Page,
-      id: self.ap_id.clone().into(),
-      attributed_to: ObjectId::new(creator.actor_id),
-      to: [community.actor_id.into(), public()],
-      name: self.name.clone(),
-      content: self.body.as_ref().map(|b| markdown_to_html(b)),
-      media_type: MediaTypeHtml::Html,
-      source,
-      url: self.url.clone().map(|u| u.into()),
-      image,
-      comments_enabled: Some(!self.locked),
-      sensitive: Some(self.nsfw),
-      stickied: Some(self.stickied),
-      published: convert_datetime(self.published),
-      updated: self.updated.map(convert_datetime),
-      unparsed: Default::default(),
-    };
-    Ok(page)
+    Ok(post_to_page(self, &creator, &community))
   }
 
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
@@ -201,8 +204,12 @@ impl FromApub for Post {
       .source
       .as_ref()
       .map(|s| remove_slurs(&s.content, &context.settings().slur_regex()));
+    let slur_filters = get_slur_filters(community.id, context.pool()).await?;
+    let mut flagged = vec![];
+    let name = slur_filters.apply(&page.name, &mut flagged)?;
+    let body_slurs_removed = slur_filters.apply_opt(&body_slurs_removed, &mut flagged)?;
     let form = PostForm {
-      name: page.name.clone(),
+      name,
       url: page.url.clone().map(|u| u.into()),
       body: body_slurs_removed,
       creator_id: creator.id,
@@ -220,7 +227,10 @@ impl FromApub for Post {
       thumbnail_url: pictrs_thumbnail.map(|u| u.into()),
       ap_id,
       local: Some(false),
+      scheduled_publish_time: None,
     };
-    Ok(blocking(context.pool(), move |conn| Post::upsert(conn, &form)).await??)
+    let post = blocking(context.pool(), move |conn| Post::upsert(conn, &form)).await??;
+    flag_post_for_review(&post, flagged, context.pool()).await?;
+    Ok(post)
   }
 }
@@ -2,7 +2,7 @@ use crate::{signatures::sign_and_send, traits::ActorType, APUB_JSON_CONTENT_TYPE
 use anyhow::{anyhow, Context, Error};
 use background_jobs::{
   create_server,
-  memory_storage::Storage,
+  dev::Storage,
   ActixJob,
   Backoff,
   MaxRetries,
@@ -10,12 +10,22 @@ use background_jobs::{
   WorkerConfig,
 };
 use lemmy_utils::{location_info, LemmyError};
-use log::warn;
+use log::{info, warn};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, env, fmt::Debug, future::Future, pin::Pin};
+use std::{collections::BTreeMap, env, fmt::Debug, future::Future, pin::Pin, sync::Arc};
 use url::Url;
 
+/// Keeps track of delivery results per instance, so that instances which have been unreachable
+/// for a long time don't get flooded with deliveries and retries.
+#[async_trait::async_trait(?Send)]
+pub trait DeliveryTracker {
+  /// Returns false if the inbox belongs to a dead instance, in which case the delivery is skipped.
+  async fn should_deliver(&self, inbox: &Url) -> Result<bool, LemmyError>;
+  async fn delivery_succeeded(&self, inbox: &Url) -> Result<(), LemmyError>;
+  async fn delivery_failed(&self, inbox: &Url) -> Result<(), LemmyError>;
+}
+
 pub async fn send_activity(
   activity: String,
   actor: &dyn ActorType,
@@ -59,7 +69,26 @@ impl ActixJob for SendActivityTask {
   const BACKOFF: Backoff = Backoff::Exponential(2);
 
   fn run(self, state: Self::State) -> Self::Future {
-    Box::pin(async move { do_send(self, &state.client).await })
+    Box::pin(async move {
+      let tracker = state.delivery_tracker;
+      let inbox = self.inbox.clone();
+      if !tracker.should_deliver(&inbox).await.map_err(|e| e.inner)? {
+        info!(
+          "Skipping delivery to {}, instance is considered dead",
+          inbox
+        );
+        return Ok(());
+      }
+
+      let result = do_send(self, &state.client).await;
+      if result.is_ok() {
+        tracker.delivery_succeeded(&inbox).await
+      } else {
+        tracker.delivery_failed(&inbox).await
+      }
+      .map_err(|e| e.inner)?;
+      result
+    })
   }
 }
 
@@ -87,14 +116,23 @@ async fn do_send(task: SendActivityTask, client: &Client) -> Result<(), Error> {
   Ok(())
 }
 
-pub fn create_activity_queue() -> QueueHandle {
+/// Creates the queue for outgoing activities, with jobs being kept in the given storage. The
+/// delivery tracker is informed about the result of each delivery attempt.
+pub fn create_activity_queue<S, T>(storage: S, delivery_tracker: T) -> QueueHandle
+where
+  S: Storage + Sync + 'static,
+  T: DeliveryTracker + Send + Sync + 'static,
+{
+  let delivery_tracker: Arc<dyn DeliveryTracker + Send + Sync> = Arc::new(delivery_tracker);
+
   // Start the application server. This guards access to to the jobs store
-  let queue_handle = create_server(Storage::new());
+  let queue_handle = create_server(storage);
   let arbiter = actix_web::rt::Arbiter::new();
 
   // Configure and start our workers
-  WorkerConfig::new(|| MyState {
+  WorkerConfig::new(move || MyState {
     client: Client::default(),
+    delivery_tracker: delivery_tracker.clone(),
   })
   .register::<SendActivityTask>()
   .start_in_arbiter(&arbiter, queue_handle.clone());
@@ -105,4 +143,5 @@ pub fn create_activity_queue() -> QueueHandle {
 #[derive(Clone)]
 struct MyState {
   pub client: Client,
+  pub delivery_tracker: Arc<dyn DeliveryTracker + Send + Sync>,
 }
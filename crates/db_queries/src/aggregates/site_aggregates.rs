@@ -59,6 +59,10 @@ mod tests {
       enable_nsfw: None,
       updated: None,
       community_creation_admin_only: Some(false),
+      require_email_verification: None,
+      require_application: None,
+      application_question: None,
+      require_totp_2fa_for_admins: None,
     };
 
     Site::create(&conn, &site_form).unwrap();
@@ -17,6 +17,7 @@ use lemmy_db_schema::{CommunityId, DbUrl, PersonId};
 use lemmy_utils::ApiError;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{env, env::VarError};
 use url::Url;
 
@@ -132,6 +133,9 @@ pub trait Reportable {
   type Form;
   type IdType;
   fn report(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error>
+  where
+    Self: Sized;
+  fn report_or_reopen(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error>
   where
     Self: Sized;
   fn resolve(
@@ -203,6 +207,23 @@ pub enum SortType {
   TopAll,
   MostComments,
   NewComments,
+  /// Best matches first, only applies to searches
+  Relevance,
+}
+
+/// What happens to posts and comments which match one of the user's keyword filters
+#[derive(EnumString, ToString, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum KeywordFilterAction {
+  Hide,
+  Blur,
+}
+
+/// What happens to posts and comments which match a site-wide or community slur filter
+#[derive(EnumString, ToString, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum SlurFilterMode {
+  Reject,
+  Replace,
+  Flag,
 }
 
 #[derive(EnumString, ToString, Debug, Serialize, Deserialize, Clone, Copy)]
@@ -223,10 +244,37 @@ pub enum SearchType {
   Url,
 }
 
+#[derive(EnumString, ToString, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModlogActionType {
+  All,
+  ModRemovePost,
+  ModLockPost,
+  ModStickyPost,
+  ModRemoveComment,
+  ModRemoveCommunity,
+  ModBanFromCommunity,
+  ModBan,
+  ModAddCommunity,
+  ModTransferCommunity,
+  ModAdd,
+}
+
 pub fn from_opt_str_to_opt_enum<T: std::str::FromStr>(opt: &Option<String>) -> Option<T> {
   opt.as_ref().map(|t| T::from_str(t).ok()).flatten()
 }
 
+/// Tokens which are sent to users are only stored as a hash, so that they can't be used if the
+/// database leaks.
+pub(crate) fn hash_token(token: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(token);
+  hasher
+    .finalize()
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
 pub fn fuzzy_search(q: &str) -> String {
   let replaced = q.replace(" ", "%");
   format!("%{}%", replaced)
@@ -299,6 +347,65 @@ pub mod functions {
   sql_function! {
     fn hot_rank(score: BigInt, time: Timestamp) -> Integer;
   }
+
+  sql_function! {
+    fn actor_domain(actor_id: Text) -> Text;
+  }
+
+  sql_function! {
+    fn post_keyword_filtered(person_id: Integer, action: SmallInt, title: Text, body: Nullable<Text>) -> Bool;
+  }
+
+  sql_function! {
+    fn comment_keyword_filtered(person_id: Integer, action: SmallInt, content: Text) -> Bool;
+  }
+}
+
+/// The `search_vector` columns of post, comment and community are generated by postgres and
+/// not part of the diesel schema, as diesel doesn't know the tsvector type.
+pub mod full_text_search {
+  use diesel::{
+    dsl::sql,
+    expression::SqlLiteral,
+    pg::Pg,
+    sql_types::{Float, Text},
+  };
+
+  #[derive(SqlType)]
+  #[postgres(type_name = "tsvector")]
+  pub struct TsVector;
+
+  #[derive(SqlType)]
+  #[postgres(type_name = "tsquery")]
+  pub struct TsQuery;
+
+  diesel_infix_operator!(TsMatches, " @@ ", backend: Pg);
+
+  sql_function! {
+    fn search_query(query: Text) -> TsQuery;
+  }
+
+  sql_function! {
+    fn ts_rank(vector: TsVector, query: TsQuery) -> Float;
+  }
+
+  pub type SearchVector = SqlLiteral<TsVector>;
+  pub type SearchQuery = search_query::HelperType<String>;
+
+  /// The search vector of the given table, which has to be part of the query
+  pub fn search_vector(table: &str) -> SearchVector {
+    sql(&format!("{}.search_vector", table))
+  }
+
+  /// Matches the search vector of a table against a query, which can contain `"quoted phrases"`,
+  /// `or` and `-excluded` terms
+  pub fn matches_search(table: &str, query: &str) -> TsMatches<SearchVector, SearchQuery> {
+    TsMatches::new(search_vector(table), search_query(query.to_owned()))
+  }
+
+  pub fn search_rank(table: &str, query: &str) -> ts_rank::HelperType<SearchVector, SearchQuery> {
+    ts_rank(search_vector(table), search_query(query.to_owned()))
+  }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use crate::Crud;
-use diesel::{dsl::*, result::Error, sql_types::Text, *};
-use lemmy_db_schema::{source::activity::*, DbUrl};
+use diesel::{dsl::*, pg::Pg, result::Error, sql_types::Text, *};
+use lemmy_db_schema::{schema::activity, source::activity::*, DbUrl};
 use serde::Serialize;
 use serde_json::Value;
 use std::{
@@ -53,11 +53,14 @@ pub trait Activity_ {
   fn read_from_apub_id(conn: &PgConnection, object_id: &DbUrl) -> Result<Activity, Error>;
   fn delete_olds(conn: &PgConnection) -> Result<usize, Error>;
 
-  /// Returns up to 20 activities of type `Announce/Create/Page` from the community
+  /// Returns the activities of type `Announce/Create/Page` from the community, newest first
   fn read_community_outbox(
     conn: &PgConnection,
     community_actor_id: &DbUrl,
+    limit: i64,
+    offset: i64,
   ) -> Result<Vec<Value>, Error>;
+  fn count_community_outbox(conn: &PgConnection, community_actor_id: &DbUrl) -> Result<i64, Error>;
 }
 
 impl Activity_ for Activity {
@@ -101,22 +104,36 @@ impl Activity_ for Activity {
   fn read_community_outbox(
     conn: &PgConnection,
     community_actor_id: &DbUrl,
+    limit: i64,
+    offset: i64,
   ) -> Result<Vec<Value>, Error> {
     use lemmy_db_schema::schema::activity::dsl::*;
-    let res: Vec<Value> = activity
+    community_outbox(community_actor_id)
       .select(data)
-      .filter(
-        sql("activity.data ->> 'type' = 'Announce'")
-          .sql(" AND activity.data -> 'object' ->> 'type' = 'Create'")
-          .sql(" AND activity.data -> 'object' -> 'object' ->> 'type' = 'Page'")
-          .sql(" AND activity.data ->> 'actor' = ")
-          .bind::<Text, _>(community_actor_id)
-          .sql(" ORDER BY activity.published DESC"),
-      )
-      .limit(20)
-      .get_results(conn)?;
-    Ok(res)
+      .order_by(published.desc())
+      .limit(limit)
+      .offset(offset)
+      .get_results(conn)
   }
+
+  fn count_community_outbox(conn: &PgConnection, community_actor_id: &DbUrl) -> Result<i64, Error> {
+    community_outbox(community_actor_id)
+      .count()
+      .get_result(conn)
+  }
+}
+
+fn community_outbox(community_actor_id: &DbUrl) -> activity::BoxedQuery<'_, Pg> {
+  use lemmy_db_schema::schema::activity::dsl::*;
+  activity
+    .filter(
+      sql("activity.data ->> 'type' = 'Announce'")
+        .sql(" AND activity.data -> 'object' ->> 'type' = 'Create'")
+        .sql(" AND activity.data -> 'object' -> 'object' ->> 'type' = 'Page'")
+        .sql(" AND activity.data ->> 'actor' = ")
+        .bind::<Text, _>(community_actor_id),
+    )
+    .into_boxed()
 }
 
 #[cfg(test)]
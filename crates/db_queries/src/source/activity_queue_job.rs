@@ -0,0 +1,185 @@
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::{
+  naive_now,
+  source::activity_queue_job::{ActivityQueueJob, ActivityQueueJobForm},
+};
+
+pub trait ActivityQueueJob_ {
+  /// Inserts the job, or overwrites it if a job with the same `job_id` already exists.
+  fn upsert(conn: &PgConnection, form: &ActivityQueueJobForm) -> Result<ActivityQueueJob, Error>;
+  fn read_from_job_id(conn: &PgConnection, job_id: &str) -> Result<ActivityQueueJob, Error>;
+
+  /// Takes the oldest job out of the given queue which is ready to run. The row is locked while
+  /// doing so, so that concurrent workers never receive the same job.
+  fn claim_from_queue(
+    conn: &PgConnection,
+    queue_name: &str,
+  ) -> Result<Option<ActivityQueueJob>, Error>;
+  fn enqueue(conn: &PgConnection, job_id: &str, queue_name: &str) -> Result<usize, Error>;
+  fn set_runner(conn: &PgConnection, job_id: &str, runner_id: &str) -> Result<usize, Error>;
+  fn delete_from_job_id(conn: &PgConnection, job_id: &str) -> Result<usize, Error>;
+
+  /// Puts jobs back into their queue which were taken out, but never returned. This happens if
+  /// the server was stopped while they were running.
+  fn requeue_unfinished(conn: &PgConnection) -> Result<usize, Error>;
+
+  fn count_pending(conn: &PgConnection) -> Result<i64, Error>;
+  fn count_running(conn: &PgConnection) -> Result<i64, Error>;
+  fn count_retrying(conn: &PgConnection) -> Result<i64, Error>;
+}
+
+impl ActivityQueueJob_ for ActivityQueueJob {
+  fn upsert(conn: &PgConnection, form: &ActivityQueueJobForm) -> Result<ActivityQueueJob, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    insert_into(activity_queue_job)
+      .values(form)
+      .on_conflict(job_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read_from_job_id(conn: &PgConnection, job_id_: &str) -> Result<ActivityQueueJob, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    activity_queue_job
+      .filter(job_id.eq(job_id_))
+      .first::<Self>(conn)
+  }
+
+  fn claim_from_queue(
+    conn: &PgConnection,
+    queue_name: &str,
+  ) -> Result<Option<ActivityQueueJob>, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    conn.transaction(|| {
+      let job = activity_queue_job
+        .filter(queue.eq(queue_name))
+        .filter(queued.eq(true))
+        .filter(next_queue.is_null().or(next_queue.lt(naive_now())))
+        .order_by(id)
+        .for_update()
+        .skip_locked()
+        .first::<Self>(conn)
+        .optional()?;
+      match job {
+        Some(job) => diesel::update(activity_queue_job.find(job.id))
+          .set(queued.eq(false))
+          .get_result::<Self>(conn)
+          .map(Some),
+        None => Ok(None),
+      }
+    })
+  }
+
+  fn enqueue(conn: &PgConnection, job_id_: &str, queue_name: &str) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    diesel::update(activity_queue_job.filter(job_id.eq(job_id_)))
+      .set((queue.eq(queue_name), queued.eq(true)))
+      .execute(conn)
+  }
+
+  fn set_runner(conn: &PgConnection, job_id_: &str, runner_id_: &str) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    diesel::update(activity_queue_job.filter(job_id.eq(job_id_)))
+      .set(runner_id.eq(runner_id_))
+      .execute(conn)
+  }
+
+  fn delete_from_job_id(conn: &PgConnection, job_id_: &str) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    diesel::delete(activity_queue_job.filter(job_id.eq(job_id_))).execute(conn)
+  }
+
+  fn requeue_unfinished(conn: &PgConnection) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    diesel::update(activity_queue_job.filter(queued.eq(false)))
+      .set((queued.eq(true), runner_id.eq::<Option<String>>(None)))
+      .execute(conn)
+  }
+
+  fn count_pending(conn: &PgConnection) -> Result<i64, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    activity_queue_job
+      .filter(status.eq("Pending"))
+      .select(count(id))
+      .first::<i64>(conn)
+  }
+
+  fn count_running(conn: &PgConnection) -> Result<i64, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    activity_queue_job
+      .filter(status.eq("Running"))
+      .select(count(id))
+      .first::<i64>(conn)
+  }
+
+  fn count_retrying(conn: &PgConnection) -> Result<i64, Error> {
+    use lemmy_db_schema::schema::activity_queue_job::dsl::*;
+    activity_queue_job
+      .filter(retry_count.gt(0))
+      .select(count(id))
+      .first::<i64>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::activity_queue_job::ActivityQueueJob_};
+  use lemmy_db_schema::source::activity_queue_job::{ActivityQueueJob, ActivityQueueJobForm};
+  use serde_json::json;
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let job_id = "3f6c4d0e-6a0f-4ad5-9a2a-5bc1e0a1f0aa";
+    let form = ActivityQueueJobForm {
+      job_id: job_id.to_string(),
+      queue: "default".to_string(),
+      status: "Pending".to_string(),
+      retry_count: 0,
+      next_queue: Some(None),
+      data: json!({ "id": job_id }),
+      updated: None,
+    };
+
+    let inserted_job = ActivityQueueJob::upsert(&conn, &form).unwrap();
+    assert!(!inserted_job.queued);
+
+    // Not in the queue yet, so it cant be claimed
+    let not_claimed = ActivityQueueJob::claim_from_queue(&conn, "default").unwrap();
+
+    ActivityQueueJob::enqueue(&conn, job_id, "default").unwrap();
+    let other_queue = ActivityQueueJob::claim_from_queue(&conn, "other").unwrap();
+    let claimed = ActivityQueueJob::claim_from_queue(&conn, "default").unwrap();
+    let claimed_twice = ActivityQueueJob::claim_from_queue(&conn, "default").unwrap();
+
+    let retry_form = ActivityQueueJobForm {
+      status: "Running".to_string(),
+      retry_count: 1,
+      ..form
+    };
+    let updated_job = ActivityQueueJob::upsert(&conn, &retry_form).unwrap();
+    let running = ActivityQueueJob::count_running(&conn).unwrap();
+    let retrying = ActivityQueueJob::count_retrying(&conn).unwrap();
+
+    let requeued = ActivityQueueJob::requeue_unfinished(&conn).unwrap();
+    let read_job = ActivityQueueJob::read_from_job_id(&conn, job_id).unwrap();
+
+    let num_deleted = ActivityQueueJob::delete_from_job_id(&conn, job_id).unwrap();
+
+    assert_eq!(None, not_claimed);
+    assert_eq!(None, other_queue);
+    assert_eq!(Some(inserted_job.id), claimed.map(|j| j.id));
+    assert_eq!(None, claimed_twice);
+    assert_eq!(inserted_job.id, updated_job.id);
+    assert_eq!(1, updated_job.retry_count);
+    assert_eq!(1, running);
+    assert_eq!(1, retrying);
+    assert_eq!(1, requeued);
+    assert!(read_job.queued);
+    assert_eq!(1, num_deleted);
+  }
+}
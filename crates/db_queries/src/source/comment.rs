@@ -7,6 +7,8 @@ use lemmy_db_schema::{
     CommentForm,
     CommentLike,
     CommentLikeForm,
+    CommentRevision,
+    CommentRevisionForm,
     CommentSaved,
     CommentSavedForm,
   },
@@ -51,6 +53,10 @@ pub trait Comment_ {
     new_content: &str,
   ) -> Result<Comment, Error>;
   fn upsert(conn: &PgConnection, comment_form: &CommentForm) -> Result<Comment, Error>;
+  fn list_for_creator(conn: &PgConnection, for_creator_id: PersonId)
+    -> Result<Vec<Comment>, Error>;
+  fn list_saved(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Comment>, Error>;
+  fn read_many(conn: &PgConnection, comment_ids: &[CommentId]) -> Result<Vec<Comment>, Error>;
 }
 
 impl Comment_ for Comment {
@@ -70,7 +76,15 @@ impl Comment_ for Comment {
     conn: &PgConnection,
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
-    use lemmy_db_schema::schema::comment::dsl::*;
+    use lemmy_db_schema::schema::{comment::dsl::*, comment_revision::dsl as cr};
+
+    // Earlier versions would still contain the content
+    diesel::delete(
+      cr::comment_revision
+        .filter(cr::comment_id.eq_any(comment.filter(creator_id.eq(for_creator_id)).select(id))),
+    )
+    .execute(conn)?;
+
     diesel::update(comment.filter(creator_id.eq(for_creator_id)))
       .set((
         content.eq("*Permananently Deleted*"),
@@ -144,6 +158,31 @@ impl Comment_ for Comment {
       .set(comment_form)
       .get_result::<Self>(conn)
   }
+
+  /// All comments of the creator, including deleted and removed ones
+  fn list_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::comment::dsl::*;
+    comment
+      .filter(creator_id.eq(for_creator_id))
+      .order_by((published.desc(), id.desc()))
+      .load::<Self>(conn)
+  }
+
+  /// All comments saved by the person, most recently saved first
+  fn list_saved(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::{comment, comment_saved};
+    comment::table
+      .inner_join(comment_saved::table)
+      .filter(comment_saved::person_id.eq(for_person_id))
+      .select(comment::all_columns)
+      .order_by(comment_saved::published.desc())
+      .load::<Self>(conn)
+  }
+
+  fn read_many(conn: &PgConnection, comment_ids: &[CommentId]) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::comment::dsl::*;
+    comment.filter(id.eq_any(comment_ids)).load::<Self>(conn)
+  }
 }
 
 impl Crud for Comment {
@@ -178,6 +217,49 @@ impl Crud for Comment {
   }
 }
 
+pub trait CommentRevision_ {
+  fn create_if_changed(
+    conn: &PgConnection,
+    old_comment: &Comment,
+    new_comment: &Comment,
+  ) -> Result<Option<CommentRevision>, Error>;
+  fn list_for_comment(
+    conn: &PgConnection,
+    for_comment_id: CommentId,
+  ) -> Result<Vec<CommentRevision>, Error>;
+}
+
+impl CommentRevision_ for CommentRevision {
+  /// Stores the old version of an edited comment, if its content changed
+  fn create_if_changed(
+    conn: &PgConnection,
+    old_comment: &Comment,
+    new_comment: &Comment,
+  ) -> Result<Option<Self>, Error> {
+    use lemmy_db_schema::schema::comment_revision::dsl::*;
+    if old_comment.content == new_comment.content {
+      return Ok(None);
+    }
+    let form = CommentRevisionForm {
+      comment_id: old_comment.id,
+      content: old_comment.content.to_owned(),
+      published: old_comment.updated.unwrap_or(old_comment.published),
+    };
+    insert_into(comment_revision)
+      .values(form)
+      .get_result::<Self>(conn)
+      .map(Some)
+  }
+
+  fn list_for_comment(conn: &PgConnection, for_comment_id: CommentId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::comment_revision::dsl::*;
+    comment_revision
+      .filter(comment_id.eq(for_comment_id))
+      .order_by(published.desc())
+      .load::<Self>(conn)
+  }
+}
+
 impl Likeable for CommentLike {
   type Form = CommentLikeForm;
   type IdType = CommentId;
@@ -236,7 +318,13 @@ impl DeleteableOrRemoveable for Comment {
 
 #[cfg(test)]
 mod tests {
-  use crate::{establish_unpooled_connection, Crud, Likeable, Saveable};
+  use crate::{
+    establish_unpooled_connection,
+    source::comment::{CommentRevision_, Comment_},
+    Crud,
+    Likeable,
+    Saveable,
+  };
   use lemmy_db_schema::source::{
     comment::*,
     community::{Community, CommunityForm},
@@ -342,10 +430,32 @@ mod tests {
       published: inserted_comment_saved.published,
     };
 
+    let creator_comments = Comment::list_for_creator(&conn, inserted_person.id).unwrap();
+    let saved_comments = Comment::list_saved(&conn, inserted_person.id).unwrap();
+    let mut many_comments =
+      Comment::read_many(&conn, &[inserted_comment.id, inserted_child_comment.id]).unwrap();
+    many_comments.sort_by_key(|c| c.id.0);
+
     let read_comment = Comment::read(&conn, inserted_comment.id).unwrap();
     let updated_comment = Comment::update(&conn, inserted_comment.id, &comment_form).unwrap();
     let like_removed = CommentLike::remove(&conn, inserted_person.id, inserted_comment.id).unwrap();
     let saved_removed = CommentSaved::unsave(&conn, &comment_saved_form).unwrap();
+
+    // Comment Revision
+    let edited_comment =
+      Comment::update_content(&conn, inserted_comment.id, "An edited comment").unwrap();
+    let inserted_revision =
+      CommentRevision::create_if_changed(&conn, &updated_comment, &edited_comment)
+        .unwrap()
+        .unwrap();
+    let expected_revision = CommentRevision {
+      id: inserted_revision.id,
+      comment_id: inserted_comment.id,
+      content: updated_comment.content.to_owned(),
+      published: inserted_comment.published,
+    };
+    let revisions = CommentRevision::list_for_comment(&conn, inserted_comment.id).unwrap();
+
     let num_deleted = Comment::delete(&conn, inserted_comment.id).unwrap();
     Comment::delete(&conn, inserted_child_comment.id).unwrap();
     Post::delete(&conn, inserted_post.id).unwrap();
@@ -357,6 +467,15 @@ mod tests {
     assert_eq!(expected_comment, updated_comment);
     assert_eq!(expected_comment_like, inserted_comment_like);
     assert_eq!(expected_comment_saved, inserted_comment_saved);
+    assert_eq!(
+      vec![inserted_child_comment.id, inserted_comment.id],
+      creator_comments.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![expected_comment.to_owned()], saved_comments);
+    assert_eq!(
+      vec![inserted_comment.id, inserted_child_comment.id],
+      many_comments.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
     assert_eq!(
       expected_comment.id,
       inserted_child_comment.parent_id.unwrap()
@@ -364,5 +483,7 @@ mod tests {
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, num_deleted);
+    assert_eq!(expected_revision, inserted_revision);
+    assert_eq!(vec![expected_revision], revisions);
   }
 }
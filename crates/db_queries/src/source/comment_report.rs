@@ -21,6 +21,28 @@ impl Reportable for CommentReport {
       .get_result::<Self>(conn)
   }
 
+  /// creates a comment report, or updates and re-opens the existing report of the same creator
+  ///
+  /// * `conn` - the postgres connection
+  /// * `comment_report_form` - the filled CommentReportForm to insert
+  fn report_or_reopen(
+    conn: &PgConnection,
+    comment_report_form: &CommentReportForm,
+  ) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::comment_report::dsl::*;
+    insert_into(comment_report)
+      .values(comment_report_form)
+      .on_conflict((comment_id, creator_id))
+      .do_update()
+      .set((
+        comment_report_form,
+        resolved.eq(false),
+        resolver_id.eq(None::<PersonId>),
+        updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+  }
+
   /// resolve a comment report
   ///
   /// * `conn` - the postgres connection
@@ -12,6 +12,7 @@ use lemmy_db_schema::{
     CommunityPersonBan,
     CommunityPersonBanForm,
     CommunitySafe,
+    ModPermissions,
   },
   CommunityId,
   DbUrl,
@@ -110,6 +111,10 @@ pub trait Community_ {
     followers_url: &DbUrl,
   ) -> Result<Community, Error>;
   fn upsert(conn: &PgConnection, community_form: &CommunityForm) -> Result<Community, Error>;
+  /// The shared inbox of each remote community, or its own inbox if there is none
+  fn remote_inboxes(conn: &PgConnection) -> Result<Vec<DbUrl>, Error>;
+  fn read_many(conn: &PgConnection, community_ids: &[CommunityId])
+    -> Result<Vec<Community>, Error>;
 }
 
 impl Community_ for Community {
@@ -167,6 +172,28 @@ impl Community_ for Community {
       .set(community_form)
       .get_result::<Self>(conn)
   }
+
+  fn remote_inboxes(conn: &PgConnection) -> Result<Vec<DbUrl>, Error> {
+    use lemmy_db_schema::schema::community::dsl::*;
+    let inboxes = community
+      .filter(local.eq(false))
+      .select((shared_inbox_url, inbox_url))
+      .distinct()
+      .load::<(Option<DbUrl>, DbUrl)>(conn)?;
+    Ok(
+      inboxes
+        .into_iter()
+        .map(|(shared_inbox, inbox)| shared_inbox.unwrap_or(inbox))
+        .collect(),
+    )
+  }
+
+  fn read_many(conn: &PgConnection, community_ids: &[CommunityId]) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::community::dsl::*;
+    community
+      .filter(id.eq_any(community_ids))
+      .load::<Self>(conn)
+  }
 }
 
 impl Joinable for CommunityModerator {
@@ -224,6 +251,17 @@ pub trait CommunityModerator_ {
     conn: &PgConnection,
     for_person_id: PersonId,
   ) -> Result<Vec<CommunityId>, Error>;
+  fn read_permissions(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+  ) -> Result<ModPermissions, Error>;
+  fn update_permissions(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+    permissions: &ModPermissions,
+  ) -> Result<CommunityModerator, Error>;
 }
 
 impl CommunityModerator_ for CommunityModerator {
@@ -245,6 +283,41 @@ impl CommunityModerator_ for CommunityModerator {
       .select(community_id)
       .load::<CommunityId>(conn)
   }
+
+  fn read_permissions(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+  ) -> Result<ModPermissions, Error> {
+    use lemmy_db_schema::schema::community_moderator::dsl::*;
+    community_moderator
+      .filter(community_id.eq(for_community_id))
+      .filter(person_id.eq(for_person_id))
+      .select((
+        can_remove,
+        can_ban,
+        can_manage_mods,
+        can_resolve_reports,
+        can_edit_community,
+      ))
+      .first::<ModPermissions>(conn)
+  }
+
+  fn update_permissions(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+    permissions: &ModPermissions,
+  ) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::community_moderator::dsl::*;
+    diesel::update(
+      community_moderator
+        .filter(community_id.eq(for_community_id))
+        .filter(person_id.eq(for_person_id)),
+    )
+    .set(permissions)
+    .get_result::<Self>(conn)
+  }
 }
 
 impl Bannable for CommunityPersonBan {
@@ -256,6 +329,9 @@ impl Bannable for CommunityPersonBan {
     use lemmy_db_schema::schema::community_person_ban::dsl::*;
     insert_into(community_person_ban)
       .values(community_person_ban_form)
+      .on_conflict((community_id, person_id))
+      .do_update()
+      .set(community_person_ban_form)
       .get_result::<Self>(conn)
   }
 
@@ -273,6 +349,23 @@ impl Bannable for CommunityPersonBan {
   }
 }
 
+pub trait CommunityPersonBan_ {
+  /// Returns the bans in local communities which have expired
+  fn read_expired(conn: &PgConnection) -> Result<Vec<CommunityPersonBan>, Error>;
+}
+
+impl CommunityPersonBan_ for CommunityPersonBan {
+  fn read_expired(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::{community, community_person_ban};
+    community_person_ban::table
+      .inner_join(community::table)
+      .filter(community::local.eq(true))
+      .filter(community_person_ban::expires.lt(naive_now()))
+      .select(community_person_ban::all_columns)
+      .load::<Self>(conn)
+  }
+}
+
 impl Followable for CommunityFollower {
   type Form = CommunityFollowerForm;
   fn follow(
@@ -329,7 +422,14 @@ impl Followable for CommunityFollower {
 
 #[cfg(test)]
 mod tests {
-  use crate::{establish_unpooled_connection, Bannable, Crud, Followable, Joinable};
+  use crate::{
+    establish_unpooled_connection,
+    source::community::CommunityModerator_,
+    Bannable,
+    Crud,
+    Followable,
+    Joinable,
+  };
   use lemmy_db_schema::source::{community::*, person::*};
   use serial_test::serial;
 
@@ -405,11 +505,35 @@ mod tests {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
       published: inserted_community_moderator.published,
+      can_remove: true,
+      can_ban: true,
+      can_manage_mods: true,
+      can_resolve_reports: true,
+      can_edit_community: true,
     };
 
+    let reviewer_permissions = ModPermissions {
+      can_remove: false,
+      can_ban: false,
+      can_manage_mods: false,
+      can_resolve_reports: true,
+      can_edit_community: false,
+    };
+    CommunityModerator::update_permissions(
+      &conn,
+      inserted_community.id,
+      inserted_person.id,
+      &reviewer_permissions,
+    )
+    .unwrap();
+    let read_permissions =
+      CommunityModerator::read_permissions(&conn, inserted_community.id, inserted_person.id)
+        .unwrap();
+
     let community_person_ban_form = CommunityPersonBanForm {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
+      expires: None,
     };
 
     let inserted_community_person_ban =
@@ -420,6 +544,7 @@ mod tests {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
       published: inserted_community_person_ban.published,
+      expires: None,
     };
 
     let read_community = Community::read(&conn, inserted_community.id).unwrap();
@@ -436,6 +561,11 @@ mod tests {
     assert_eq!(expected_community, updated_community);
     assert_eq!(expected_community_follower, inserted_community_follower);
     assert_eq!(expected_community_moderator, inserted_community_moderator);
+    assert_eq!(reviewer_permissions, read_permissions);
+    assert!(read_permissions.has(ModPermission::ResolveReports));
+    assert!(!read_permissions.has(ModPermission::Ban));
+    assert!(read_permissions.is_subset_of(&ModPermissions::all()));
+    assert!(!ModPermissions::all().is_subset_of(&read_permissions));
     assert_eq!(expected_community_person_ban, inserted_community_person_ban);
     assert_eq!(1, ignored_community);
     assert_eq!(1, left_community);
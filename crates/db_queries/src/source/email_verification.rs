@@ -0,0 +1,137 @@
+use crate::{hash_token, Crud};
+use diesel::{dsl::*, result::Error, PgConnection, *};
+use lemmy_db_schema::{
+  schema::email_verification::dsl::*,
+  source::email_verification::*,
+  LocalUserId,
+};
+
+impl Crud for EmailVerification {
+  type Form = EmailVerificationForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, email_verification_id: i32) -> Result<Self, Error> {
+    email_verification
+      .find(email_verification_id)
+      .first::<Self>(conn)
+  }
+  fn create(conn: &PgConnection, form: &EmailVerificationForm) -> Result<Self, Error> {
+    insert_into(email_verification)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+  fn update(
+    conn: &PgConnection,
+    email_verification_id: i32,
+    form: &EmailVerificationForm,
+  ) -> Result<Self, Error> {
+    diesel::update(email_verification.find(email_verification_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+pub trait EmailVerification_ {
+  fn create_token(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    for_email: &str,
+    token: &str,
+  ) -> Result<EmailVerification, Error>;
+  fn read_from_token(conn: &PgConnection, token: &str) -> Result<EmailVerification, Error>;
+  fn delete_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error>;
+}
+
+impl EmailVerification_ for EmailVerification {
+  fn create_token(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    for_email: &str,
+    token: &str,
+  ) -> Result<EmailVerification, Error> {
+    let form = EmailVerificationForm {
+      local_user_id: for_local_user_id,
+      email: for_email.to_owned(),
+      verification_token: hash_token(token),
+    };
+
+    Self::create(conn, &form)
+  }
+  fn read_from_token(conn: &PgConnection, token: &str) -> Result<EmailVerification, Error> {
+    let token_hash = hash_token(token);
+    email_verification
+      .filter(verification_token.eq(token_hash))
+      .filter(published.gt(now - 7.days()))
+      .first::<Self>(conn)
+  }
+  fn delete_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    diesel::delete(email_verification.filter(local_user_id.eq(for_local_user_id))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{email_verification::EmailVerification_, local_user::LocalUser_},
+    Crud,
+  };
+  use lemmy_db_schema::source::{
+    email_verification::EmailVerification,
+    local_user::{LocalUser, LocalUserForm},
+    person::*,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "email_verification_person".into(),
+      ..PersonForm::default()
+    };
+
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      email: Some(Some("old@example.com".to_string())),
+      ..LocalUserForm::default()
+    };
+
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let token = "verify_me";
+    let inserted_email_verification =
+      EmailVerification::create_token(&conn, inserted_local_user.id, "new@example.com", token)
+        .unwrap();
+
+    let read_email_verification = EmailVerification::read_from_token(&conn, token).unwrap();
+    let read_wrong_token = EmailVerification::read_from_token(&conn, "wrong");
+    let verified_local_user =
+      LocalUser::set_verified_email(&conn, inserted_local_user.id, "new@example.com").unwrap();
+    let num_deleted_verifications =
+      EmailVerification::delete_for_local_user(&conn, inserted_local_user.id).unwrap();
+    let num_deleted = Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_ne!(token, inserted_email_verification.verification_token);
+    assert_eq!(inserted_email_verification, read_email_verification);
+    assert!(read_wrong_token.is_err());
+    assert!(!inserted_local_user.email_verified);
+    assert!(verified_local_user.email_verified);
+    assert_eq!(
+      Some("new@example.com".to_string()),
+      verified_local_user.email
+    );
+    assert_eq!(1, num_deleted_verifications);
+    assert_eq!(1, num_deleted);
+  }
+}
@@ -0,0 +1,76 @@
+use diesel::{insert_into, result::Error, *};
+use lemmy_db_schema::source::instance::{Instance, InstanceForm};
+
+pub trait Instance_ {
+  /// Adds the instance to the allowlist or blocklist, replacing any previous entry for its domain.
+  fn upsert(conn: &PgConnection, form: &InstanceForm) -> Result<Instance, Error>;
+  fn delete_from_domain(conn: &PgConnection, domain: &str) -> Result<Instance, Error>;
+  fn list(conn: &PgConnection) -> Result<Vec<Instance>, Error>;
+}
+
+impl Instance_ for Instance {
+  fn upsert(conn: &PgConnection, form: &InstanceForm) -> Result<Instance, Error> {
+    use lemmy_db_schema::schema::instance::dsl::*;
+    insert_into(instance)
+      .values(form)
+      .on_conflict(domain)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete_from_domain(conn: &PgConnection, for_domain: &str) -> Result<Instance, Error> {
+    use lemmy_db_schema::schema::instance::dsl::*;
+    diesel::delete(instance.filter(domain.eq(for_domain))).get_result::<Self>(conn)
+  }
+
+  fn list(conn: &PgConnection) -> Result<Vec<Instance>, Error> {
+    use lemmy_db_schema::schema::instance::dsl::*;
+    instance.order_by(domain).load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::instance::Instance_};
+  use lemmy_db_schema::{
+    naive_now,
+    source::instance::{Instance, InstanceForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let form = InstanceForm {
+      domain: "spam.example.com".to_string(),
+      blocked: true,
+      reason: Some(Some("spam".to_string())),
+      updated: None,
+    };
+    let inserted_instance = Instance::upsert(&conn, &form).unwrap();
+
+    let allow_form = InstanceForm {
+      blocked: false,
+      reason: Some(None),
+      updated: Some(Some(naive_now())),
+      ..form
+    };
+    let updated_instance = Instance::upsert(&conn, &allow_form).unwrap();
+    let instances = Instance::list(&conn).unwrap();
+    let deleted_instance = Instance::delete_from_domain(&conn, "spam.example.com").unwrap();
+    let instances_after_delete = Instance::list(&conn).unwrap();
+
+    assert!(inserted_instance.blocked);
+    assert_eq!(Some("spam".to_string()), inserted_instance.reason);
+    assert_eq!(inserted_instance.id, updated_instance.id);
+    assert!(!updated_instance.blocked);
+    assert_eq!(None, updated_instance.reason);
+    assert!(updated_instance.updated.is_some());
+    assert_eq!(vec![updated_instance.clone()], instances);
+    assert_eq!(updated_instance, deleted_instance);
+    assert!(instances_after_delete.is_empty());
+  }
+}
@@ -0,0 +1,25 @@
+use crate::Blockable;
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::source::instance_block::{InstanceBlock, InstanceBlockForm};
+
+impl Blockable for InstanceBlock {
+  type Form = InstanceBlockForm;
+  fn block(conn: &PgConnection, instance_block_form: &Self::Form) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::instance_block::dsl::*;
+    insert_into(instance_block)
+      .values(instance_block_form)
+      .on_conflict((person_id, domain))
+      .do_update()
+      .set(instance_block_form)
+      .get_result::<Self>(conn)
+  }
+  fn unblock(conn: &PgConnection, instance_block_form: &Self::Form) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::instance_block::dsl::*;
+    diesel::delete(
+      instance_block
+        .filter(person_id.eq(instance_block_form.person_id))
+        .filter(domain.eq(&instance_block_form.domain)),
+    )
+    .execute(conn)
+  }
+}
@@ -0,0 +1,147 @@
+use chrono::Duration;
+use diesel::{insert_into, result::Error, *};
+use lemmy_db_schema::{
+  naive_now,
+  source::instance_health::{InstanceHealth, InstanceHealthForm},
+};
+
+pub trait InstanceHealth_ {
+  fn read_from_domain(conn: &PgConnection, domain: &str) -> Result<InstanceHealth, Error>;
+  fn record_success(conn: &PgConnection, domain: &str) -> Result<InstanceHealth, Error>;
+  fn record_failure(conn: &PgConnection, domain: &str) -> Result<InstanceHealth, Error>;
+
+  /// Returns true if the caller is allowed to send a probe to the instance, which is the case if
+  /// no other probe was sent during the given interval.
+  fn claim_probe(conn: &PgConnection, domain: &str, interval: Duration) -> Result<bool, Error>;
+
+  /// An instance is considered dead if deliveries have failed without interruption for at least
+  /// the given duration.
+  fn is_dead(&self, dead_after: Duration) -> bool;
+}
+
+fn insert_if_missing(conn: &PgConnection, for_domain: &str) -> Result<usize, Error> {
+  use lemmy_db_schema::schema::instance_health::dsl::*;
+  let form = InstanceHealthForm {
+    domain: for_domain.to_string(),
+  };
+  insert_into(instance_health)
+    .values(form)
+    .on_conflict(domain)
+    .do_nothing()
+    .execute(conn)
+}
+
+impl InstanceHealth_ for InstanceHealth {
+  fn read_from_domain(conn: &PgConnection, for_domain: &str) -> Result<InstanceHealth, Error> {
+    use lemmy_db_schema::schema::instance_health::dsl::*;
+    instance_health
+      .filter(domain.eq(for_domain))
+      .first::<Self>(conn)
+  }
+
+  fn record_success(conn: &PgConnection, for_domain: &str) -> Result<InstanceHealth, Error> {
+    use lemmy_db_schema::schema::instance_health::dsl::*;
+    conn.transaction(|| {
+      insert_if_missing(conn, for_domain)?;
+      let now = naive_now();
+      diesel::update(instance_health.filter(domain.eq(for_domain)))
+        .set((
+          consecutive_failures.eq(0),
+          first_failed_delivery.eq::<Option<chrono::NaiveDateTime>>(None),
+          last_successful_delivery.eq(now),
+          updated.eq(now),
+        ))
+        .get_result::<Self>(conn)
+    })
+  }
+
+  fn record_failure(conn: &PgConnection, for_domain: &str) -> Result<InstanceHealth, Error> {
+    use lemmy_db_schema::schema::instance_health::dsl::*;
+    conn.transaction(|| {
+      insert_if_missing(conn, for_domain)?;
+      let now = naive_now();
+      diesel::update(
+        instance_health
+          .filter(domain.eq(for_domain))
+          .filter(first_failed_delivery.is_null()),
+      )
+      .set(first_failed_delivery.eq(now))
+      .execute(conn)?;
+      diesel::update(instance_health.filter(domain.eq(for_domain)))
+        .set((
+          consecutive_failures.eq(consecutive_failures + 1),
+          last_failed_delivery.eq(now),
+          updated.eq(now),
+        ))
+        .get_result::<Self>(conn)
+    })
+  }
+
+  fn claim_probe(conn: &PgConnection, for_domain: &str, interval: Duration) -> Result<bool, Error> {
+    use lemmy_db_schema::schema::instance_health::dsl::*;
+    let now = naive_now();
+    let updated_rows = diesel::update(
+      instance_health
+        .filter(domain.eq(for_domain))
+        .filter(last_probe.is_null().or(last_probe.lt(now - interval))),
+    )
+    .set(last_probe.eq(now))
+    .execute(conn)?;
+    Ok(updated_rows == 1)
+  }
+
+  fn is_dead(&self, dead_after: Duration) -> bool {
+    match self.first_failed_delivery {
+      Some(first_failed) => {
+        self.consecutive_failures > 0 && first_failed < naive_now() - dead_after
+      }
+      None => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::instance_health::InstanceHealth_};
+  use chrono::Duration;
+  use diesel::RunQueryDsl;
+  use lemmy_db_schema::source::instance_health::InstanceHealth;
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+    let domain = "health.example.com";
+
+    let first_failure = InstanceHealth::record_failure(&conn, domain).unwrap();
+    let second_failure = InstanceHealth::record_failure(&conn, domain).unwrap();
+    let read_health = InstanceHealth::read_from_domain(&conn, domain).unwrap();
+
+    let dead_immediately = read_health.is_dead(Duration::seconds(-1));
+    let dead_after_a_day = read_health.is_dead(Duration::days(1));
+
+    let first_probe = InstanceHealth::claim_probe(&conn, domain, Duration::hours(1)).unwrap();
+    let second_probe = InstanceHealth::claim_probe(&conn, domain, Duration::hours(1)).unwrap();
+
+    let recovered = InstanceHealth::record_success(&conn, domain).unwrap();
+
+    assert_eq!(1, first_failure.consecutive_failures);
+    assert_eq!(2, second_failure.consecutive_failures);
+    assert_eq!(
+      first_failure.first_failed_delivery,
+      second_failure.first_failed_delivery
+    );
+    assert_eq!(second_failure, read_health);
+    assert!(dead_immediately);
+    assert!(!dead_after_a_day);
+    assert!(first_probe);
+    assert!(!second_probe);
+    assert_eq!(0, recovered.consecutive_failures);
+    assert_eq!(None, recovered.first_failed_delivery);
+    assert!(recovered.last_successful_delivery.is_some());
+    assert!(!recovered.is_dead(Duration::seconds(-1)));
+
+    diesel::delete(&recovered).execute(&conn).unwrap();
+  }
+}
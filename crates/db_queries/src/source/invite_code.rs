@@ -0,0 +1,143 @@
+use crate::Crud;
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::{
+  naive_now,
+  schema::invite_code::dsl::*,
+  source::invite_code::{InviteCode, InviteCodeForm},
+  PersonId,
+};
+
+impl Crud for InviteCode {
+  type Form = InviteCodeForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, invite_code_id: i32) -> Result<Self, Error> {
+    invite_code.find(invite_code_id).first::<Self>(conn)
+  }
+  fn create(conn: &PgConnection, form: &InviteCodeForm) -> Result<Self, Error> {
+    insert_into(invite_code)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+  fn update(
+    conn: &PgConnection,
+    invite_code_id: i32,
+    form: &InviteCodeForm,
+  ) -> Result<Self, Error> {
+    diesel::update(invite_code.find(invite_code_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+  fn delete(conn: &PgConnection, invite_code_id: i32) -> Result<usize, Error> {
+    diesel::delete(invite_code.find(invite_code_id)).execute(conn)
+  }
+}
+
+pub trait InviteCode_ {
+  /// Reads the code, if it isn't expired or used up
+  fn read_valid(conn: &PgConnection, for_code: &str) -> Result<InviteCode, Error>;
+  /// Counts one use of the code, if it isn't expired or used up
+  fn use_code(conn: &PgConnection, for_code: &str) -> Result<InviteCode, Error>;
+  /// Lists all codes, or only those of the given creator
+  fn list(conn: &PgConnection, for_creator_id: Option<PersonId>) -> Result<Vec<InviteCode>, Error>;
+  fn count_valid_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<i64, Error>;
+}
+
+impl InviteCode_ for InviteCode {
+  fn read_valid(conn: &PgConnection, for_code: &str) -> Result<InviteCode, Error> {
+    invite_code
+      .filter(code.eq(for_code))
+      .filter(max_uses.is_null().or(uses.nullable().lt(max_uses)))
+      .filter(expires.is_null().or(expires.gt(naive_now())))
+      .first::<Self>(conn)
+  }
+
+  fn use_code(conn: &PgConnection, for_code: &str) -> Result<InviteCode, Error> {
+    let valid_code = invite_code
+      .filter(code.eq(for_code))
+      .filter(max_uses.is_null().or(uses.nullable().lt(max_uses)))
+      .filter(expires.is_null().or(expires.gt(naive_now())));
+    diesel::update(valid_code)
+      .set(uses.eq(uses + 1))
+      .get_result::<Self>(conn)
+  }
+
+  fn list(conn: &PgConnection, for_creator_id: Option<PersonId>) -> Result<Vec<InviteCode>, Error> {
+    let mut query = invite_code.into_boxed();
+    if let Some(for_creator_id) = for_creator_id {
+      query = query.filter(creator_id.eq(for_creator_id));
+    }
+    query.order_by(published.desc()).load::<Self>(conn)
+  }
+
+  fn count_valid_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<i64, Error> {
+    invite_code
+      .filter(creator_id.eq(for_creator_id))
+      .filter(max_uses.is_null().or(uses.nullable().lt(max_uses)))
+      .filter(expires.is_null().or(expires.gt(naive_now())))
+      .select(count(id))
+      .first::<i64>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::invite_code::InviteCode_, Crud};
+  use chrono::Duration;
+  use lemmy_db_schema::{
+    naive_now,
+    source::{
+      invite_code::{InviteCode, InviteCodeForm},
+      person::*,
+    },
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "invite_code_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let single_use_form = InviteCodeForm {
+      code: "single_use_code".into(),
+      creator_id: inserted_person.id,
+      max_uses: Some(1),
+      expires: None,
+    };
+    let single_use = InviteCode::create(&conn, &single_use_form).unwrap();
+
+    let expired_form = InviteCodeForm {
+      code: "expired_code".into(),
+      creator_id: inserted_person.id,
+      max_uses: None,
+      expires: Some(naive_now() - Duration::days(1)),
+    };
+    InviteCode::create(&conn, &expired_form).unwrap();
+
+    let read_single_use = InviteCode::read_valid(&conn, "single_use_code").unwrap();
+    let read_expired = InviteCode::read_valid(&conn, "expired_code");
+    let valid_count = InviteCode::count_valid_for_creator(&conn, inserted_person.id).unwrap();
+    let used = InviteCode::use_code(&conn, "single_use_code").unwrap();
+    let used_again = InviteCode::use_code(&conn, "single_use_code");
+    let valid_count_after_use =
+      InviteCode::count_valid_for_creator(&conn, inserted_person.id).unwrap();
+    let listed = InviteCode::list(&conn, Some(inserted_person.id)).unwrap();
+    let num_deleted = InviteCode::delete(&conn, single_use.id).unwrap();
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(single_use, read_single_use);
+    assert!(read_expired.is_err());
+    assert_eq!(1, valid_count);
+    assert_eq!(1, used.uses);
+    assert!(used_again.is_err());
+    assert_eq!(0, valid_count_after_use);
+    assert_eq!(2, listed.len());
+    assert_eq!(1, num_deleted);
+  }
+}
@@ -28,6 +28,9 @@ mod safe_settings_type {
     show_scores,
     show_read_posts,
     show_new_post_notifs,
+    email_verified,
+    accepted_application,
+    totp_2fa_enabled,
   );
 
   impl ToSafeSettings for LocalUser {
@@ -51,6 +54,9 @@ mod safe_settings_type {
         show_scores,
         show_read_posts,
         show_new_post_notifs,
+        email_verified,
+        accepted_application,
+        totp_2fa_enabled,
       )
     }
   }
@@ -63,6 +69,23 @@ pub trait LocalUser_ {
     local_user_id: LocalUserId,
     new_password: &str,
   ) -> Result<LocalUser, Error>;
+  /// Sets the email address of the user, and marks it as verified
+  fn set_verified_email(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    new_email: &str,
+  ) -> Result<LocalUser, Error>;
+  fn set_accepted_application(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    accepted: bool,
+  ) -> Result<LocalUser, Error>;
+  fn set_totp_2fa_secret(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    secret: Option<String>,
+    enabled: bool,
+  ) -> Result<LocalUser, Error>;
 }
 
 impl LocalUser_ for LocalUser {
@@ -89,6 +112,37 @@ impl LocalUser_ for LocalUser {
       ))
       .get_result::<Self>(conn)
   }
+
+  fn set_verified_email(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    new_email: &str,
+  ) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set((email.eq(new_email), email_verified.eq(true)))
+      .get_result::<Self>(conn)
+  }
+
+  fn set_accepted_application(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    accepted: bool,
+  ) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set(accepted_application.eq(accepted))
+      .get_result::<Self>(conn)
+  }
+
+  fn set_totp_2fa_secret(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    secret: Option<String>,
+    enabled: bool,
+  ) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set((totp_2fa_secret.eq(secret), totp_2fa_enabled.eq(enabled)))
+      .get_result::<Self>(conn)
+  }
 }
 
 impl Crud for LocalUser {
@@ -0,0 +1,136 @@
+use diesel::{dsl::*, result::Error, sql_types::Text, *};
+use lemmy_db_schema::{
+  source::local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterForm},
+  LocalUserId,
+};
+
+pub trait LocalUserKeywordFilter_ {
+  fn for_local_user(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+  ) -> Result<Vec<LocalUserKeywordFilter>, Error>;
+
+  /// Replaces all keyword filters of the user with the given ones.
+  fn replace_for_local_user(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    forms: &[LocalUserKeywordFilterForm],
+  ) -> Result<Vec<LocalUserKeywordFilter>, Error>;
+
+  /// Checks that the pattern is a valid regular expression for postgres, which does the matching.
+  fn is_valid_regex(conn: &PgConnection, pattern: &str) -> bool;
+}
+
+impl LocalUserKeywordFilter_ for LocalUserKeywordFilter {
+  fn for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<LocalUserKeywordFilter>, Error> {
+    use lemmy_db_schema::schema::local_user_keyword_filter::dsl::*;
+    local_user_keyword_filter
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(id)
+      .load::<Self>(conn)
+  }
+
+  fn replace_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    forms: &[LocalUserKeywordFilterForm],
+  ) -> Result<Vec<LocalUserKeywordFilter>, Error> {
+    use lemmy_db_schema::schema::local_user_keyword_filter::dsl::*;
+    conn.transaction(|| {
+      diesel::delete(local_user_keyword_filter.filter(local_user_id.eq(for_local_user_id)))
+        .execute(conn)?;
+      insert_into(local_user_keyword_filter)
+        .values(forms)
+        .execute(conn)?;
+      Self::for_local_user(conn, for_local_user_id)
+    })
+  }
+
+  fn is_valid_regex(conn: &PgConnection, pattern: &str) -> bool {
+    select(sql::<diesel::sql_types::Bool>("'' ~* ").bind::<Text, _>(pattern))
+      .get_result::<bool>(conn)
+      .is_ok()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::local_user_keyword_filter::LocalUserKeywordFilter_,
+    Crud,
+    KeywordFilterAction,
+  };
+  use lemmy_db_schema::source::{
+    local_user::{LocalUser, LocalUserForm},
+    local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterForm},
+    person::{Person, PersonForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "keyword_filter_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let form = LocalUserKeywordFilterForm {
+      local_user_id: inserted_local_user.id,
+      phrase: "spoiler".to_string(),
+      regex: false,
+      scope_title: true,
+      scope_body: true,
+      scope_comments: false,
+      action: KeywordFilterAction::Hide as i16,
+    };
+    let regex_form = LocalUserKeywordFilterForm {
+      phrase: "^polit(ics|ical)".to_string(),
+      regex: true,
+      action: KeywordFilterAction::Blur as i16,
+      ..form.clone()
+    };
+
+    let first_filters = LocalUserKeywordFilter::replace_for_local_user(
+      &conn,
+      inserted_local_user.id,
+      &[form.clone(), regex_form],
+    )
+    .unwrap();
+    let second_filters =
+      LocalUserKeywordFilter::replace_for_local_user(&conn, inserted_local_user.id, &[form])
+        .unwrap();
+    let read_filters =
+      LocalUserKeywordFilter::for_local_user(&conn, inserted_local_user.id).unwrap();
+
+    let valid_regex = LocalUserKeywordFilter::is_valid_regex(&conn, "^polit(ics|ical)");
+    let invalid_regex = LocalUserKeywordFilter::is_valid_regex(&conn, "polit(ics");
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+    let filters_after_delete =
+      LocalUserKeywordFilter::for_local_user(&conn, inserted_local_user.id).unwrap();
+
+    assert_eq!(2, first_filters.len());
+    assert!(first_filters[1].regex);
+    assert_eq!(1, second_filters.len());
+    assert_eq!("spoiler", second_filters[0].phrase);
+    assert_eq!(second_filters, read_filters);
+    assert!(valid_regex);
+    assert!(!invalid_regex);
+    assert!(filters_after_delete.is_empty());
+  }
+}
@@ -0,0 +1,239 @@
+use crate::hash_token;
+use chrono::Duration;
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::{
+  naive_now,
+  source::login_token::{LoginToken, LoginTokenForm},
+  LocalUserId,
+};
+
+pub trait LoginToken_ {
+  /// Creates a new login session, with the given refresh token.
+  fn create_session(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    refresh_token: &str,
+    ip: Option<String>,
+    user_agent: Option<String>,
+  ) -> Result<LoginToken, Error>;
+
+  /// Reads the session, if it belongs to the user and was used during the given duration.
+  fn read_active(
+    conn: &PgConnection,
+    login_token_id: i32,
+    local_user_id: LocalUserId,
+    inactive_after: Duration,
+  ) -> Result<LoginToken, Error>;
+
+  /// Replaces the refresh token of an active session with a new one, and marks it as used.
+  fn refresh(
+    conn: &PgConnection,
+    old_refresh_token: &str,
+    new_refresh_token: &str,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    inactive_after: Duration,
+  ) -> Result<LoginToken, Error>;
+
+  fn list_for_local_user(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+  ) -> Result<Vec<LoginToken>, Error>;
+
+  fn revoke(
+    conn: &PgConnection,
+    login_token_id: i32,
+    local_user_id: LocalUserId,
+  ) -> Result<usize, Error>;
+
+  fn revoke_all(conn: &PgConnection, local_user_id: LocalUserId) -> Result<usize, Error>;
+
+  fn delete_inactive(conn: &PgConnection, inactive_after: Duration) -> Result<usize, Error>;
+}
+
+impl LoginToken_ for LoginToken {
+  fn create_session(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    refresh_token: &str,
+    ip: Option<String>,
+    user_agent: Option<String>,
+  ) -> Result<LoginToken, Error> {
+    use lemmy_db_schema::schema::login_token;
+    let form = LoginTokenForm {
+      local_user_id: for_local_user_id,
+      token_encrypted: hash_token(refresh_token),
+      ip,
+      user_agent,
+    };
+    insert_into(login_token::table)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read_active(
+    conn: &PgConnection,
+    login_token_id: i32,
+    for_local_user_id: LocalUserId,
+    inactive_after: Duration,
+  ) -> Result<LoginToken, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    login_token
+      .find(login_token_id)
+      .filter(local_user_id.eq(for_local_user_id))
+      .filter(last_used.gt(naive_now() - inactive_after))
+      .first::<Self>(conn)
+  }
+
+  fn refresh(
+    conn: &PgConnection,
+    old_refresh_token: &str,
+    new_refresh_token: &str,
+    new_ip: Option<String>,
+    new_user_agent: Option<String>,
+    inactive_after: Duration,
+  ) -> Result<LoginToken, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    diesel::update(
+      login_token
+        .filter(token_encrypted.eq(hash_token(old_refresh_token)))
+        .filter(last_used.gt(naive_now() - inactive_after)),
+    )
+    .set((
+      token_encrypted.eq(hash_token(new_refresh_token)),
+      ip.eq(new_ip),
+      user_agent.eq(new_user_agent),
+      last_used.eq(naive_now()),
+    ))
+    .get_result::<Self>(conn)
+  }
+
+  fn list_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<LoginToken>, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    login_token
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(last_used.desc())
+      .load::<Self>(conn)
+  }
+
+  fn revoke(
+    conn: &PgConnection,
+    login_token_id: i32,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    diesel::delete(
+      login_token
+        .find(login_token_id)
+        .filter(local_user_id.eq(for_local_user_id)),
+    )
+    .execute(conn)
+  }
+
+  fn revoke_all(conn: &PgConnection, for_local_user_id: LocalUserId) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    diesel::delete(login_token.filter(local_user_id.eq(for_local_user_id))).execute(conn)
+  }
+
+  fn delete_inactive(conn: &PgConnection, inactive_after: Duration) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::login_token::dsl::*;
+    diesel::delete(login_token.filter(last_used.lt(naive_now() - inactive_after))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::login_token::LoginToken_, Crud};
+  use chrono::Duration;
+  use lemmy_db_schema::source::{
+    local_user::{LocalUser, LocalUserForm},
+    login_token::LoginToken,
+    person::{Person, PersonForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "login_token_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let local_user_id = inserted_local_user.id;
+    let inactive_after = Duration::days(30);
+
+    let first_session = LoginToken::create_session(
+      &conn,
+      local_user_id,
+      "first_refresh_token",
+      Some("127.0.0.1".to_string()),
+      Some("test browser".to_string()),
+    )
+    .unwrap();
+    let second_session =
+      LoginToken::create_session(&conn, local_user_id, "second_refresh_token", None, None).unwrap();
+
+    let read_session =
+      LoginToken::read_active(&conn, first_session.id, local_user_id, inactive_after).unwrap();
+    let read_expired_session = LoginToken::read_active(
+      &conn,
+      first_session.id,
+      local_user_id,
+      Duration::seconds(-1),
+    );
+
+    let refreshed_session = LoginToken::refresh(
+      &conn,
+      "first_refresh_token",
+      "third_refresh_token",
+      None,
+      Some("other browser".to_string()),
+      inactive_after,
+    )
+    .unwrap();
+    let refresh_with_old_token = LoginToken::refresh(
+      &conn,
+      "first_refresh_token",
+      "fourth_refresh_token",
+      None,
+      None,
+      inactive_after,
+    );
+
+    let sessions = LoginToken::list_for_local_user(&conn, local_user_id).unwrap();
+    let revoked = LoginToken::revoke(&conn, second_session.id, local_user_id).unwrap();
+    let read_revoked_session =
+      LoginToken::read_active(&conn, second_session.id, local_user_id, inactive_after);
+    let revoked_all = LoginToken::revoke_all(&conn, local_user_id).unwrap();
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_ne!("first_refresh_token", first_session.token_encrypted);
+    assert_eq!(first_session, read_session);
+    assert!(read_expired_session.is_err());
+    assert_eq!(first_session.id, refreshed_session.id);
+    assert_eq!(
+      Some("other browser".to_string()),
+      refreshed_session.user_agent
+    );
+    assert!(refresh_with_old_token.is_err());
+    assert_eq!(2, sessions.len());
+    assert_eq!(first_session.id, sessions[0].id);
+    assert_eq!(1, revoked);
+    assert!(read_revoked_session.is_err());
+    assert_eq!(1, revoked_all);
+  }
+}
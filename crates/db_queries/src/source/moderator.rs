@@ -318,6 +318,7 @@ mod tests {
       mod_person_id: inserted_mod.id,
       post_id: inserted_post.id,
       locked: None,
+      reason: None,
     };
     let inserted_mod_lock_post = ModLockPost::create(&conn, &mod_lock_post_form).unwrap();
     let read_mod_lock_post = ModLockPost::read(&conn, inserted_mod_lock_post.id).unwrap();
@@ -327,6 +328,7 @@ mod tests {
       mod_person_id: inserted_mod.id,
       locked: Some(true),
       when_: inserted_mod_lock_post.when_,
+      reason: None,
     };
 
     // sticky post
@@ -335,6 +337,7 @@ mod tests {
       mod_person_id: inserted_mod.id,
       post_id: inserted_post.id,
       stickied: None,
+      reason: None,
     };
     let inserted_mod_sticky_post = ModStickyPost::create(&conn, &mod_sticky_post_form).unwrap();
     let read_mod_sticky_post = ModStickyPost::read(&conn, inserted_mod_sticky_post.id).unwrap();
@@ -344,6 +347,7 @@ mod tests {
       mod_person_id: inserted_mod.id,
       stickied: Some(true),
       when_: inserted_mod_sticky_post.when_,
+      reason: None,
     };
 
     // comment
@@ -443,6 +447,10 @@ mod tests {
       other_person_id: inserted_person.id,
       community_id: inserted_community.id,
       removed: None,
+      reason: None,
+      can_remove: Some(true),
+      can_ban: Some(false),
+      ..ModAddCommunityForm::default()
     };
     let inserted_mod_add_community =
       ModAddCommunity::create(&conn, &mod_add_community_form).unwrap();
@@ -455,6 +463,12 @@ mod tests {
       other_person_id: inserted_person.id,
       removed: Some(false),
       when_: inserted_mod_add_community.when_,
+      reason: None,
+      can_remove: Some(true),
+      can_ban: Some(false),
+      can_manage_mods: None,
+      can_resolve_reports: None,
+      can_edit_community: None,
     };
 
     // mod add
@@ -463,6 +477,7 @@ mod tests {
       mod_person_id: inserted_mod.id,
       other_person_id: inserted_person.id,
       removed: None,
+      reason: None,
     };
     let inserted_mod_add = ModAdd::create(&conn, &mod_add_form).unwrap();
     let read_mod_add = ModAdd::read(&conn, inserted_mod_add.id).unwrap();
@@ -472,6 +487,7 @@ mod tests {
       other_person_id: inserted_person.id,
       removed: Some(false),
       when_: inserted_mod_add.when_,
+      reason: None,
     };
 
     Comment::delete(&conn, inserted_comment.id).unwrap();
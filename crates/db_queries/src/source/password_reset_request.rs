@@ -1,11 +1,10 @@
-use crate::Crud;
+use crate::{hash_token, Crud};
 use diesel::{dsl::*, result::Error, PgConnection, *};
 use lemmy_db_schema::{
   schema::password_reset_request::dsl::*,
   source::password_reset_request::*,
   LocalUserId,
 };
-use sha2::{Digest, Sha256};
 
 impl Crud for PasswordResetRequest {
   type Form = PasswordResetRequestForm;
@@ -46,9 +45,7 @@ impl PasswordResetRequest_ for PasswordResetRequest {
     from_local_user_id: LocalUserId,
     token: &str,
   ) -> Result<PasswordResetRequest, Error> {
-    let mut hasher = Sha256::new();
-    hasher.update(token);
-    let token_hash: String = bytes_to_hex(hasher.finalize().to_vec());
+    let token_hash = hash_token(token);
 
     let form = PasswordResetRequestForm {
       local_user_id: from_local_user_id,
@@ -58,9 +55,7 @@ impl PasswordResetRequest_ for PasswordResetRequest {
     Self::create(conn, &form)
   }
   fn read_from_token(conn: &PgConnection, token: &str) -> Result<PasswordResetRequest, Error> {
-    let mut hasher = Sha256::new();
-    hasher.update(token);
-    let token_hash: String = bytes_to_hex(hasher.finalize().to_vec());
+    let token_hash = hash_token(token);
     password_reset_request
       .filter(token_encrypted.eq(token_hash))
       .filter(published.gt(now - 1.days()))
@@ -68,14 +63,6 @@ impl PasswordResetRequest_ for PasswordResetRequest {
   }
 }
 
-fn bytes_to_hex(bytes: Vec<u8>) -> String {
-  let mut str = String::new();
-  for byte in bytes {
-    str = format!("{}{:02x}", str, byte);
-  }
-  str
-}
-
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -4,7 +4,10 @@ use lemmy_db_schema::{
   naive_now,
   schema::person::dsl::*,
   source::person::{Person, PersonForm},
+  CommentId,
+  DbUrl,
   PersonId,
+  PostId,
 };
 
 mod safe_type {
@@ -179,13 +182,38 @@ impl Crud for Person {
   }
 }
 
+/// A local post or comment in the outbox of a person
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PersonOutboxItem {
+  Post(PostId),
+  Comment(CommentId),
+}
+
 pub trait Person_ {
   fn ban_person(conn: &PgConnection, person_id: PersonId, ban: bool) -> Result<Person, Error>;
   fn add_admin(conn: &PgConnection, person_id: PersonId, added: bool) -> Result<Person, Error>;
   fn find_by_name(conn: &PgConnection, name: &str) -> Result<Person, Error>;
+  /// Like `find_by_name()`, but also returns deleted accounts
+  fn find_by_name_include_deleted(conn: &PgConnection, name: &str) -> Result<Person, Error>;
   fn mark_as_updated(conn: &PgConnection, person_id: PersonId) -> Result<Person, Error>;
   fn delete_account(conn: &PgConnection, person_id: PersonId) -> Result<Person, Error>;
   fn upsert(conn: &PgConnection, person_form: &PersonForm) -> Result<Person, Error>;
+  fn set_moved_to(
+    conn: &PgConnection,
+    person_id: PersonId,
+    new_actor_id: Option<DbUrl>,
+  ) -> Result<Person, Error>;
+  /// The shared inbox of each remote person, or their own inbox if they don't have one
+  fn remote_inboxes(conn: &PgConnection) -> Result<Vec<DbUrl>, Error>;
+  /// Local posts and comments created by the person, newest first. Deleted, removed and scheduled
+  /// items are left out.
+  fn read_outbox(
+    conn: &PgConnection,
+    person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<PersonOutboxItem>, Error>;
+  fn count_outbox(conn: &PgConnection, person_id: PersonId) -> Result<i64, Error>;
 }
 
 impl Person_ for Person {
@@ -209,6 +237,13 @@ impl Person_ for Person {
       .first::<Person>(conn)
   }
 
+  fn find_by_name_include_deleted(conn: &PgConnection, from_name: &str) -> Result<Person, Error> {
+    person
+      .filter(local.eq(true))
+      .filter(name.ilike(from_name))
+      .first::<Person>(conn)
+  }
+
   fn mark_as_updated(conn: &PgConnection, person_id: PersonId) -> Result<Person, Error> {
     diesel::update(person.find(person_id))
       .set((last_refreshed_at.eq(naive_now()),))
@@ -245,11 +280,75 @@ impl Person_ for Person {
       .set(person_form)
       .get_result::<Self>(conn)
   }
+
+  fn set_moved_to(
+    conn: &PgConnection,
+    person_id: PersonId,
+    new_actor_id: Option<DbUrl>,
+  ) -> Result<Person, Error> {
+    diesel::update(person.find(person_id))
+      .set(moved_to.eq(new_actor_id))
+      .get_result::<Self>(conn)
+  }
+
+  fn remote_inboxes(conn: &PgConnection) -> Result<Vec<DbUrl>, Error> {
+    let inboxes = person
+      .filter(local.eq(false))
+      .select((shared_inbox_url, inbox_url))
+      .distinct()
+      .load::<(Option<DbUrl>, DbUrl)>(conn)?;
+    Ok(
+      inboxes
+        .into_iter()
+        .map(|(shared_inbox, inbox)| shared_inbox.unwrap_or(inbox))
+        .collect(),
+    )
+  }
+
+  fn read_outbox(
+    conn: &PgConnection,
+    person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<PersonOutboxItem>, Error> {
+    use lemmy_db_schema::schema::person_outbox;
+    let items = person_outbox::table
+      .filter(person_outbox::creator_id.eq(person_id))
+      .select((person_outbox::kind, person_outbox::id))
+      .order_by((person_outbox::published.desc(), person_outbox::id.desc()))
+      .limit(limit)
+      .offset(offset)
+      .load::<(String, i32)>(conn)?;
+    Ok(
+      items
+        .into_iter()
+        .map(|(kind, item_id)| match kind.as_str() {
+          "post" => PersonOutboxItem::Post(PostId(item_id)),
+          _ => PersonOutboxItem::Comment(CommentId(item_id)),
+        })
+        .collect(),
+    )
+  }
+
+  fn count_outbox(conn: &PgConnection, person_id: PersonId) -> Result<i64, Error> {
+    use lemmy_db_schema::schema::person_outbox;
+    person_outbox::table
+      .filter(person_outbox::creator_id.eq(person_id))
+      .count()
+      .get_result(conn)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use crate::{establish_unpooled_connection, source::person::*};
+  use lemmy_db_schema::source::{
+    comment::{Comment, CommentForm},
+    community::{Community, CommunityForm},
+    post::{Post, PostForm},
+  };
+  use serial_test::serial;
+  use url::Url;
 
   #[test]
   fn test_crud() {
@@ -283,15 +382,90 @@ mod tests {
       inbox_url: inserted_person.inbox_url.to_owned(),
       shared_inbox_url: None,
       matrix_user_id: None,
+      moved_to: None,
+      also_known_as: vec![],
     };
 
     let read_person = Person::read(&conn, inserted_person.id).unwrap();
     let updated_person = Person::update(&conn, inserted_person.id, &new_person).unwrap();
+    let new_actor_id: DbUrl = Url::parse("http://example.com/u/holly").unwrap().into();
+    let moved_person =
+      Person::set_moved_to(&conn, inserted_person.id, Some(new_actor_id.clone())).unwrap();
     let num_deleted = Person::delete(&conn, inserted_person.id).unwrap();
 
     assert_eq!(expected_person, read_person);
     assert_eq!(expected_person, inserted_person);
     assert_eq!(expected_person, updated_person);
+    assert_eq!(Some(new_actor_id), moved_person.moved_to);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_outbox() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "outbox_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "outbox_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let post_form = |post_name: &str| PostForm {
+      name: post_name.into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &post_form("An outbox post")).unwrap();
+    let deleted_post = Post::create(
+      &conn,
+      &PostForm {
+        deleted: Some(true),
+        ..post_form("A deleted post")
+      },
+    )
+    .unwrap();
+    let scheduled_post = Post::create(
+      &conn,
+      &PostForm {
+        scheduled_publish_time: Some(Some(naive_now())),
+        ..post_form("A scheduled post")
+      },
+    )
+    .unwrap();
+
+    let comment_form = CommentForm {
+      content: "An outbox comment".into(),
+      creator_id: inserted_person.id,
+      post_id: inserted_post.id,
+      ..CommentForm::default()
+    };
+    let inserted_comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let count = Person::count_outbox(&conn, inserted_person.id).unwrap();
+    let first_page = Person::read_outbox(&conn, inserted_person.id, 1, 0).unwrap();
+    let second_page = Person::read_outbox(&conn, inserted_person.id, 1, 1).unwrap();
+
+    Comment::delete(&conn, inserted_comment.id).unwrap();
+    for p in [inserted_post.id, deleted_post.id, scheduled_post.id] {
+      Post::delete(&conn, p).unwrap();
+    }
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(2, count);
+    assert_eq!(
+      vec![PersonOutboxItem::Comment(inserted_comment.id)],
+      first_page
+    );
+    assert_eq!(vec![PersonOutboxItem::Post(inserted_post.id)], second_page);
+  }
 }
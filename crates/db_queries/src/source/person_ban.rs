@@ -0,0 +1,87 @@
+use crate::Bannable;
+use diesel::{insert_into, result::Error, *};
+use lemmy_db_schema::{
+  naive_now,
+  source::person_ban::{PersonBan, PersonBanForm},
+};
+
+pub trait PersonBan_ {
+  /// Returns the site bans which have expired
+  fn read_expired(conn: &PgConnection) -> Result<Vec<PersonBan>, Error>;
+}
+
+impl PersonBan_ for PersonBan {
+  fn read_expired(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::person_ban::dsl::*;
+    person_ban
+      .filter(expires.lt(naive_now()))
+      .load::<Self>(conn)
+  }
+}
+
+impl Bannable for PersonBan {
+  type Form = PersonBanForm;
+  fn ban(conn: &PgConnection, person_ban_form: &PersonBanForm) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::person_ban::dsl::*;
+    insert_into(person_ban)
+      .values(person_ban_form)
+      .on_conflict(person_id)
+      .do_update()
+      .set(person_ban_form)
+      .get_result::<Self>(conn)
+  }
+
+  fn unban(conn: &PgConnection, person_ban_form: &PersonBanForm) -> Result<usize, Error> {
+    use lemmy_db_schema::schema::person_ban::dsl::*;
+    diesel::delete(person_ban.filter(person_id.eq(person_ban_form.person_id))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::person_ban::PersonBan_, Bannable, Crud};
+  use chrono::Duration;
+  use lemmy_db_schema::{
+    naive_now,
+    source::{
+      person::{Person, PersonForm},
+      person_ban::{PersonBan, PersonBanForm},
+    },
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "temporarily_banned".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let ban_form = PersonBanForm {
+      person_id: inserted_person.id,
+      expires: Some(Some(naive_now() + Duration::days(7))),
+    };
+    let inserted_ban = PersonBan::ban(&conn, &ban_form).unwrap();
+    let not_expired = PersonBan::read_expired(&conn).unwrap();
+
+    // Banning again overwrites the expiry date
+    let expired_form = PersonBanForm {
+      expires: Some(Some(naive_now() - Duration::days(1))),
+      ..ban_form
+    };
+    let updated_ban = PersonBan::ban(&conn, &expired_form).unwrap();
+    let expired = PersonBan::read_expired(&conn).unwrap();
+
+    let num_unbanned = PersonBan::unban(&conn, &expired_form).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert!(not_expired.is_empty());
+    assert_eq!(inserted_ban.id, updated_ban.id);
+    assert_eq!(vec![updated_ban], expired);
+    assert_eq!(1, num_unbanned);
+  }
+}
@@ -1,4 +1,5 @@
 use crate::{Crud, DeleteableOrRemoveable, Likeable, Readable, Saveable};
+use chrono::NaiveDateTime;
 use diesel::{dsl::*, result::Error, *};
 use lemmy_db_schema::{
   naive_now,
@@ -9,6 +10,8 @@ use lemmy_db_schema::{
     PostLikeForm,
     PostRead,
     PostReadForm,
+    PostRevision,
+    PostRevisionForm,
     PostSaved,
     PostSavedForm,
   },
@@ -73,6 +76,11 @@ pub trait Post_ {
   ) -> Result<Post, Error>;
   fn is_post_creator(person_id: PersonId, post_creator_id: PersonId) -> bool;
   fn upsert(conn: &PgConnection, post_form: &PostForm) -> Result<Post, Error>;
+  fn list_due_scheduled(conn: &PgConnection) -> Result<Vec<Post>, Error>;
+  fn publish_scheduled(conn: &PgConnection, post_id: PostId) -> Result<Post, Error>;
+  fn list_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<Vec<Post>, Error>;
+  fn list_saved(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Post>, Error>;
+  fn read_many(conn: &PgConnection, post_ids: &[PostId]) -> Result<Vec<Post>, Error>;
 }
 
 impl Post_ for Post {
@@ -101,7 +109,14 @@ impl Post_ for Post {
     conn: &PgConnection,
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
-    use lemmy_db_schema::schema::post::dsl::*;
+    use lemmy_db_schema::schema::{post::dsl::*, post_revision::dsl as pr};
+
+    // Earlier versions would still contain the content
+    diesel::delete(
+      pr::post_revision
+        .filter(pr::post_id.eq_any(post.filter(creator_id.eq(for_creator_id)).select(id))),
+    )
+    .execute(conn)?;
 
     let perma_deleted = "*Permananently Deleted*";
     let perma_deleted_url = "https://deleted.com";
@@ -190,6 +205,107 @@ impl Post_ for Post {
       .set(post_form)
       .get_result::<Self>(conn)
   }
+
+  /// Scheduled posts whose publish time has been reached
+  fn list_due_scheduled(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::post::dsl::*;
+    post
+      .filter(scheduled_publish_time.le(naive_now()))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .load::<Self>(conn)
+  }
+
+  /// Clears the schedule of a post and sets its published time (and that of its aggregates) to now
+  fn publish_scheduled(conn: &PgConnection, post_id: PostId) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::{post::dsl::*, post_aggregates::dsl as pa};
+    let publish_time = naive_now();
+    conn.transaction(|| {
+      diesel::update(pa::post_aggregates.filter(pa::post_id.eq(post_id)))
+        .set((
+          pa::published.eq(publish_time),
+          pa::newest_comment_time.eq(publish_time),
+          pa::newest_comment_time_necro.eq(publish_time),
+        ))
+        .execute(conn)?;
+      diesel::update(post.find(post_id))
+        .set((
+          published.eq(publish_time),
+          scheduled_publish_time.eq(None::<NaiveDateTime>),
+        ))
+        .get_result::<Self>(conn)
+    })
+  }
+
+  /// All posts of the creator, including deleted, removed and scheduled ones
+  fn list_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::post::dsl::*;
+    post
+      .filter(creator_id.eq(for_creator_id))
+      .order_by((published.desc(), id.desc()))
+      .load::<Self>(conn)
+  }
+
+  /// All posts saved by the person, most recently saved first
+  fn list_saved(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::{post, post_saved};
+    post::table
+      .inner_join(post_saved::table)
+      .filter(post_saved::person_id.eq(for_person_id))
+      .select(post::all_columns)
+      .order_by(post_saved::published.desc())
+      .load::<Self>(conn)
+  }
+
+  fn read_many(conn: &PgConnection, post_ids: &[PostId]) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::post::dsl::*;
+    post.filter(id.eq_any(post_ids)).load::<Self>(conn)
+  }
+}
+
+pub trait PostRevision_ {
+  fn create_if_changed(
+    conn: &PgConnection,
+    old_post: &Post,
+    new_post: &Post,
+  ) -> Result<Option<PostRevision>, Error>;
+  fn list_for_post(conn: &PgConnection, for_post_id: PostId) -> Result<Vec<PostRevision>, Error>;
+}
+
+impl PostRevision_ for PostRevision {
+  /// Stores the old version of an edited post, unless only other fields than the content changed
+  fn create_if_changed(
+    conn: &PgConnection,
+    old_post: &Post,
+    new_post: &Post,
+  ) -> Result<Option<Self>, Error> {
+    use lemmy_db_schema::schema::post_revision::dsl::*;
+    if old_post.name == new_post.name
+      && old_post.url == new_post.url
+      && old_post.body == new_post.body
+    {
+      return Ok(None);
+    }
+    let form = PostRevisionForm {
+      post_id: old_post.id,
+      name: old_post.name.to_owned(),
+      url: old_post.url.to_owned(),
+      body: old_post.body.to_owned(),
+      published: old_post.updated.unwrap_or(old_post.published),
+    };
+    insert_into(post_revision)
+      .values(form)
+      .get_result::<Self>(conn)
+      .map(Some)
+  }
+
+  fn list_for_post(conn: &PgConnection, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    use lemmy_db_schema::schema::post_revision::dsl::*;
+    post_revision
+      .filter(post_id.eq(for_post_id))
+      .order_by(published.desc())
+      .load::<Self>(conn)
+  }
 }
 
 impl Likeable for PostLike {
@@ -332,6 +448,7 @@ mod tests {
       thumbnail_url: None,
       ap_id: inserted_post.ap_id.to_owned(),
       local: true,
+      scheduled_publish_time: None,
     };
 
     // Post Like
@@ -373,6 +490,9 @@ mod tests {
     };
 
     let inserted_post_read = PostRead::mark_as_read(&conn, &post_read_form).unwrap();
+    let creator_posts = Post::list_for_creator(&conn, inserted_person.id).unwrap();
+    let saved_posts = Post::list_saved(&conn, inserted_person.id).unwrap();
+    let many_posts = Post::read_many(&conn, &[inserted_post.id]).unwrap();
 
     let expected_post_read = PostRead {
       id: inserted_post_read.id,
@@ -381,8 +501,44 @@ mod tests {
       published: inserted_post_read.published,
     };
 
+    // Scheduled post
+    let scheduled_post_form = PostForm {
+      name: "A scheduled post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      scheduled_publish_time: Some(Some(naive_now())),
+      ..PostForm::default()
+    };
+    let scheduled_post = Post::create(&conn, &scheduled_post_form).unwrap();
+    let due_posts = Post::list_due_scheduled(&conn).unwrap();
+    let published_post = Post::publish_scheduled(&conn, scheduled_post.id).unwrap();
+    let due_posts_after_publish = Post::list_due_scheduled(&conn).unwrap();
+    Post::delete(&conn, scheduled_post.id).unwrap();
+
     let read_post = Post::read(&conn, inserted_post.id).unwrap();
     let updated_post = Post::update(&conn, inserted_post.id, &new_post).unwrap();
+
+    // Post Revision
+    let unchanged_revision =
+      PostRevision::create_if_changed(&conn, &inserted_post, &updated_post).unwrap();
+    let edited_post_form = PostForm {
+      body: Some("An edited body".into()),
+      ..new_post
+    };
+    let edited_post = Post::update(&conn, inserted_post.id, &edited_post_form).unwrap();
+    let inserted_revision = PostRevision::create_if_changed(&conn, &updated_post, &edited_post)
+      .unwrap()
+      .unwrap();
+    let expected_revision = PostRevision {
+      id: inserted_revision.id,
+      post_id: inserted_post.id,
+      name: "A test post".into(),
+      url: None,
+      body: None,
+      published: inserted_post.published,
+    };
+    let revisions = PostRevision::list_for_post(&conn, inserted_post.id).unwrap();
+
     let like_removed = PostLike::remove(&conn, inserted_person.id, inserted_post.id).unwrap();
     let saved_removed = PostSaved::unsave(&conn, &post_saved_form).unwrap();
     let read_removed = PostRead::mark_as_unread(&conn, &post_read_form).unwrap();
@@ -396,9 +552,21 @@ mod tests {
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
     assert_eq!(expected_post_read, inserted_post_read);
+    assert_eq!(vec![expected_post.to_owned()], creator_posts);
+    assert_eq!(vec![expected_post.to_owned()], saved_posts);
+    assert_eq!(vec![expected_post.to_owned()], many_posts);
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, read_removed);
     assert_eq!(1, num_deleted);
+    assert_eq!(None, unchanged_revision);
+    assert_eq!(expected_revision, inserted_revision);
+    assert_eq!(vec![expected_revision], revisions);
+    assert!(due_posts.iter().any(|p| p.id == scheduled_post.id));
+    assert_eq!(None, published_post.scheduled_publish_time);
+    assert!(published_post.published >= scheduled_post.published);
+    assert!(!due_posts_after_publish
+      .iter()
+      .any(|p| p.id == scheduled_post.id));
   }
 }
@@ -17,6 +17,28 @@ impl Reportable for PostReport {
       .get_result::<Self>(conn)
   }
 
+  /// creates a post report, or updates and re-opens the existing report of the same creator
+  ///
+  /// * `conn` - the postgres connection
+  /// * `post_report_form` - the filled PostReportForm to insert
+  fn report_or_reopen(
+    conn: &PgConnection,
+    post_report_form: &PostReportForm,
+  ) -> Result<Self, Error> {
+    use lemmy_db_schema::schema::post_report::dsl::*;
+    insert_into(post_report)
+      .values(post_report_form)
+      .on_conflict((post_id, creator_id))
+      .do_update()
+      .set((
+        post_report_form,
+        resolved.eq(false),
+        resolver_id.eq(None::<PersonId>),
+        updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+  }
+
   /// resolve a post report
   ///
   /// * `conn` - the postgres connection
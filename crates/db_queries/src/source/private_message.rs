@@ -58,6 +58,10 @@ pub trait PrivateMessage_ {
     conn: &PgConnection,
     private_message_form: &PrivateMessageForm,
   ) -> Result<PrivateMessage, Error>;
+  fn list_for_person(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+  ) -> Result<Vec<PrivateMessage>, Error>;
 }
 
 impl PrivateMessage_ for PrivateMessage {
@@ -132,6 +136,22 @@ impl PrivateMessage_ for PrivateMessage {
       .set(private_message_form)
       .get_result::<Self>(conn)
   }
+
+  /// All private messages sent or received by the person, including deleted ones
+  fn list_for_person(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+  ) -> Result<Vec<PrivateMessage>, Error> {
+    use lemmy_db_schema::schema::private_message::dsl::*;
+    private_message
+      .filter(
+        creator_id
+          .eq(for_person_id)
+          .or(recipient_id.eq(for_person_id)),
+      )
+      .order_by((published.desc(), id.desc()))
+      .load::<Self>(conn)
+  }
 }
 
 impl DeleteableOrRemoveable for PrivateMessage {
@@ -195,6 +215,8 @@ mod tests {
       PrivateMessage::update_deleted(&conn, inserted_private_message.id, true).unwrap();
     let marked_read_private_message =
       PrivateMessage::update_read(&conn, inserted_private_message.id, true).unwrap();
+    let recipient_private_messages =
+      PrivateMessage::list_for_person(&conn, inserted_recipient.id).unwrap();
     Person::delete(&conn, inserted_creator.id).unwrap();
     Person::delete(&conn, inserted_recipient.id).unwrap();
 
@@ -203,5 +225,9 @@ mod tests {
     assert_eq!(expected_private_message, inserted_private_message);
     assert!(deleted_private_message.deleted);
     assert!(marked_read_private_message.read);
+    assert_eq!(
+      vec![marked_read_private_message.to_owned()],
+      recipient_private_messages
+    );
   }
 }
@@ -0,0 +1,68 @@
+use crate::Crud;
+use diesel::{dsl::*, result::Error, PgConnection, *};
+use lemmy_db_schema::{
+  schema::registration_application::dsl::*,
+  source::registration_application::*,
+  LocalUserId,
+  PersonId,
+};
+
+impl Crud for RegistrationApplication {
+  type Form = RegistrationApplicationForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, registration_application_id: i32) -> Result<Self, Error> {
+    registration_application
+      .find(registration_application_id)
+      .first::<Self>(conn)
+  }
+  fn create(conn: &PgConnection, form: &RegistrationApplicationForm) -> Result<Self, Error> {
+    insert_into(registration_application)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+  fn update(
+    conn: &PgConnection,
+    registration_application_id: i32,
+    form: &RegistrationApplicationForm,
+  ) -> Result<Self, Error> {
+    diesel::update(registration_application.find(registration_application_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+pub trait RegistrationApplication_ {
+  fn find_by_local_user_id(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<RegistrationApplication, Error>;
+  /// Stores the decision of an admin. Approved applications have no deny reason.
+  fn decide(
+    conn: &PgConnection,
+    registration_application_id: i32,
+    for_admin_id: PersonId,
+    for_deny_reason: Option<String>,
+  ) -> Result<RegistrationApplication, Error>;
+}
+
+impl RegistrationApplication_ for RegistrationApplication {
+  fn find_by_local_user_id(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<RegistrationApplication, Error> {
+    registration_application
+      .filter(local_user_id.eq(for_local_user_id))
+      .first::<Self>(conn)
+  }
+
+  fn decide(
+    conn: &PgConnection,
+    registration_application_id: i32,
+    for_admin_id: PersonId,
+    for_deny_reason: Option<String>,
+  ) -> Result<RegistrationApplication, Error> {
+    diesel::update(registration_application.find(registration_application_id))
+      .set((admin_id.eq(for_admin_id), deny_reason.eq(for_deny_reason)))
+      .get_result::<Self>(conn)
+  }
+}
@@ -0,0 +1,145 @@
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::{
+  source::slur_filter::{SlurFilter, SlurFilterForm},
+  CommunityId,
+};
+
+pub trait SlurFilter_ {
+  /// The site-wide filters if `community_id` is None, otherwise those of the community.
+  fn list(conn: &PgConnection, community_id: Option<CommunityId>)
+    -> Result<Vec<SlurFilter>, Error>;
+
+  /// All filters which apply to content in the community, both site-wide and its own.
+  fn for_content_in(
+    conn: &PgConnection,
+    community_id: CommunityId,
+  ) -> Result<Vec<SlurFilter>, Error>;
+
+  /// Replaces the site-wide filters, or those of the community, with the given ones.
+  fn replace(
+    conn: &PgConnection,
+    community_id: Option<CommunityId>,
+    forms: &[SlurFilterForm],
+  ) -> Result<Vec<SlurFilter>, Error>;
+}
+
+impl SlurFilter_ for SlurFilter {
+  fn list(
+    conn: &PgConnection,
+    for_community_id: Option<CommunityId>,
+  ) -> Result<Vec<SlurFilter>, Error> {
+    use lemmy_db_schema::schema::slur_filter::dsl::*;
+    let query = slur_filter.into_boxed();
+    let query = match for_community_id {
+      Some(for_community_id) => query.filter(community_id.eq(for_community_id)),
+      None => query.filter(community_id.is_null()),
+    };
+    query.order_by(id).load::<Self>(conn)
+  }
+
+  fn for_content_in(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<SlurFilter>, Error> {
+    use lemmy_db_schema::schema::slur_filter::dsl::*;
+    slur_filter
+      .filter(community_id.is_null().or(community_id.eq(for_community_id)))
+      .order_by(id)
+      .load::<Self>(conn)
+  }
+
+  fn replace(
+    conn: &PgConnection,
+    for_community_id: Option<CommunityId>,
+    forms: &[SlurFilterForm],
+  ) -> Result<Vec<SlurFilter>, Error> {
+    use lemmy_db_schema::schema::slur_filter::dsl::*;
+    conn.transaction(|| {
+      match for_community_id {
+        Some(for_community_id) => {
+          diesel::delete(slur_filter.filter(community_id.eq(for_community_id))).execute(conn)?
+        }
+        None => diesel::delete(slur_filter.filter(community_id.is_null())).execute(conn)?,
+      };
+      insert_into(slur_filter).values(forms).execute(conn)?;
+      Self::list(conn, for_community_id)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::slur_filter::SlurFilter_,
+    Crud,
+    SlurFilterMode,
+  };
+  use lemmy_db_schema::source::{
+    community::{Community, CommunityForm},
+    slur_filter::{SlurFilter, SlurFilterForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_community = CommunityForm {
+      name: "slur_filter_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+    let other_community = CommunityForm {
+      name: "slur_filter_community_2".to_string(),
+      ..new_community
+    };
+    let other_community = Community::create(&conn, &other_community).unwrap();
+
+    let site_form = SlurFilterForm {
+      community_id: None,
+      phrase: "site_slur_filter_test".to_string(),
+      regex: false,
+      mode: SlurFilterMode::Replace as i16,
+    };
+    let community_form = SlurFilterForm {
+      community_id: Some(inserted_community.id),
+      phrase: "spoiler".to_string(),
+      regex: false,
+      mode: SlurFilterMode::Flag as i16,
+    };
+    let other_form = SlurFilterForm {
+      community_id: Some(other_community.id),
+      phrase: "^ending".to_string(),
+      regex: true,
+      mode: SlurFilterMode::Reject as i16,
+    };
+
+    let site_filters = SlurFilter::replace(&conn, None, &[site_form]).unwrap();
+    SlurFilter::replace(&conn, Some(inserted_community.id), &[community_form]).unwrap();
+    SlurFilter::replace(&conn, Some(other_community.id), &[other_form]).unwrap();
+
+    let community_filters = SlurFilter::list(&conn, Some(inserted_community.id)).unwrap();
+    let applicable = SlurFilter::for_content_in(&conn, inserted_community.id).unwrap();
+    let cleared = SlurFilter::replace(&conn, Some(inserted_community.id), &[]).unwrap();
+    let site_filters_after_clear = SlurFilter::list(&conn, None).unwrap();
+
+    SlurFilter::replace(&conn, None, &[]).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Community::delete(&conn, other_community.id).unwrap();
+    let other_after_delete = SlurFilter::list(&conn, Some(other_community.id)).unwrap();
+
+    assert_eq!(1, site_filters.len());
+    assert_eq!(1, community_filters.len());
+    assert_eq!("spoiler", community_filters[0].phrase);
+    assert_eq!(
+      vec![site_filters[0].id, community_filters[0].id],
+      applicable.iter().map(|f| f.id).collect::<Vec<_>>()
+    );
+    assert!(cleared.is_empty());
+    assert_eq!(site_filters, site_filters_after_clear);
+    assert!(other_after_delete.is_empty());
+  }
+}
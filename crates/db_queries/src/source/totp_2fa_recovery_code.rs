@@ -0,0 +1,129 @@
+use crate::hash_token;
+use diesel::{dsl::*, result::Error, *};
+use lemmy_db_schema::{
+  schema::totp_2fa_recovery_code::dsl::*,
+  source::totp_2fa_recovery_code::{Totp2faRecoveryCode, Totp2faRecoveryCodeForm},
+  LocalUserId,
+};
+
+pub trait Totp2faRecoveryCode_ {
+  /// Replaces all recovery codes of the user with the given ones
+  fn replace_for_local_user(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    codes: &[String],
+  ) -> Result<usize, Error>;
+
+  /// Deletes the code if it belongs to the user, so that it can only be used once. Returns false
+  /// if the code is invalid.
+  fn use_code(conn: &PgConnection, local_user_id: LocalUserId, code: &str) -> Result<bool, Error>;
+
+  fn delete_for_local_user(conn: &PgConnection, local_user_id: LocalUserId)
+    -> Result<usize, Error>;
+}
+
+impl Totp2faRecoveryCode_ for Totp2faRecoveryCode {
+  fn replace_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    codes: &[String],
+  ) -> Result<usize, Error> {
+    let forms = codes
+      .iter()
+      .map(|c| Totp2faRecoveryCodeForm {
+        local_user_id: for_local_user_id,
+        code_encrypted: hash_token(c),
+      })
+      .collect::<Vec<Totp2faRecoveryCodeForm>>();
+    conn.transaction(|| {
+      Self::delete_for_local_user(conn, for_local_user_id)?;
+      insert_into(totp_2fa_recovery_code)
+        .values(forms)
+        .execute(conn)
+    })
+  }
+
+  fn use_code(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    code: &str,
+  ) -> Result<bool, Error> {
+    let deleted = diesel::delete(
+      totp_2fa_recovery_code
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(code_encrypted.eq(hash_token(code))),
+    )
+    .execute(conn)?;
+    Ok(deleted > 0)
+  }
+
+  fn delete_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    diesel::delete(totp_2fa_recovery_code.filter(local_user_id.eq(for_local_user_id))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{local_user::LocalUser_, totp_2fa_recovery_code::Totp2faRecoveryCode_},
+    Crud,
+  };
+  use lemmy_db_schema::source::{
+    local_user::{LocalUser, LocalUserForm},
+    person::*,
+    totp_2fa_recovery_code::Totp2faRecoveryCode,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "totp_2fa_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let local_user_id = inserted_local_user.id;
+
+    let enabled_local_user =
+      LocalUser::set_totp_2fa_secret(&conn, local_user_id, Some("secret".into()), true).unwrap();
+
+    let codes = vec!["first_code".to_string(), "second_code".to_string()];
+    let inserted =
+      Totp2faRecoveryCode::replace_for_local_user(&conn, local_user_id, &codes).unwrap();
+    let used_first = Totp2faRecoveryCode::use_code(&conn, local_user_id, "first_code").unwrap();
+    let used_first_again =
+      Totp2faRecoveryCode::use_code(&conn, local_user_id, "first_code").unwrap();
+    let used_wrong = Totp2faRecoveryCode::use_code(&conn, local_user_id, "wrong_code").unwrap();
+    let deleted = Totp2faRecoveryCode::delete_for_local_user(&conn, local_user_id).unwrap();
+
+    let disabled_local_user =
+      LocalUser::set_totp_2fa_secret(&conn, local_user_id, None, false).unwrap();
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert!(!inserted_local_user.totp_2fa_enabled);
+    assert_eq!(Some("secret".into()), enabled_local_user.totp_2fa_secret);
+    assert!(enabled_local_user.totp_2fa_enabled);
+    assert_eq!(2, inserted);
+    assert!(used_first);
+    assert!(!used_first_again);
+    assert!(!used_wrong);
+    assert_eq!(1, deleted);
+    assert_eq!(None, disabled_local_user.totp_2fa_secret);
+    assert!(!disabled_local_user.totp_2fa_enabled);
+  }
+}
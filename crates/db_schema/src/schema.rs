@@ -10,6 +10,29 @@ table! {
     }
 }
 
+table! {
+    activity_queue_job (id) {
+        id -> Int4,
+        job_id -> Varchar,
+        queue -> Varchar,
+        status -> Varchar,
+        queued -> Bool,
+        runner_id -> Nullable<Varchar>,
+        retry_count -> Int4,
+        next_queue -> Nullable<Timestamp>,
+        data -> Jsonb,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    code_migration (name) {
+        name -> Text,
+        published -> Timestamp,
+    }
+}
+
 table! {
     comment (id) {
         id -> Int4,
@@ -72,6 +95,15 @@ table! {
     }
 }
 
+table! {
+    comment_revision (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
+    }
+}
+
 table! {
     community (id) {
         id -> Int4,
@@ -127,6 +159,11 @@ table! {
         community_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        can_remove -> Bool,
+        can_ban -> Bool,
+        can_manage_mods -> Bool,
+        can_resolve_reports -> Bool,
+        can_edit_community -> Bool,
     }
 }
 
@@ -136,6 +173,63 @@ table! {
         community_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        expires -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    email_verification (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        email -> Text,
+        verification_token -> Text,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    instance (id) {
+        id -> Int4,
+        domain -> Varchar,
+        blocked -> Bool,
+        reason -> Nullable<Text>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    instance_block (id) {
+        id -> Int4,
+        person_id -> Int4,
+        domain -> Varchar,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    instance_health (id) {
+        id -> Int4,
+        domain -> Varchar,
+        consecutive_failures -> Int4,
+        first_failed_delivery -> Nullable<Timestamp>,
+        last_failed_delivery -> Nullable<Timestamp>,
+        last_successful_delivery -> Nullable<Timestamp>,
+        last_probe -> Nullable<Timestamp>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    invite_code (id) {
+        id -> Int4,
+        code -> Text,
+        creator_id -> Int4,
+        max_uses -> Nullable<Int4>,
+        uses -> Int4,
+        expires -> Nullable<Timestamp>,
+        published -> Timestamp,
     }
 }
 
@@ -157,6 +251,36 @@ table! {
         show_scores -> Bool,
         show_read_posts -> Bool,
         show_new_post_notifs -> Bool,
+        email_verified -> Bool,
+        accepted_application -> Bool,
+        totp_2fa_secret -> Nullable<Text>,
+        totp_2fa_enabled -> Bool,
+    }
+}
+
+table! {
+    local_user_keyword_filter (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        phrase -> Text,
+        regex -> Bool,
+        scope_title -> Bool,
+        scope_body -> Bool,
+        scope_comments -> Bool,
+        action -> Int2,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    login_token (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        token_encrypted -> Text,
+        ip -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        published -> Timestamp,
+        last_used -> Timestamp,
     }
 }
 
@@ -167,6 +291,7 @@ table! {
         other_person_id -> Int4,
         removed -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason -> Nullable<Text>,
     }
 }
 
@@ -178,6 +303,12 @@ table! {
         community_id -> Int4,
         removed -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason -> Nullable<Text>,
+        can_remove -> Nullable<Bool>,
+        can_ban -> Nullable<Bool>,
+        can_manage_mods -> Nullable<Bool>,
+        can_resolve_reports -> Nullable<Bool>,
+        can_edit_community -> Nullable<Bool>,
     }
 }
 
@@ -189,6 +320,7 @@ table! {
         community_id -> Int4,
         removed -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason -> Nullable<Text>,
     }
 }
 
@@ -224,6 +356,7 @@ table! {
         post_id -> Int4,
         locked -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason -> Nullable<Text>,
     }
 }
 
@@ -268,6 +401,18 @@ table! {
         post_id -> Int4,
         stickied -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason -> Nullable<Text>,
+    }
+}
+
+table! {
+    modlog_combined (kind, id) {
+        kind -> Text,
+        id -> Int4,
+        when_ -> Timestamp,
+        mod_person_id -> Int4,
+        other_person_id -> Nullable<Int4>,
+        community_id -> Nullable<Int4>,
     }
 }
 
@@ -302,6 +447,8 @@ table! {
         matrix_user_id -> Nullable<Text>,
         admin -> Bool,
         bot_account -> Bool,
+        moved_to -> Nullable<Varchar>,
+        also_known_as -> Array<Text>,
     }
 }
 
@@ -321,6 +468,7 @@ table! {
         id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        expires -> Nullable<Timestamp>,
     }
 }
 
@@ -334,6 +482,15 @@ table! {
     }
 }
 
+table! {
+    person_outbox (kind, id) {
+        kind -> Text,
+        id -> Int4,
+        creator_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 table! {
     post (id) {
         id -> Int4,
@@ -355,6 +512,7 @@ table! {
         thumbnail_url -> Nullable<Text>,
         ap_id -> Varchar,
         local -> Bool,
+        scheduled_publish_time -> Nullable<Timestamp>,
     }
 }
 
@@ -417,6 +575,17 @@ table! {
     }
 }
 
+table! {
+    post_revision (id) {
+        id -> Int4,
+        post_id -> Int4,
+        name -> Varchar,
+        url -> Nullable<Text>,
+        body -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 table! {
     private_message (id) {
         id -> Int4,
@@ -432,6 +601,17 @@ table! {
     }
 }
 
+table! {
+    registration_application (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        answer -> Text,
+        admin_id -> Nullable<Int4>,
+        deny_reason -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 table! {
     site (id) {
         id -> Int4,
@@ -447,6 +627,21 @@ table! {
         banner -> Nullable<Varchar>,
         description -> Nullable<Text>,
         community_creation_admin_only -> Bool,
+        require_email_verification -> Bool,
+        require_application -> Bool,
+        application_question -> Nullable<Text>,
+        require_totp_2fa_for_admins -> Bool,
+    }
+}
+
+table! {
+    slur_filter (id) {
+        id -> Int4,
+        community_id -> Nullable<Int4>,
+        phrase -> Text,
+        regex -> Bool,
+        mode -> Int2,
+        published -> Timestamp,
     }
 }
 
@@ -465,6 +660,15 @@ table! {
     }
 }
 
+table! {
+    totp_2fa_recovery_code (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        code_encrypted -> Text,
+        published -> Timestamp,
+    }
+}
+
 table! {
     person_block (id) {
         id -> Int4,
@@ -523,6 +727,8 @@ table! {
         matrix_user_id -> Nullable<Text>,
         admin -> Bool,
         bot_account -> Bool,
+        moved_to -> Nullable<Varchar>,
+        also_known_as -> Array<Text>,
     }
 }
 
@@ -548,6 +754,8 @@ table! {
         matrix_user_id -> Nullable<Text>,
         admin -> Bool,
         bot_account -> Bool,
+        moved_to -> Nullable<Varchar>,
+        also_known_as -> Array<Text>,
     }
 }
 
@@ -579,9 +787,12 @@ joinable!(comment_like -> post (post_id));
 joinable!(comment_report -> comment (comment_id));
 joinable!(comment_saved -> comment (comment_id));
 joinable!(comment_saved -> person (person_id));
+joinable!(comment_revision -> comment (comment_id));
 joinable!(community_aggregates -> community (community_id));
 joinable!(community_block -> community (community_id));
 joinable!(community_block -> person (person_id));
+joinable!(instance_block -> person (person_id));
+joinable!(invite_code -> person (creator_id));
 joinable!(community_follower -> community (community_id));
 joinable!(community_follower -> person (person_id));
 joinable!(community_moderator -> community (community_id));
@@ -589,6 +800,11 @@ joinable!(community_moderator -> person (person_id));
 joinable!(community_person_ban -> community (community_id));
 joinable!(community_person_ban -> person (person_id));
 joinable!(local_user -> person (person_id));
+joinable!(local_user_keyword_filter -> local_user (local_user_id));
+joinable!(email_verification -> local_user (local_user_id));
+joinable!(login_token -> local_user (local_user_id));
+joinable!(registration_application -> local_user (local_user_id));
+joinable!(totp_2fa_recovery_code -> local_user (local_user_id));
 joinable!(mod_add_community -> community (community_id));
 joinable!(mod_transfer_community -> community (community_id));
 joinable!(mod_ban_from_community -> community (community_id));
@@ -617,23 +833,35 @@ joinable!(post_read -> post (post_id));
 joinable!(post_report -> post (post_id));
 joinable!(post_saved -> person (person_id));
 joinable!(post_saved -> post (post_id));
+joinable!(post_revision -> post (post_id));
 joinable!(site -> person (creator_id));
+joinable!(slur_filter -> community (community_id));
 joinable!(site_aggregates -> site (site_id));
 
 allow_tables_to_appear_in_same_query!(
   activity,
+  activity_queue_job,
+  code_migration,
   comment,
   comment_aggregates,
   community_block,
   comment_like,
   comment_report,
   comment_saved,
+  comment_revision,
   community,
   community_aggregates,
   community_follower,
   community_moderator,
   community_person_ban,
+  email_verification,
+  instance,
+  instance_block,
+  instance_health,
+  invite_code,
   local_user,
+  local_user_keyword_filter,
+  login_token,
   mod_add,
   mod_add_community,
   mod_transfer_community,
@@ -644,21 +872,27 @@ allow_tables_to_appear_in_same_query!(
   mod_remove_community,
   mod_remove_post,
   mod_sticky_post,
+  modlog_combined,
   password_reset_request,
   person,
   person_aggregates,
   person_ban,
   person_block,
   person_mention,
+  person_outbox,
   post,
   post_aggregates,
   post_like,
   post_read,
   post_report,
   post_saved,
+  post_revision,
   private_message,
+  registration_application,
   site,
   site_aggregates,
+  slur_filter,
+  totp_2fa_recovery_code,
   comment_alias_1,
   person_alias_1,
   person_alias_2,
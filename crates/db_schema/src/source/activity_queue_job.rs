@@ -0,0 +1,30 @@
+use crate::schema::activity_queue_job;
+use serde_json::Value;
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone)]
+#[table_name = "activity_queue_job"]
+pub struct ActivityQueueJob {
+  pub id: i32,
+  pub job_id: String,
+  pub queue: String,
+  pub status: String,
+  pub queued: bool,
+  pub runner_id: Option<String>,
+  pub retry_count: i32,
+  pub next_queue: Option<chrono::NaiveDateTime>,
+  pub data: Value,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "activity_queue_job"]
+pub struct ActivityQueueJobForm {
+  pub job_id: String,
+  pub queue: String,
+  pub status: String,
+  pub retry_count: i32,
+  pub next_queue: Option<Option<chrono::NaiveDateTime>>,
+  pub data: Value,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
@@ -1,5 +1,5 @@
 use crate::{
-  schema::{comment, comment_alias_1, comment_like, comment_saved},
+  schema::{comment, comment_alias_1, comment_like, comment_revision, comment_saved},
   source::post::Post,
   CommentId,
   DbUrl,
@@ -110,6 +110,26 @@ pub struct CommentSavedForm {
   pub person_id: PersonId,
 }
 
+/// An earlier version of a comment, stored whenever its content is edited
+#[derive(Identifiable, Queryable, Associations, PartialEq, Debug, Serialize, Clone)]
+#[belongs_to(Comment)]
+#[table_name = "comment_revision"]
+pub struct CommentRevision {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub content: String,
+  /// When this version of the comment was created
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "comment_revision"]
+pub struct CommentRevisionForm {
+  pub comment_id: CommentId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
+
 impl ApubObject for Comment {
   type DataType = PgConnection;
 
@@ -8,7 +8,7 @@ use chrono::NaiveDateTime;
 use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use lemmy_apub_lib::traits::{ActorType, ApubObject};
 use lemmy_utils::LemmyError;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -85,6 +85,11 @@ pub struct CommunityModerator {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub can_remove: bool,
+  pub can_ban: bool,
+  pub can_manage_mods: bool,
+  pub can_resolve_reports: bool,
+  pub can_edit_community: bool,
 }
 
 #[derive(Insertable, AsChangeset, Clone)]
@@ -94,6 +99,60 @@ pub struct CommunityModeratorForm {
   pub person_id: PersonId,
 }
 
+/// What a community moderator is allowed to do. Admins and the top mod of a community can always
+/// do everything.
+#[derive(Queryable, AsChangeset, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "community_moderator"]
+pub struct ModPermissions {
+  /// Remove, lock and sticky posts and comments
+  pub can_remove: bool,
+  pub can_ban: bool,
+  /// Add and remove other mods, and change their permissions
+  pub can_manage_mods: bool,
+  pub can_resolve_reports: bool,
+  pub can_edit_community: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ModPermission {
+  Remove,
+  Ban,
+  ManageMods,
+  ResolveReports,
+  EditCommunity,
+}
+
+impl ModPermissions {
+  pub fn all() -> Self {
+    ModPermissions {
+      can_remove: true,
+      can_ban: true,
+      can_manage_mods: true,
+      can_resolve_reports: true,
+      can_edit_community: true,
+    }
+  }
+
+  pub fn has(&self, permission: ModPermission) -> bool {
+    match permission {
+      ModPermission::Remove => self.can_remove,
+      ModPermission::Ban => self.can_ban,
+      ModPermission::ManageMods => self.can_manage_mods,
+      ModPermission::ResolveReports => self.can_resolve_reports,
+      ModPermission::EditCommunity => self.can_edit_community,
+    }
+  }
+
+  /// True if these permissions don't allow anything which `other` doesn't allow
+  pub fn is_subset_of(&self, other: &ModPermissions) -> bool {
+    (!self.can_remove || other.can_remove)
+      && (!self.can_ban || other.can_ban)
+      && (!self.can_manage_mods || other.can_manage_mods)
+      && (!self.can_resolve_reports || other.can_resolve_reports)
+      && (!self.can_edit_community || other.can_edit_community)
+  }
+}
+
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
 #[belongs_to(Community)]
 #[table_name = "community_person_ban"]
@@ -102,6 +161,7 @@ pub struct CommunityPersonBan {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub expires: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Insertable, AsChangeset, Clone)]
@@ -109,6 +169,7 @@ pub struct CommunityPersonBan {
 pub struct CommunityPersonBanForm {
   pub community_id: CommunityId,
   pub person_id: PersonId,
+  pub expires: Option<Option<chrono::NaiveDateTime>>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
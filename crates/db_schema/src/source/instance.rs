@@ -0,0 +1,22 @@
+use crate::schema::instance;
+use serde::Serialize;
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone, Serialize)]
+#[table_name = "instance"]
+pub struct Instance {
+  pub id: i32,
+  pub domain: String,
+  pub blocked: bool,
+  pub reason: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "instance"]
+pub struct InstanceForm {
+  pub domain: String,
+  pub blocked: bool,
+  pub reason: Option<Option<String>>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
@@ -0,0 +1,18 @@
+use crate::{schema::instance_block, InstanceBlockId, PersonId};
+use serde::Serialize;
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "instance_block"]
+pub struct InstanceBlock {
+  pub id: InstanceBlockId,
+  pub person_id: PersonId,
+  pub domain: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "instance_block"]
+pub struct InstanceBlockForm {
+  pub person_id: PersonId,
+  pub domain: String,
+}
@@ -0,0 +1,21 @@
+use crate::schema::instance_health;
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone)]
+#[table_name = "instance_health"]
+pub struct InstanceHealth {
+  pub id: i32,
+  pub domain: String,
+  pub consecutive_failures: i32,
+  pub first_failed_delivery: Option<chrono::NaiveDateTime>,
+  pub last_failed_delivery: Option<chrono::NaiveDateTime>,
+  pub last_successful_delivery: Option<chrono::NaiveDateTime>,
+  pub last_probe: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "instance_health"]
+pub struct InstanceHealthForm {
+  pub domain: String,
+}
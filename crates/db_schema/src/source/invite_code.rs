@@ -0,0 +1,25 @@
+use crate::{schema::invite_code, PersonId};
+use serde::Serialize;
+
+/// A code which lets people register while registration is closed
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "invite_code"]
+pub struct InviteCode {
+  pub id: i32,
+  pub code: String,
+  pub creator_id: PersonId,
+  /// Unlimited if not set
+  pub max_uses: Option<i32>,
+  pub uses: i32,
+  pub expires: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "invite_code"]
+pub struct InviteCodeForm {
+  pub code: String,
+  pub creator_id: PersonId,
+  pub max_uses: Option<i32>,
+  pub expires: Option<chrono::NaiveDateTime>,
+}
@@ -20,6 +20,11 @@ pub struct LocalUser {
   pub show_scores: bool,
   pub show_read_posts: bool,
   pub show_new_post_notifs: bool,
+  pub email_verified: bool,
+  pub accepted_application: bool,
+  #[serde(skip)]
+  pub totp_2fa_secret: Option<String>,
+  pub totp_2fa_enabled: bool,
 }
 
 // TODO redo these, check table defaults
@@ -40,6 +45,10 @@ pub struct LocalUserForm {
   pub show_scores: Option<bool>,
   pub show_read_posts: Option<bool>,
   pub show_new_post_notifs: Option<bool>,
+  pub email_verified: Option<bool>,
+  pub accepted_application: Option<bool>,
+  pub totp_2fa_secret: Option<Option<String>>,
+  pub totp_2fa_enabled: Option<bool>,
 }
 
 /// A local user view that removes password encrypted
@@ -61,4 +70,7 @@ pub struct LocalUserSettings {
   pub show_scores: bool,
   pub show_read_posts: bool,
   pub show_new_post_notifs: bool,
+  pub email_verified: bool,
+  pub accepted_application: bool,
+  pub totp_2fa_enabled: bool,
 }
@@ -0,0 +1,28 @@
+use crate::{schema::local_user_keyword_filter, LocalUserId};
+use serde::Serialize;
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "local_user_keyword_filter"]
+pub struct LocalUserKeywordFilter {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub phrase: String,
+  pub regex: bool,
+  pub scope_title: bool,
+  pub scope_body: bool,
+  pub scope_comments: bool,
+  pub action: i16,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "local_user_keyword_filter"]
+pub struct LocalUserKeywordFilterForm {
+  pub local_user_id: LocalUserId,
+  pub phrase: String,
+  pub regex: bool,
+  pub scope_title: bool,
+  pub scope_body: bool,
+  pub scope_comments: bool,
+  pub action: i16,
+}
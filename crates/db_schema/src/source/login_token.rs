@@ -0,0 +1,25 @@
+use crate::{schema::login_token, LocalUserId};
+use serde::Serialize;
+
+/// A login session of a local user
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "login_token"]
+pub struct LoginToken {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  #[serde(skip)]
+  pub token_encrypted: String,
+  pub ip: Option<String>,
+  pub user_agent: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub last_used: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "login_token"]
+pub struct LoginTokenForm {
+  pub local_user_id: LocalUserId,
+  pub token_encrypted: String,
+  pub ip: Option<String>,
+  pub user_agent: Option<String>,
+}
@@ -1,16 +1,28 @@
 pub mod activity;
+pub mod activity_queue_job;
 pub mod comment;
 pub mod comment_report;
 pub mod community;
 pub mod community_block;
+pub mod email_verification;
+pub mod instance;
+pub mod instance_block;
+pub mod instance_health;
+pub mod invite_code;
 pub mod local_user;
+pub mod local_user_keyword_filter;
+pub mod login_token;
 pub mod moderator;
 pub mod password_reset_request;
 pub mod person;
+pub mod person_ban;
 pub mod person_block;
 pub mod person_mention;
 pub mod post;
 pub mod post_report;
 pub mod private_message;
+pub mod registration_application;
 pub mod secret;
 pub mod site;
+pub mod slur_filter;
+pub mod totp_2fa_recovery_code;
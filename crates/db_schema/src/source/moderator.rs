@@ -46,6 +46,7 @@ pub struct ModLockPost {
   pub post_id: PostId,
   pub locked: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -54,6 +55,7 @@ pub struct ModLockPostForm {
   pub mod_person_id: PersonId,
   pub post_id: PostId,
   pub locked: Option<bool>,
+  pub reason: Option<String>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -64,6 +66,7 @@ pub struct ModStickyPost {
   pub post_id: PostId,
   pub stickied: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -72,6 +75,7 @@ pub struct ModStickyPostForm {
   pub mod_person_id: PersonId,
   pub post_id: PostId,
   pub stickied: Option<bool>,
+  pub reason: Option<String>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -171,15 +175,28 @@ pub struct ModAddCommunity {
   pub community_id: CommunityId,
   pub removed: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason: Option<String>,
+  /// The permissions which the mod was given. Not set if they were removed.
+  pub can_remove: Option<bool>,
+  pub can_ban: Option<bool>,
+  pub can_manage_mods: Option<bool>,
+  pub can_resolve_reports: Option<bool>,
+  pub can_edit_community: Option<bool>,
 }
 
-#[derive(Insertable, AsChangeset)]
+#[derive(Insertable, AsChangeset, Default)]
 #[table_name = "mod_add_community"]
 pub struct ModAddCommunityForm {
   pub mod_person_id: PersonId,
   pub other_person_id: PersonId,
   pub community_id: CommunityId,
   pub removed: Option<bool>,
+  pub reason: Option<String>,
+  pub can_remove: Option<bool>,
+  pub can_ban: Option<bool>,
+  pub can_manage_mods: Option<bool>,
+  pub can_resolve_reports: Option<bool>,
+  pub can_edit_community: Option<bool>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -191,6 +208,7 @@ pub struct ModTransferCommunity {
   pub community_id: CommunityId,
   pub removed: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -200,6 +218,7 @@ pub struct ModTransferCommunityForm {
   pub other_person_id: PersonId,
   pub community_id: CommunityId,
   pub removed: Option<bool>,
+  pub reason: Option<String>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -210,6 +229,7 @@ pub struct ModAdd {
   pub other_person_id: PersonId,
   pub removed: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -218,4 +238,5 @@ pub struct ModAddForm {
   pub mod_person_id: PersonId,
   pub other_person_id: PersonId,
   pub removed: Option<bool>,
+  pub reason: Option<String>,
 }
@@ -33,6 +33,8 @@ pub struct Person {
   pub matrix_user_id: Option<String>,
   pub admin: bool,
   pub bot_account: bool,
+  pub moved_to: Option<DbUrl>,
+  pub also_known_as: Vec<DbUrl>,
 }
 
 /// A safe representation of person, without the sensitive info
@@ -81,6 +83,8 @@ pub struct PersonAlias1 {
   pub matrix_user_id: Option<String>,
   pub admin: bool,
   pub bot_account: bool,
+  pub moved_to: Option<DbUrl>,
+  pub also_known_as: Vec<DbUrl>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -128,6 +132,8 @@ pub struct PersonAlias2 {
   pub matrix_user_id: Option<String>,
   pub admin: bool,
   pub bot_account: bool,
+  pub moved_to: Option<DbUrl>,
+  pub also_known_as: Vec<DbUrl>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
@@ -174,6 +180,8 @@ pub struct PersonForm {
   pub matrix_user_id: Option<Option<String>>,
   pub admin: Option<bool>,
   pub bot_account: Option<bool>,
+  pub moved_to: Option<Option<DbUrl>>,
+  pub also_known_as: Option<Vec<DbUrl>>,
 }
 
 impl ApubObject for Person {
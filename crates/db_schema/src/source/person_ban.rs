@@ -0,0 +1,17 @@
+use crate::{schema::person_ban, PersonId};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "person_ban"]
+pub struct PersonBan {
+  pub id: i32,
+  pub person_id: PersonId,
+  pub published: chrono::NaiveDateTime,
+  pub expires: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "person_ban"]
+pub struct PersonBanForm {
+  pub person_id: PersonId,
+  pub expires: Option<Option<chrono::NaiveDateTime>>,
+}
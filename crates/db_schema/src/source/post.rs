@@ -1,5 +1,5 @@
 use crate::{
-  schema::{post, post_like, post_read, post_saved},
+  schema::{post, post_like, post_read, post_revision, post_saved},
   CommunityId,
   DbUrl,
   PersonId,
@@ -34,6 +34,7 @@ pub struct Post {
   pub thumbnail_url: Option<DbUrl>,
   pub ap_id: DbUrl,
   pub local: bool,
+  pub scheduled_publish_time: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Insertable, AsChangeset, Default)]
@@ -57,6 +58,7 @@ pub struct PostForm {
   pub thumbnail_url: Option<DbUrl>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  pub scheduled_publish_time: Option<Option<chrono::NaiveDateTime>>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
@@ -112,6 +114,30 @@ pub struct PostReadForm {
   pub person_id: PersonId,
 }
 
+/// An earlier version of a post, stored whenever its content is edited
+#[derive(Identifiable, Queryable, Associations, PartialEq, Debug, Serialize, Clone)]
+#[belongs_to(Post)]
+#[table_name = "post_revision"]
+pub struct PostRevision {
+  pub id: i32,
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  /// When this version of the post was created
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "post_revision"]
+pub struct PostRevisionForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
+
 impl ApubObject for Post {
   type DataType = PgConnection;
 
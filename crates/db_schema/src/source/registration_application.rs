@@ -0,0 +1,22 @@
+use crate::{schema::registration_application, LocalUserId, PersonId};
+use serde::Serialize;
+
+/// An application to join the site, which has to be approved by an admin
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "registration_application"]
+pub struct RegistrationApplication {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub answer: String,
+  /// The admin who approved or denied the application
+  pub admin_id: Option<PersonId>,
+  pub deny_reason: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "registration_application"]
+pub struct RegistrationApplicationForm {
+  pub local_user_id: LocalUserId,
+  pub answer: String,
+}
@@ -0,0 +1,22 @@
+use crate::{schema::slur_filter, CommunityId};
+use serde::Serialize;
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize)]
+#[table_name = "slur_filter"]
+pub struct SlurFilter {
+  pub id: i32,
+  pub community_id: Option<CommunityId>,
+  pub phrase: String,
+  pub regex: bool,
+  pub mode: i16,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "slur_filter"]
+pub struct SlurFilterForm {
+  pub community_id: Option<CommunityId>,
+  pub phrase: String,
+  pub regex: bool,
+  pub mode: i16,
+}
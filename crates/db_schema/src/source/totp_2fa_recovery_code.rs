@@ -0,0 +1,18 @@
+use crate::{schema::totp_2fa_recovery_code, LocalUserId};
+
+/// A single use code to log in without the authenticator app
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone)]
+#[table_name = "totp_2fa_recovery_code"]
+pub struct Totp2faRecoveryCode {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub code_encrypted: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "totp_2fa_recovery_code"]
+pub struct Totp2faRecoveryCodeForm {
+  pub local_user_id: LocalUserId,
+  pub code_encrypted: String,
+}
@@ -1,9 +1,11 @@
+use chrono::NaiveDateTime;
 use diesel::{result::Error, *};
 use lemmy_db_queries::{
   aggregates::comment_aggregates::CommentAggregates,
-  functions::hot_rank,
-  fuzzy_search,
+  full_text_search::{matches_search, search_rank},
+  functions::{actor_domain, comment_keyword_filtered, hot_rank},
   limit_and_offset,
+  KeywordFilterAction,
   ListingType,
   MaybeOptional,
   SortType,
@@ -21,6 +23,7 @@ use lemmy_db_schema::{
     community_block,
     community_follower,
     community_person_ban,
+    instance_block,
     person,
     person_alias_1,
     person_block,
@@ -54,6 +57,7 @@ pub struct CommentView {
   pub saved: bool,                         // Left join to CommentSaved
   pub creator_blocked: bool,               // Left join to PersonBlock
   pub my_vote: Option<i16>,                // Left join to CommentLike
+  pub keyword_blurred: bool, // Matches one of the person's keyword filters with blur action
 }
 
 type CommentViewTuple = (
@@ -69,6 +73,7 @@ type CommentViewTuple = (
   Option<CommentSaved>,
   Option<PersonBlock>,
   Option<i16>,
+  bool,
 );
 
 impl CommentView {
@@ -93,6 +98,7 @@ impl CommentView {
       saved,
       creator_blocked,
       comment_like,
+      keyword_blurred,
     ) = comment::table
       .find(comment_id)
       .inner_join(person::table)
@@ -150,6 +156,11 @@ impl CommentView {
         comment_saved::all_columns.nullable(),
         person_block::all_columns.nullable(),
         comment_like::score.nullable(),
+        comment_keyword_filtered(
+          person_id_join,
+          KeywordFilterAction::Blur as i16,
+          comment::content,
+        ),
       ))
       .first::<CommentViewTuple>(conn)?;
 
@@ -173,6 +184,7 @@ impl CommentView {
       saved: saved.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
+      keyword_blurred,
     })
   }
 
@@ -197,6 +209,11 @@ pub struct CommentQueryBuilder<'a> {
   recipient_id: Option<PersonId>,
   my_person_id: Option<PersonId>,
   search_term: Option<String>,
+  instance: Option<String>,
+  published_after: Option<NaiveDateTime>,
+  published_before: Option<NaiveDateTime>,
+  has_url: Option<bool>,
+  has_body: Option<bool>,
   saved_only: Option<bool>,
   unread_only: Option<bool>,
   show_bot_accounts: Option<bool>,
@@ -217,6 +234,11 @@ impl<'a> CommentQueryBuilder<'a> {
       recipient_id: None,
       my_person_id: None,
       search_term: None,
+      instance: None,
+      published_after: None,
+      published_before: None,
+      has_url: None,
+      has_body: None,
       saved_only: None,
       unread_only: None,
       show_bot_accounts: None,
@@ -270,6 +292,34 @@ impl<'a> CommentQueryBuilder<'a> {
     self
   }
 
+  /// Only list comments in communities hosted on the given domain.
+  pub fn instance<T: MaybeOptional<String>>(mut self, instance: T) -> Self {
+    self.instance = instance.get_optional();
+    self
+  }
+
+  pub fn published_after<T: MaybeOptional<NaiveDateTime>>(mut self, published_after: T) -> Self {
+    self.published_after = published_after.get_optional();
+    self
+  }
+
+  pub fn published_before<T: MaybeOptional<NaiveDateTime>>(mut self, published_before: T) -> Self {
+    self.published_before = published_before.get_optional();
+    self
+  }
+
+  /// Filters on whether the parent post has a url.
+  pub fn has_url<T: MaybeOptional<bool>>(mut self, has_url: T) -> Self {
+    self.has_url = has_url.get_optional();
+    self
+  }
+
+  /// Filters on whether the parent post has a body.
+  pub fn has_body<T: MaybeOptional<bool>>(mut self, has_body: T) -> Self {
+    self.has_body = has_body.get_optional();
+    self
+  }
+
   pub fn saved_only<T: MaybeOptional<bool>>(mut self, saved_only: T) -> Self {
     self.saved_only = saved_only.get_optional();
     self
@@ -344,6 +394,16 @@ impl<'a> CommentQueryBuilder<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      // Matches if either the community or the creator are on a blocked instance
+      .left_join(
+        instance_block::table.on(
+          instance_block::person_id.eq(person_id_join).and(
+            actor_domain(community::actor_id)
+              .eq(instance_block::domain)
+              .or(actor_domain(person::actor_id).eq(instance_block::domain)),
+          ),
+        ),
+      )
       .left_join(
         comment_like::table.on(
           comment::id
@@ -364,6 +424,11 @@ impl<'a> CommentQueryBuilder<'a> {
         comment_saved::all_columns.nullable(),
         person_block::all_columns.nullable(),
         comment_like::score.nullable(),
+        comment_keyword_filtered(
+          person_id_join,
+          KeywordFilterAction::Blur as i16,
+          comment::content,
+        ),
       ))
       .into_boxed();
 
@@ -401,10 +466,38 @@ impl<'a> CommentQueryBuilder<'a> {
       query = query.filter(comment::post_id.eq(post_id));
     };
 
-    if let Some(search_term) = self.search_term {
-      query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(matches_search("comment", search_term));
     };
 
+    if let Some(instance) = self.instance {
+      query = query.filter(actor_domain(community::actor_id).eq(instance));
+    }
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(comment::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(comment::published.lt(published_before));
+    }
+
+    if let Some(has_url) = self.has_url {
+      query = if has_url {
+        query.filter(post::url.is_not_null())
+      } else {
+        query.filter(post::url.is_null())
+      };
+    }
+
+    if let Some(has_body) = self.has_body {
+      query = if has_body {
+        query.filter(post::body.is_not_null().and(post::body.ne("")))
+      } else {
+        query.filter(post::body.is_null().or(post::body.eq("")))
+      };
+    }
+
     if let Some(listing_type) = self.listing_type {
       query = match listing_type {
         ListingType::Subscribed => query.filter(community_follower::person_id.is_not_null()), // TODO could be this: and(community_follower::person_id.eq(person_id_join)),
@@ -422,10 +515,12 @@ impl<'a> CommentQueryBuilder<'a> {
     };
 
     query = match self.sort.unwrap_or(SortType::New) {
+      SortType::Relevance if self.search_term.is_some() => query
+        .order_by(search_rank("comment", self.search_term.as_deref().unwrap_or_default()).desc()),
       SortType::Hot | SortType::Active => query
         .order_by(hot_rank(comment_aggregates::score, comment_aggregates::published).desc())
         .then_order_by(comment_aggregates::published.desc()),
-      SortType::New | SortType::MostComments | SortType::NewComments => {
+      SortType::New | SortType::MostComments | SortType::NewComments | SortType::Relevance => {
         query.order_by(comment::published.desc())
       }
       SortType::TopAll => query.order_by(comment_aggregates::score.desc()),
@@ -443,10 +538,16 @@ impl<'a> CommentQueryBuilder<'a> {
         .order_by(comment_aggregates::score.desc()),
     };
 
-    // Don't show blocked communities or persons
+    // Don't show blocked communities, instances or persons
     if self.my_person_id.is_some() {
       query = query.filter(community_block::person_id.is_null());
+      query = query.filter(instance_block::person_id.is_null());
       query = query.filter(person_block::person_id.is_null());
+      query = query.filter(not(comment_keyword_filtered(
+        person_id_join,
+        KeywordFilterAction::Hide as i16,
+        comment::content,
+      )));
     }
 
     let (limit, offset) = limit_and_offset(self.page, self.limit);
@@ -478,6 +579,7 @@ impl ViewToVec for CommentView {
         saved: a.9.is_some(),
         creator_blocked: a.10.is_some(),
         my_vote: a.11,
+        keyword_blurred: a.12,
       })
       .collect::<Vec<Self>>()
   }
@@ -489,13 +591,17 @@ mod tests {
   use lemmy_db_queries::{
     aggregates::comment_aggregates::CommentAggregates,
     establish_unpooled_connection,
+    source::local_user_keyword_filter::LocalUserKeywordFilter_,
     Blockable,
     Crud,
+    KeywordFilterAction,
     Likeable,
   };
   use lemmy_db_schema::source::{
     comment::*,
     community::*,
+    local_user::{LocalUser, LocalUserForm},
+    local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterForm},
     person::*,
     person_block::PersonBlockForm,
     post::*,
@@ -590,6 +696,7 @@ mod tests {
       subscribed: false,
       saved: false,
       creator_blocked: false,
+      keyword_blurred: false,
       comment: Comment {
         id: inserted_comment.id,
         content: "A test comment 32".into(),
@@ -644,6 +751,7 @@ mod tests {
         thumbnail_url: None,
         ap_id: inserted_post.ap_id.to_owned(),
         local: true,
+        scheduled_publish_time: None,
       },
       community: CommunitySafe {
         id: inserted_community.id,
@@ -713,4 +821,105 @@ mod tests {
     assert_eq!(1, num_deleted);
     assert_eq!(1, like_removed);
   }
+
+  #[test]
+  #[serial]
+  fn test_keyword_filters() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "comment_keyword_filter_reader".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test_community_comment_keyword_filter".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "Season finale discussion".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let mut comment_ids = vec![];
+    for content in [
+      "Spoiler: they all die",
+      "Political rant",
+      "What a great episode",
+    ] {
+      let comment_form = CommentForm {
+        content: content.into(),
+        creator_id: inserted_person.id,
+        post_id: inserted_post.id,
+        ..CommentForm::default()
+      };
+      comment_ids.push(Comment::create(&conn, &comment_form).unwrap().id);
+    }
+
+    let hide_spoilers = LocalUserKeywordFilterForm {
+      local_user_id: inserted_local_user.id,
+      phrase: "spoiler".to_string(),
+      regex: false,
+      scope_title: true,
+      scope_body: true,
+      scope_comments: true,
+      action: KeywordFilterAction::Hide as i16,
+    };
+    let blur_politics = LocalUserKeywordFilterForm {
+      phrase: "^polit".to_string(),
+      regex: true,
+      action: KeywordFilterAction::Blur as i16,
+      ..hide_spoilers.clone()
+    };
+    // Only applies to posts
+    let hide_episodes = LocalUserKeywordFilterForm {
+      phrase: "episode".to_string(),
+      scope_comments: false,
+      ..hide_spoilers.clone()
+    };
+    LocalUserKeywordFilter::replace_for_local_user(
+      &conn,
+      inserted_local_user.id,
+      &[hide_spoilers, blur_politics, hide_episodes],
+    )
+    .unwrap();
+
+    let read_comments = CommentQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .post_id(inserted_post.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let read_comments_no_person = CommentQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .post_id(inserted_post.id)
+      .list()
+      .unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    // The spoiler comment is hidden, and the politics comment is blurred
+    let read: Vec<(CommentId, bool)> = read_comments
+      .iter()
+      .map(|c| (c.comment.id, c.keyword_blurred))
+      .collect();
+    assert_eq!(vec![(comment_ids[2], false), (comment_ids[1], true)], read);
+    assert_eq!(3, read_comments_no_person.len());
+    assert!(!read_comments_no_person.iter().any(|c| c.keyword_blurred));
+  }
 }
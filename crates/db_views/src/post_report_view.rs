@@ -551,6 +551,23 @@ mod tests {
       PostReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
     assert_eq!(1, report_count_after_resolved);
 
+    // Reporting again re-opens the resolved report instead of failing
+    let reopened_jessica_report = PostReport::report_or_reopen(
+      &conn,
+      &PostReportForm {
+        reason: "from jessica again".into(),
+        ..jessica_report_form
+      },
+    )
+    .unwrap();
+    assert_eq!(inserted_jessica_report.id, reopened_jessica_report.id);
+    assert_eq!("from jessica again", reopened_jessica_report.reason);
+    assert!(!reopened_jessica_report.resolved);
+    assert_eq!(None, reopened_jessica_report.resolver_id);
+    let report_count_after_reopen =
+      PostReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
+    assert_eq!(2, report_count_after_reopen);
+
     Person::delete(&conn, inserted_timmy.id).unwrap();
     Person::delete(&conn, inserted_sara.id).unwrap();
     Person::delete(&conn, inserted_jessica.id).unwrap();
@@ -1,9 +1,11 @@
+use chrono::NaiveDateTime;
 use diesel::{pg::Pg, result::Error, *};
 use lemmy_db_queries::{
   aggregates::post_aggregates::PostAggregates,
-  functions::hot_rank,
-  fuzzy_search,
+  full_text_search::{matches_search, search_rank},
+  functions::{actor_domain, hot_rank, post_keyword_filtered},
   limit_and_offset,
+  KeywordFilterAction,
   ListingType,
   MaybeOptional,
   SortType,
@@ -16,6 +18,7 @@ use lemmy_db_schema::{
     community_block,
     community_follower,
     community_person_ban,
+    instance_block,
     person,
     person_block,
     post,
@@ -50,6 +53,7 @@ pub struct PostView {
   pub read: bool,            // Left join to PostRead
   pub creator_blocked: bool, // Left join to PersonBlock
   pub my_vote: Option<i16>,  // Left join to PostLike
+  pub keyword_blurred: bool, // Matches one of the person's keyword filters with blur action
 }
 
 type PostViewTuple = (
@@ -63,6 +67,7 @@ type PostViewTuple = (
   Option<PostRead>,
   Option<PersonBlock>,
   Option<i16>,
+  bool,
 );
 
 impl PostView {
@@ -85,6 +90,7 @@ impl PostView {
       read,
       creator_blocked,
       post_like,
+      keyword_blurred,
     ) = post::table
       .find(post_id)
       .inner_join(person::table)
@@ -143,6 +149,12 @@ impl PostView {
         post_read::all_columns.nullable(),
         person_block::all_columns.nullable(),
         post_like::score.nullable(),
+        post_keyword_filtered(
+          person_id_join,
+          KeywordFilterAction::Blur as i16,
+          post::name,
+          post::body,
+        ),
       ))
       .first::<PostViewTuple>(conn)?;
 
@@ -165,6 +177,7 @@ impl PostView {
       read: read.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
+      keyword_blurred,
     })
   }
 }
@@ -179,10 +192,16 @@ pub struct PostQueryBuilder<'a> {
   my_person_id: Option<PersonId>,
   search_term: Option<String>,
   url_search: Option<String>,
+  instance: Option<String>,
+  published_after: Option<NaiveDateTime>,
+  published_before: Option<NaiveDateTime>,
+  has_url: Option<bool>,
+  has_body: Option<bool>,
   show_nsfw: Option<bool>,
   show_bot_accounts: Option<bool>,
   show_read_posts: Option<bool>,
   saved_only: Option<bool>,
+  scheduled_only: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -199,10 +218,16 @@ impl<'a> PostQueryBuilder<'a> {
       my_person_id: None,
       search_term: None,
       url_search: None,
+      instance: None,
+      published_after: None,
+      published_before: None,
+      has_url: None,
+      has_body: None,
       show_nsfw: None,
       show_bot_accounts: None,
       show_read_posts: None,
       saved_only: None,
+      scheduled_only: None,
       page: None,
       limit: None,
     }
@@ -248,6 +273,32 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  /// Only list posts in communities hosted on the given domain.
+  pub fn instance<T: MaybeOptional<String>>(mut self, instance: T) -> Self {
+    self.instance = instance.get_optional();
+    self
+  }
+
+  pub fn published_after<T: MaybeOptional<NaiveDateTime>>(mut self, published_after: T) -> Self {
+    self.published_after = published_after.get_optional();
+    self
+  }
+
+  pub fn published_before<T: MaybeOptional<NaiveDateTime>>(mut self, published_before: T) -> Self {
+    self.published_before = published_before.get_optional();
+    self
+  }
+
+  pub fn has_url<T: MaybeOptional<bool>>(mut self, has_url: T) -> Self {
+    self.has_url = has_url.get_optional();
+    self
+  }
+
+  pub fn has_body<T: MaybeOptional<bool>>(mut self, has_body: T) -> Self {
+    self.has_body = has_body.get_optional();
+    self
+  }
+
   pub fn show_nsfw<T: MaybeOptional<bool>>(mut self, show_nsfw: T) -> Self {
     self.show_nsfw = show_nsfw.get_optional();
     self
@@ -268,6 +319,12 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  /// Only list posts which are scheduled for later publication. These are hidden otherwise.
+  pub fn scheduled_only<T: MaybeOptional<bool>>(mut self, scheduled_only: T) -> Self {
+    self.scheduled_only = scheduled_only.get_optional();
+    self
+  }
+
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -330,6 +387,16 @@ impl<'a> PostQueryBuilder<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      // Matches if either the community or the creator are on a blocked instance
+      .left_join(
+        instance_block::table.on(
+          instance_block::person_id.eq(person_id_join).and(
+            actor_domain(community::actor_id)
+              .eq(instance_block::domain)
+              .or(actor_domain(person::actor_id).eq(instance_block::domain)),
+          ),
+        ),
+      )
       .left_join(
         post_like::table.on(
           post::id
@@ -348,6 +415,12 @@ impl<'a> PostQueryBuilder<'a> {
         post_read::all_columns.nullable(),
         person_block::all_columns.nullable(),
         post_like::score.nullable(),
+        post_keyword_filtered(
+          person_id_join,
+          KeywordFilterAction::Blur as i16,
+          post::name,
+          post::body,
+        ),
       ))
       .into_boxed();
 
@@ -375,13 +448,36 @@ impl<'a> PostQueryBuilder<'a> {
       query = query.filter(post::url.eq(url_search));
     }
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
-      query = query.filter(
-        post::name
-          .ilike(searcher.to_owned())
-          .or(post::body.ilike(searcher)),
-      );
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(matches_search("post", search_term));
+    }
+
+    if let Some(instance) = self.instance {
+      query = query.filter(actor_domain(community::actor_id).eq(instance));
+    }
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(post::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(post::published.lt(published_before));
+    }
+
+    if let Some(has_url) = self.has_url {
+      query = if has_url {
+        query.filter(post::url.is_not_null())
+      } else {
+        query.filter(post::url.is_null())
+      };
+    }
+
+    if let Some(has_body) = self.has_body {
+      query = if has_body {
+        query.filter(post::body.is_not_null().and(post::body.ne("")))
+      } else {
+        query.filter(post::body.is_null().or(post::body.eq("")))
+      };
     }
 
     // If its for a specific person, show the removed / deleted
@@ -407,13 +503,28 @@ impl<'a> PostQueryBuilder<'a> {
       query = query.filter(post_saved::id.is_not_null());
     };
 
-    // Don't show blocked communities or persons
+    if self.scheduled_only.unwrap_or(false) {
+      query = query.filter(post::scheduled_publish_time.is_not_null());
+    } else {
+      query = query.filter(post::scheduled_publish_time.is_null());
+    }
+
+    // Don't show blocked communities, instances or persons
     if self.my_person_id.is_some() {
       query = query.filter(community_block::person_id.is_null());
+      query = query.filter(instance_block::person_id.is_null());
       query = query.filter(person_block::person_id.is_null());
+      query = query.filter(not(post_keyword_filtered(
+        person_id_join,
+        KeywordFilterAction::Hide as i16,
+        post::name,
+        post::body,
+      )));
     }
 
     query = match self.sort.unwrap_or(SortType::Hot) {
+      SortType::Relevance if self.search_term.is_some() => query
+        .then_order_by(search_rank("post", self.search_term.as_deref().unwrap_or_default()).desc()),
       SortType::Active => query
         .then_order_by(
           hot_rank(
@@ -423,7 +534,7 @@ impl<'a> PostQueryBuilder<'a> {
           .desc(),
         )
         .then_order_by(post_aggregates::newest_comment_time_necro.desc()),
-      SortType::Hot => query
+      SortType::Hot | SortType::Relevance => query
         .then_order_by(hot_rank(post_aggregates::score, post_aggregates::published).desc())
         .then_order_by(post_aggregates::published.desc()),
       SortType::New => query.then_order_by(post_aggregates::published.desc()),
@@ -478,6 +589,7 @@ impl ViewToVec for PostView {
         read: a.7.is_some(),
         creator_blocked: a.8.is_some(),
         my_vote: a.9,
+        keyword_blurred: a.10,
       })
       .collect::<Vec<Self>>()
   }
@@ -485,24 +597,39 @@ impl ViewToVec for PostView {
 
 #[cfg(test)]
 mod tests {
-  use crate::post_view::{PostQueryBuilder, PostView};
+  use crate::{
+    comment_view::CommentQueryBuilder,
+    post_view::{PostQueryBuilder, PostView},
+  };
+  use chrono::NaiveDate;
   use lemmy_db_queries::{
     aggregates::post_aggregates::PostAggregates,
     establish_unpooled_connection,
+    source::local_user_keyword_filter::LocalUserKeywordFilter_,
     Blockable,
     Crud,
+    KeywordFilterAction,
     Likeable,
     ListingType,
     SortType,
   };
-  use lemmy_db_schema::source::{
-    community::*,
-    community_block::{CommunityBlock, CommunityBlockForm},
-    person::*,
-    person_block::{PersonBlock, PersonBlockForm},
-    post::*,
+  use lemmy_db_schema::{
+    source::{
+      comment::{Comment, CommentForm},
+      community::*,
+      community_block::{CommunityBlock, CommunityBlockForm},
+      instance_block::{InstanceBlock, InstanceBlockForm},
+      local_user::{LocalUser, LocalUserForm},
+      local_user_keyword_filter::{LocalUserKeywordFilter, LocalUserKeywordFilterForm},
+      person::*,
+      person_block::{PersonBlock, PersonBlockForm},
+      post::*,
+    },
+    CommentId,
+    PostId,
   };
   use serial_test::serial;
+  use url::Url;
 
   #[test]
   #[serial]
@@ -641,6 +768,7 @@ mod tests {
         thumbnail_url: None,
         ap_id: inserted_post.ap_id.to_owned(),
         local: true,
+        scheduled_publish_time: None,
       },
       my_vote: None,
       creator: PersonSafe {
@@ -694,6 +822,7 @@ mod tests {
       read: false,
       saved: false,
       creator_blocked: false,
+      keyword_blurred: false,
     };
 
     // Test a community block
@@ -712,6 +841,27 @@ mod tests {
       .list()
       .unwrap();
 
+    // Test an instance block, which hides all posts in communities of that instance
+    CommunityBlock::unblock(&conn, &community_block).unwrap();
+    let instance_block = InstanceBlockForm {
+      person_id: inserted_person.id,
+      domain: inserted_community
+        .actor_id
+        .to_owned()
+        .into_inner()
+        .host_str()
+        .unwrap()
+        .to_string(),
+    };
+    InstanceBlock::block(&conn, &instance_block).unwrap();
+
+    let read_post_listings_with_person_after_instance_block = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::All)
+      .sort(SortType::New)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+
     // TODO More needs to be added here
     let mut expected_post_listing_with_user = expected_post_listing_no_person.to_owned();
     expected_post_listing_with_user.my_vote = Some(1);
@@ -719,7 +869,7 @@ mod tests {
     let like_removed = PostLike::remove(&conn, inserted_person.id, inserted_post.id).unwrap();
     let num_deleted = Post::delete(&conn, inserted_post.id).unwrap();
     PersonBlock::unblock(&conn, &person_block).unwrap();
-    CommunityBlock::unblock(&conn, &community_block).unwrap();
+    InstanceBlock::unblock(&conn, &instance_block).unwrap();
     Community::delete(&conn, inserted_community.id).unwrap();
     Person::delete(&conn, inserted_person.id).unwrap();
     Person::delete(&conn, inserted_bot.id).unwrap();
@@ -751,8 +901,323 @@ mod tests {
     // Should be 0 posts after the community block
     assert_eq!(0, read_post_listings_with_person_after_block.len());
 
+    // None of the posts should be listed after the instance block
+    assert!(!read_post_listings_with_person_after_instance_block
+      .iter()
+      .any(|p| p.community.id == inserted_community.id));
+
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(1, like_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_keyword_filters() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "keyword_filter_reader".to_string(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_local_user = LocalUserForm {
+      person_id: inserted_person.id,
+      password_encrypted: "pass".to_string(),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test_community_keyword_filter".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let mut post_ids = vec![];
+    for (name, body) in [
+      ("Season finale discussion", Some("SPOILERS below")),
+      ("Political news", None),
+      ("Cat pictures", None),
+    ] {
+      let new_post = PostForm {
+        name: name.to_string(),
+        body: body.map(ToString::to_string),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        ..PostForm::default()
+      };
+      post_ids.push(Post::create(&conn, &new_post).unwrap().id);
+    }
+
+    let hide_spoilers = LocalUserKeywordFilterForm {
+      local_user_id: inserted_local_user.id,
+      phrase: "spoiler".to_string(),
+      regex: false,
+      scope_title: true,
+      scope_body: true,
+      scope_comments: true,
+      action: KeywordFilterAction::Hide as i16,
+    };
+    let blur_politics = LocalUserKeywordFilterForm {
+      phrase: "^polit".to_string(),
+      regex: true,
+      action: KeywordFilterAction::Blur as i16,
+      ..hide_spoilers.clone()
+    };
+    LocalUserKeywordFilter::replace_for_local_user(
+      &conn,
+      inserted_local_user.id,
+      &[hide_spoilers, blur_politics],
+    )
+    .unwrap();
+
+    let read_post_listings = PostQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let read_post_listings_no_person = PostQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .list()
+      .unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    // The spoiler post is hidden, and the politics post is blurred
+    assert_eq!(2, read_post_listings.len());
+    assert_eq!(post_ids[2], read_post_listings[0].post.id);
+    assert!(!read_post_listings[0].keyword_blurred);
+    assert_eq!(post_ids[1], read_post_listings[1].post.id);
+    assert!(read_post_listings[1].keyword_blurred);
+
+    // Filters only apply to the person who created them
+    assert_eq!(3, read_post_listings_no_person.len());
+    assert!(!read_post_listings_no_person
+      .iter()
+      .any(|p| p.keyword_blurred));
+  }
+
+  #[test]
+  #[serial]
+  fn test_instance_block_hides_remote_creators() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "instance_block_reader".to_string(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let remote_actor_id = Url::parse("https://remote-instance.tld/u/remote_poster").unwrap();
+    let new_remote_person = PersonForm {
+      name: "remote_poster".to_string(),
+      actor_id: Some(remote_actor_id.into()),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let inserted_remote_person = Person::create(&conn, &new_remote_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test_community_instance_block".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let post_form = |name: &str, creator_id| PostForm {
+      name: name.to_string(),
+      creator_id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let local_post = Post::create(&conn, &post_form("Local post", inserted_person.id)).unwrap();
+    let remote_post =
+      Post::create(&conn, &post_form("Remote post", inserted_remote_person.id)).unwrap();
+    let comment_form = |content: &str, creator_id| CommentForm {
+      content: content.to_string(),
+      creator_id,
+      post_id: local_post.id,
+      ..CommentForm::default()
+    };
+    let local_comment =
+      Comment::create(&conn, &comment_form("Local comment", inserted_person.id)).unwrap();
+    Comment::create(
+      &conn,
+      &comment_form("Remote comment", inserted_remote_person.id),
+    )
+    .unwrap();
+
+    let instance_block = InstanceBlockForm {
+      person_id: inserted_person.id,
+      domain: "remote-instance.tld".to_string(),
+    };
+    InstanceBlock::block(&conn, &instance_block).unwrap();
+
+    let read_posts = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let read_posts_no_person = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .list()
+      .unwrap();
+    let read_comments = CommentQueryBuilder::create(&conn)
+      .post_id(local_post.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+
+    InstanceBlock::unblock(&conn, &instance_block).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, inserted_remote_person.id).unwrap();
+
+    // The community is local, but posts and comments by the remote person are hidden
+    let post_ids: Vec<PostId> = read_posts.iter().map(|p| p.post.id).collect();
+    assert_eq!(vec![local_post.id], post_ids);
+    assert_eq!(2, read_posts_no_person.len());
+    assert!(read_posts_no_person
+      .iter()
+      .any(|p| p.post.id == remote_post.id));
+    let comment_ids: Vec<CommentId> = read_comments.iter().map(|c| c.comment.id).collect();
+    assert_eq!(vec![local_comment.id], comment_ids);
+  }
+
+  #[test]
+  #[serial]
+  fn test_full_text_search() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "full_text_searcher".to_string(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test_community_full_text_search".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let mut post_ids = vec![];
+    for (name, body) in [
+      (
+        "Gardening tips",
+        Some("How to grow tomatoes in a small garden"),
+      ),
+      ("Tomato soup", Some("A recipe with garden tomatoes")),
+      ("Growing peppers", None),
+    ] {
+      let new_post = PostForm {
+        name: name.to_string(),
+        body: body.map(ToString::to_string),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        ..PostForm::default()
+      };
+      post_ids.push(Post::create(&conn, &new_post).unwrap().id);
+    }
+
+    let search = |q: &str| {
+      PostQueryBuilder::create(&conn)
+        .sort(SortType::Relevance)
+        .community_id(inserted_community.id)
+        .search_term(q.to_string())
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|p| p.post.id)
+        .collect::<Vec<_>>()
+    };
+    // Stemming matches "grow" and "growing", titles rank higher than bodies
+    let stemmed = search("grow");
+    let phrase = search("\"garden tomatoes\"");
+    let excluded = search("tomato -soup");
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(vec![post_ids[2], post_ids[0]], stemmed);
+    assert_eq!(vec![post_ids[1]], phrase);
+    assert_eq!(vec![post_ids[0]], excluded);
+  }
+
+  #[test]
+  #[serial]
+  fn test_search_filters() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "search_filterer".to_string(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test_community_search_filters".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+    let domain = inserted_community
+      .actor_id
+      .to_owned()
+      .into_inner()
+      .domain()
+      .unwrap()
+      .to_string();
+
+    let old_link = PostForm {
+      name: "old link".to_string(),
+      url: Some(url::Url::parse("https://example.com/").unwrap().into()),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      published: Some(NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0)),
+      ..PostForm::default()
+    };
+    let old_link = Post::create(&conn, &old_link).unwrap();
+    let new_text = PostForm {
+      name: "new text".to_string(),
+      body: Some("some text".to_string()),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let new_text = Post::create(&conn, &new_text).unwrap();
+
+    let query = || PostQueryBuilder::create(&conn).community_id(inserted_community.id);
+    let ids = |posts: Vec<PostView>| posts.into_iter().map(|p| p.post.id).collect::<Vec<_>>();
+    let cutoff = NaiveDate::from_ymd(2021, 1, 1).and_hms(0, 0, 0);
+
+    let after = ids(query().published_after(cutoff).list().unwrap());
+    let before = ids(query().published_before(cutoff).list().unwrap());
+    let with_url = ids(query().has_url(true).list().unwrap());
+    let without_body = ids(query().has_body(false).list().unwrap());
+    let same_instance = ids(query().instance(domain).list().unwrap());
+    let other_instance = ids(
+      query()
+        .instance("other.example".to_string())
+        .list()
+        .unwrap(),
+    );
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(vec![new_text.id], after);
+    assert_eq!(vec![old_link.id], before);
+    assert_eq!(vec![old_link.id], with_url);
+    assert_eq!(vec![old_link.id], without_body);
+    assert_eq!(2, same_instance.len());
+    assert!(other_instance.is_empty());
+  }
 }
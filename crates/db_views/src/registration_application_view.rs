@@ -0,0 +1,250 @@
+use diesel::{result::Error, *};
+use lemmy_db_queries::{limit_and_offset, MaybeOptional, ToSafe, ToSafeSettings, ViewToVec};
+use lemmy_db_schema::{
+  schema::{local_user, person, person_alias_1, registration_application},
+  source::{
+    local_user::{LocalUser, LocalUserSettings},
+    person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
+    registration_application::RegistrationApplication,
+  },
+};
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct RegistrationApplicationView {
+  pub registration_application: RegistrationApplication,
+  pub creator_local_user: LocalUserSettings,
+  pub creator: PersonSafe,
+  pub admin: Option<PersonSafeAlias1>,
+}
+
+type RegistrationApplicationViewTuple = (
+  RegistrationApplication,
+  LocalUserSettings,
+  PersonSafe,
+  Option<PersonSafeAlias1>,
+);
+
+impl RegistrationApplicationView {
+  pub fn read(conn: &PgConnection, registration_application_id: i32) -> Result<Self, Error> {
+    let (registration_application, creator_local_user, creator, admin) =
+      registration_application::table
+        .find(registration_application_id)
+        .inner_join(
+          local_user::table.on(registration_application::local_user_id.eq(local_user::id)),
+        )
+        .inner_join(person::table.on(local_user::person_id.eq(person::id)))
+        .left_join(
+          person_alias_1::table
+            .on(registration_application::admin_id.eq(person_alias_1::id.nullable())),
+        )
+        .select((
+          registration_application::all_columns,
+          LocalUser::safe_settings_columns_tuple(),
+          Person::safe_columns_tuple(),
+          PersonAlias1::safe_columns_tuple().nullable(),
+        ))
+        .first::<RegistrationApplicationViewTuple>(conn)?;
+
+    Ok(RegistrationApplicationView {
+      registration_application,
+      creator_local_user,
+      creator,
+      admin,
+    })
+  }
+
+  /// Returns the number of applications which weren't decided by an admin yet
+  pub fn get_unread_count(conn: &PgConnection) -> Result<i64, Error> {
+    use diesel::dsl::*;
+    registration_application::table
+      .inner_join(local_user::table.on(registration_application::local_user_id.eq(local_user::id)))
+      .filter(registration_application::admin_id.is_null())
+      .filter(local_user::accepted_application.eq(false))
+      .select(count(registration_application::id))
+      .first::<i64>(conn)
+  }
+}
+
+pub struct RegistrationApplicationQueryBuilder<'a> {
+  conn: &'a PgConnection,
+  unread_only: Option<bool>,
+  page: Option<i64>,
+  limit: Option<i64>,
+}
+
+impl<'a> RegistrationApplicationQueryBuilder<'a> {
+  pub fn create(conn: &'a PgConnection) -> Self {
+    RegistrationApplicationQueryBuilder {
+      conn,
+      unread_only: None,
+      page: None,
+      limit: None,
+    }
+  }
+
+  pub fn unread_only<T: MaybeOptional<bool>>(mut self, unread_only: T) -> Self {
+    self.unread_only = unread_only.get_optional();
+    self
+  }
+
+  pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
+    self.page = page.get_optional();
+    self
+  }
+
+  pub fn limit<T: MaybeOptional<i64>>(mut self, limit: T) -> Self {
+    self.limit = limit.get_optional();
+    self
+  }
+
+  pub fn list(self) -> Result<Vec<RegistrationApplicationView>, Error> {
+    let mut query = registration_application::table
+      .inner_join(local_user::table.on(registration_application::local_user_id.eq(local_user::id)))
+      .inner_join(person::table.on(local_user::person_id.eq(person::id)))
+      .left_join(
+        person_alias_1::table
+          .on(registration_application::admin_id.eq(person_alias_1::id.nullable())),
+      )
+      .select((
+        registration_application::all_columns,
+        LocalUser::safe_settings_columns_tuple(),
+        Person::safe_columns_tuple(),
+        PersonAlias1::safe_columns_tuple().nullable(),
+      ))
+      .into_boxed();
+
+    if self.unread_only.unwrap_or(false) {
+      query = query
+        .filter(registration_application::admin_id.is_null())
+        .filter(local_user::accepted_application.eq(false));
+    }
+
+    let (limit, offset) = limit_and_offset(self.page, self.limit);
+
+    let res = query
+      .order_by(registration_application::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<RegistrationApplicationViewTuple>(self.conn)?;
+
+    Ok(RegistrationApplicationView::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for RegistrationApplicationView {
+  type DbTuple = RegistrationApplicationViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        registration_application: a.0.to_owned(),
+        creator_local_user: a.1.to_owned(),
+        creator: a.2.to_owned(),
+        admin: a.3.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::registration_application_view::{
+    RegistrationApplicationQueryBuilder,
+    RegistrationApplicationView,
+  };
+  use lemmy_db_queries::{
+    establish_unpooled_connection,
+    source::registration_application::RegistrationApplication_,
+    Crud,
+  };
+  use lemmy_db_schema::source::{
+    local_user::{LocalUser, LocalUserForm},
+    person::*,
+    registration_application::{RegistrationApplication, RegistrationApplicationForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let admin_form = PersonForm {
+      name: "timmy_rav".into(),
+      admin: Some(true),
+      ..PersonForm::default()
+    };
+    let inserted_admin = Person::create(&conn, &admin_form).unwrap();
+
+    let applicant_form = PersonForm {
+      name: "sara_rav".into(),
+      ..PersonForm::default()
+    };
+    let inserted_applicant = Person::create(&conn, &applicant_form).unwrap();
+
+    let applicant_local_user_form = LocalUserForm {
+      person_id: inserted_applicant.id,
+      password_encrypted: "pass".to_string(),
+      accepted_application: Some(false),
+      ..LocalUserForm::default()
+    };
+    let inserted_applicant_local_user =
+      LocalUser::create(&conn, &applicant_local_user_form).unwrap();
+
+    let application_form = RegistrationApplicationForm {
+      local_user_id: inserted_applicant_local_user.id,
+      answer: "I like cats".into(),
+    };
+    let inserted_application = RegistrationApplication::create(&conn, &application_form).unwrap();
+
+    let read_application =
+      RegistrationApplicationView::read(&conn, inserted_application.id).unwrap();
+    let found_application =
+      RegistrationApplication::find_by_local_user_id(&conn, inserted_applicant_local_user.id)
+        .unwrap();
+    let unread_count = RegistrationApplicationView::get_unread_count(&conn).unwrap();
+    let unread_applications = RegistrationApplicationQueryBuilder::create(&conn)
+      .unread_only(true)
+      .list()
+      .unwrap();
+
+    let denied_application = RegistrationApplication::decide(
+      &conn,
+      inserted_application.id,
+      inserted_admin.id,
+      Some("No cats allowed".into()),
+    )
+    .unwrap();
+    let read_denied_application =
+      RegistrationApplicationView::read(&conn, inserted_application.id).unwrap();
+    let unread_count_after_decision = RegistrationApplicationView::get_unread_count(&conn).unwrap();
+    let all_applications = RegistrationApplicationQueryBuilder::create(&conn)
+      .list()
+      .unwrap();
+
+    Person::delete(&conn, inserted_admin.id).unwrap();
+    Person::delete(&conn, inserted_applicant.id).unwrap();
+
+    assert_eq!(
+      inserted_application,
+      read_application.registration_application
+    );
+    assert_eq!(inserted_application, found_application);
+    assert_eq!(inserted_applicant.id, read_application.creator.id);
+    assert_eq!(None, read_application.admin);
+    assert_eq!(1, unread_count);
+    assert_eq!(vec![read_application], unread_applications);
+    assert_eq!(Some(inserted_admin.id), denied_application.admin_id);
+    assert_eq!(
+      Some("No cats allowed".to_string()),
+      denied_application.deny_reason
+    );
+    assert_eq!(
+      Some(inserted_admin.id),
+      read_denied_application.admin.map(|a| a.id)
+    );
+    assert_eq!(0, unread_count_after_decision);
+    assert_eq!(1, all_applications.len());
+  }
+}
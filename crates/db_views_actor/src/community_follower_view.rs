@@ -35,6 +35,13 @@ impl CommunityFollowerView {
     Ok(Self::from_tuple_to_vec(res))
   }
 
+  pub fn count_for_community(conn: &PgConnection, community_id: CommunityId) -> Result<i64, Error> {
+    community_follower::table
+      .filter(community_follower::community_id.eq(community_id))
+      .count()
+      .get_result(conn)
+  }
+
   pub fn for_person(conn: &PgConnection, person_id: PersonId) -> Result<Vec<Self>, Error> {
     let res = community_follower::table
       .inner_join(community::table)
@@ -3,7 +3,7 @@ use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{community, community_moderator, person},
   source::{
-    community::{Community, CommunitySafe},
+    community::{Community, CommunitySafe, ModPermissions},
     person::{Person, PersonSafe},
   },
   CommunityId,
@@ -15,9 +15,27 @@ use serde::Serialize;
 pub struct CommunityModeratorView {
   pub community: CommunitySafe,
   pub moderator: PersonSafe,
+  pub permissions: ModPermissions,
 }
 
-type CommunityModeratorViewTuple = (CommunitySafe, PersonSafe);
+type CommunityModeratorViewTuple = (CommunitySafe, PersonSafe, ModPermissions);
+
+type ModPermissionColumns = (
+  community_moderator::can_remove,
+  community_moderator::can_ban,
+  community_moderator::can_manage_mods,
+  community_moderator::can_resolve_reports,
+  community_moderator::can_edit_community,
+);
+
+/// The columns which are loaded into `ModPermissions`
+const MOD_PERMISSION_COLUMNS: ModPermissionColumns = (
+  community_moderator::can_remove,
+  community_moderator::can_ban,
+  community_moderator::can_manage_mods,
+  community_moderator::can_resolve_reports,
+  community_moderator::can_edit_community,
+);
 
 impl CommunityModeratorView {
   pub fn for_community(conn: &PgConnection, community_id: CommunityId) -> Result<Vec<Self>, Error> {
@@ -27,6 +45,7 @@ impl CommunityModeratorView {
       .select((
         Community::safe_columns_tuple(),
         Person::safe_columns_tuple(),
+        MOD_PERMISSION_COLUMNS,
       ))
       .filter(community_moderator::community_id.eq(community_id))
       .order_by(community_moderator::published)
@@ -35,6 +54,64 @@ impl CommunityModeratorView {
     Ok(Self::from_tuple_to_vec(res))
   }
 
+  /// Returns one page of the community moderators, in the same order as `for_community`
+  pub fn for_community_paged(
+    conn: &PgConnection,
+    community_id: CommunityId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let res = community_moderator::table
+      .inner_join(community::table)
+      .inner_join(person::table)
+      .select((
+        Community::safe_columns_tuple(),
+        Person::safe_columns_tuple(),
+        MOD_PERMISSION_COLUMNS,
+      ))
+      .filter(community_moderator::community_id.eq(community_id))
+      .order_by(community_moderator::published)
+      .limit(limit)
+      .offset(offset)
+      .load::<CommunityModeratorViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+
+  /// The mod who was added first, if the community has any
+  pub fn top_mod(
+    conn: &PgConnection,
+    community_id: CommunityId,
+  ) -> Result<Option<PersonId>, Error> {
+    community_moderator::table
+      .filter(community_moderator::community_id.eq(community_id))
+      .order_by(community_moderator::published)
+      .select(community_moderator::person_id)
+      .first::<PersonId>(conn)
+      .optional()
+  }
+
+  /// The permissions stored for a mod of the community, or None if the person isn't a mod there
+  pub fn permissions(
+    conn: &PgConnection,
+    person_id: PersonId,
+    community_id: CommunityId,
+  ) -> Result<Option<ModPermissions>, Error> {
+    community_moderator::table
+      .filter(community_moderator::community_id.eq(community_id))
+      .filter(community_moderator::person_id.eq(person_id))
+      .select(MOD_PERMISSION_COLUMNS)
+      .first::<ModPermissions>(conn)
+      .optional()
+  }
+
+  pub fn count_for_community(conn: &PgConnection, community_id: CommunityId) -> Result<i64, Error> {
+    community_moderator::table
+      .filter(community_moderator::community_id.eq(community_id))
+      .count()
+      .get_result(conn)
+  }
+
   pub fn for_person(conn: &PgConnection, person_id: PersonId) -> Result<Vec<Self>, Error> {
     let res = community_moderator::table
       .inner_join(community::table)
@@ -42,6 +119,7 @@ impl CommunityModeratorView {
       .select((
         Community::safe_columns_tuple(),
         Person::safe_columns_tuple(),
+        MOD_PERMISSION_COLUMNS,
       ))
       .filter(community_moderator::person_id.eq(person_id))
       .order_by(community_moderator::published)
@@ -59,6 +137,7 @@ impl CommunityModeratorView {
       .select((
         Community::safe_columns_tuple(),
         Person::safe_columns_tuple(),
+        MOD_PERMISSION_COLUMNS,
       ))
       // A hacky workaround instead of group_bys
       // https://stackoverflow.com/questions/24042359/how-to-join-only-one-row-in-joined-table-with-postgres
@@ -81,6 +160,7 @@ impl ViewToVec for CommunityModeratorView {
       .map(|a| Self {
         community: a.0.to_owned(),
         moderator: a.1.to_owned(),
+        permissions: a.2,
       })
       .collect::<Vec<Self>>()
   }
@@ -2,7 +2,8 @@ use crate::{community_moderator_view::CommunityModeratorView, person_view::Perso
 use diesel::{result::Error, *};
 use lemmy_db_queries::{
   aggregates::community_aggregates::CommunityAggregates,
-  functions::hot_rank,
+  full_text_search::{matches_search, search_rank},
+  functions::{actor_domain, hot_rank},
   fuzzy_search,
   limit_and_offset,
   ListingType,
@@ -12,9 +13,16 @@ use lemmy_db_queries::{
   ViewToVec,
 };
 use lemmy_db_schema::{
-  schema::{community, community_aggregates, community_block, community_follower},
+  schema::{
+    community,
+    community_aggregates,
+    community_block,
+    community_follower,
+    instance_block,
+    person,
+  },
   source::{
-    community::{Community, CommunityFollower, CommunitySafe},
+    community::{Community, CommunityFollower, CommunitySafe, ModPermission, ModPermissions},
     community_block::CommunityBlock,
   },
   CommunityId,
@@ -104,6 +112,40 @@ impl CommunityView {
       .unwrap_or_default()
       .contains(&person_id)
   }
+
+  /// Admins and the top mod of a community are allowed everything, other mods only what their
+  /// permissions include.
+  pub fn has_mod_permission(
+    conn: &PgConnection,
+    person_id: PersonId,
+    community_id: CommunityId,
+    permission: ModPermission,
+  ) -> Result<bool, Error> {
+    Ok(
+      Self::mod_permissions(conn, person_id, community_id)?
+        .map(|p| p.has(permission))
+        .unwrap_or(false),
+    )
+  }
+
+  /// What the person is allowed to do in the community, or None if they are neither mod nor admin
+  pub fn mod_permissions(
+    conn: &PgConnection,
+    person_id: PersonId,
+    community_id: CommunityId,
+  ) -> Result<Option<ModPermissions>, Error> {
+    let is_admin = person::table
+      .find(person_id)
+      .select(person::admin)
+      .first::<bool>(conn)
+      .optional()?
+      .unwrap_or(false);
+    // The top mod is always allowed everything
+    if is_admin || CommunityModeratorView::top_mod(conn, community_id)? == Some(person_id) {
+      return Ok(Some(ModPermissions::all()));
+    }
+    CommunityModeratorView::permissions(conn, person_id, community_id)
+  }
 }
 
 pub struct CommunityQueryBuilder<'a> {
@@ -113,6 +155,7 @@ pub struct CommunityQueryBuilder<'a> {
   my_person_id: Option<PersonId>,
   show_nsfw: Option<bool>,
   search_term: Option<String>,
+  instance: Option<String>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -126,6 +169,7 @@ impl<'a> CommunityQueryBuilder<'a> {
       sort: None,
       show_nsfw: None,
       search_term: None,
+      instance: None,
       page: None,
       limit: None,
     }
@@ -151,6 +195,12 @@ impl<'a> CommunityQueryBuilder<'a> {
     self
   }
 
+  /// Only list communities hosted on the given domain.
+  pub fn instance<T: MaybeOptional<String>>(mut self, instance: T) -> Self {
+    self.instance = instance.get_optional();
+    self
+  }
+
   pub fn my_person_id<T: MaybeOptional<PersonId>>(mut self, my_person_id: T) -> Self {
     self.my_person_id = my_person_id.get_optional();
     self
@@ -186,6 +236,13 @@ impl<'a> CommunityQueryBuilder<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        instance_block::table.on(
+          actor_domain(community::actor_id)
+            .eq(instance_block::domain)
+            .and(instance_block::person_id.eq(person_id_join)),
+        ),
+      )
       .select((
         Community::safe_columns_tuple(),
         community_aggregates::all_columns,
@@ -194,15 +251,18 @@ impl<'a> CommunityQueryBuilder<'a> {
       ))
       .into_boxed();
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
+    if let Some(search_term) = &self.search_term {
       query = query
-        .filter(community::name.ilike(searcher.to_owned()))
-        .or_filter(community::title.ilike(searcher.to_owned()))
-        .or_filter(community::description.ilike(searcher));
+        .filter(community::name.ilike(fuzzy_search(search_term)))
+        .or_filter(matches_search("community", search_term));
     };
 
     match self.sort.unwrap_or(SortType::Hot) {
+      SortType::Relevance if self.search_term.is_some() => {
+        query = query.order_by(
+          search_rank("community", self.search_term.as_deref().unwrap_or_default()).desc(),
+        )
+      }
       SortType::New => query = query.order_by(community::published.desc()),
       SortType::TopAll => query = query.order_by(community_aggregates::subscribers.desc()),
       SortType::TopMonth => query = query.order_by(community_aggregates::users_active_month.desc()),
@@ -232,9 +292,14 @@ impl<'a> CommunityQueryBuilder<'a> {
       };
     }
 
-    // Don't show blocked communities
+    if let Some(instance) = self.instance {
+      query = query.filter(actor_domain(community::actor_id).eq(instance));
+    }
+
+    // Don't show blocked communities, or communities on blocked instances
     if self.my_person_id.is_some() {
       query = query.filter(community_block::person_id.is_null());
+      query = query.filter(instance_block::person_id.is_null());
     }
 
     let (limit, offset) = limit_and_offset(self.page, self.limit);
@@ -263,3 +328,107 @@ impl ViewToVec for CommunityView {
       .collect::<Vec<Self>>()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::community_view::CommunityView;
+  use lemmy_db_queries::{
+    establish_unpooled_connection,
+    source::community::CommunityModerator_,
+    Crud,
+    Joinable,
+  };
+  use lemmy_db_schema::source::{
+    community::{
+      Community,
+      CommunityForm,
+      CommunityModerator,
+      CommunityModeratorForm,
+      ModPermission,
+      ModPermissions,
+    },
+    person::{Person, PersonForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_mod_permissions() {
+    let conn = establish_unpooled_connection();
+
+    let create_person = |name: &str, admin| {
+      let person_form = PersonForm {
+        name: name.into(),
+        admin: Some(admin),
+        ..PersonForm::default()
+      };
+      Person::create(&conn, &person_form).unwrap()
+    };
+    let admin = create_person("perm_admin", true);
+    let top_mod = create_person("perm_top_mod", false);
+    let reviewer = create_person("perm_reviewer", false);
+    let other = create_person("perm_other", false);
+
+    let community_form = CommunityForm {
+      name: "perm_community".into(),
+      title: "nada".into(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let reviewer_permissions = ModPermissions {
+      can_remove: false,
+      can_ban: false,
+      can_manage_mods: false,
+      can_resolve_reports: true,
+      can_edit_community: false,
+    };
+    for person in [&top_mod, &reviewer] {
+      let form = CommunityModeratorForm {
+        community_id: community.id,
+        person_id: person.id,
+      };
+      CommunityModerator::join(&conn, &form).unwrap();
+      // Stored permissions don't restrict the top mod
+      CommunityModerator::update_permissions(&conn, community.id, person.id, &reviewer_permissions)
+        .unwrap();
+    }
+
+    let permissions_of =
+      |person: &Person| CommunityView::mod_permissions(&conn, person.id, community.id).unwrap();
+    let has = |person: &Person, permission| {
+      CommunityView::has_mod_permission(&conn, person.id, community.id, permission).unwrap()
+    };
+    let admin_permissions = permissions_of(&admin);
+    let top_mod_permissions = permissions_of(&top_mod);
+    let read_reviewer_permissions = permissions_of(&reviewer);
+    let other_permissions = permissions_of(&other);
+    let reviewer_can_resolve = has(&reviewer, ModPermission::ResolveReports);
+    let reviewer_can_remove = has(&reviewer, ModPermission::Remove);
+    let reviewer_can_ban = has(&reviewer, ModPermission::Ban);
+    let other_can_resolve = has(&other, ModPermission::ResolveReports);
+
+    Community::delete(&conn, community.id).unwrap();
+    for person in [&admin, &top_mod, &reviewer, &other] {
+      Person::delete(&conn, person.id).unwrap();
+    }
+
+    assert_eq!(Some(ModPermissions::all()), admin_permissions);
+    assert_eq!(Some(ModPermissions::all()), top_mod_permissions);
+    assert_eq!(Some(reviewer_permissions), read_reviewer_permissions);
+    assert_eq!(None, other_permissions);
+    assert!(reviewer_can_resolve);
+    assert!(!reviewer_can_remove);
+    assert!(!reviewer_can_ban);
+    assert!(!other_can_resolve);
+
+    // Mods can only grant the permissions which they have themselves
+    assert!(!ModPermissions::all().is_subset_of(&reviewer_permissions));
+    let with_ban = ModPermissions {
+      can_ban: true,
+      ..reviewer_permissions
+    };
+    assert!(!with_ban.is_subset_of(&reviewer_permissions));
+    assert!(reviewer_permissions.is_subset_of(&reviewer_permissions));
+  }
+}
@@ -0,0 +1,42 @@
+use diesel::{result::Error, *};
+use lemmy_db_queries::{ToSafe, ViewToVec};
+use lemmy_db_schema::{
+  schema::{instance_block, person},
+  source::person::{Person, PersonSafe},
+  PersonId,
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InstanceBlockView {
+  pub person: PersonSafe,
+  pub domain: String,
+}
+
+type InstanceBlockViewTuple = (PersonSafe, String);
+
+impl InstanceBlockView {
+  pub fn for_person(conn: &PgConnection, person_id: PersonId) -> Result<Vec<Self>, Error> {
+    let res = instance_block::table
+      .inner_join(person::table)
+      .select((Person::safe_columns_tuple(), instance_block::domain))
+      .filter(instance_block::person_id.eq(person_id))
+      .order_by(instance_block::published)
+      .load::<InstanceBlockViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for InstanceBlockView {
+  type DbTuple = InstanceBlockViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        person: a.0.to_owned(),
+        domain: a.1.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
@@ -291,7 +291,7 @@ impl<'a> PersonMentionQueryBuilder<'a> {
     }
 
     query = match self.sort.unwrap_or(SortType::Hot) {
-      SortType::Hot | SortType::Active => query
+      SortType::Hot | SortType::Active | SortType::Relevance => query
         .order_by(hot_rank(comment_aggregates::score, comment_aggregates::published).desc())
         .then_order_by(comment_aggregates::published.desc()),
       SortType::New | SortType::MostComments | SortType::NewComments => {
@@ -1,6 +1,7 @@
 use diesel::{dsl::*, result::Error, *};
 use lemmy_db_queries::{
   aggregates::person_aggregates::PersonAggregates,
+  functions::actor_domain,
   fuzzy_search,
   limit_and_offset,
   MaybeOptional,
@@ -11,6 +12,7 @@ use lemmy_db_queries::{
 use lemmy_db_schema::{
   schema::{person, person_aggregates},
   source::person::{Person, PersonSafe},
+  DbUrl,
   PersonId,
 };
 use serde::Serialize;
@@ -19,24 +21,38 @@ use serde::Serialize;
 pub struct PersonViewSafe {
   pub person: PersonSafe,
   pub counts: PersonAggregates,
+  /// Set if the account moved to another instance
+  pub moved_to: Option<DbUrl>,
 }
 
-type PersonViewSafeTuple = (PersonSafe, PersonAggregates);
+type PersonViewSafeTuple = (PersonSafe, PersonAggregates, Option<DbUrl>);
 
 impl PersonViewSafe {
   pub fn read(conn: &PgConnection, person_id: PersonId) -> Result<Self, Error> {
-    let (person, counts) = person::table
+    let (person, counts, moved_to) = person::table
       .find(person_id)
       .inner_join(person_aggregates::table)
-      .select((Person::safe_columns_tuple(), person_aggregates::all_columns))
+      .select((
+        Person::safe_columns_tuple(),
+        person_aggregates::all_columns,
+        person::moved_to,
+      ))
       .first::<PersonViewSafeTuple>(conn)?;
-    Ok(Self { person, counts })
+    Ok(Self {
+      person,
+      counts,
+      moved_to,
+    })
   }
 
   pub fn admins(conn: &PgConnection) -> Result<Vec<Self>, Error> {
     let admins = person::table
       .inner_join(person_aggregates::table)
-      .select((Person::safe_columns_tuple(), person_aggregates::all_columns))
+      .select((
+        Person::safe_columns_tuple(),
+        person_aggregates::all_columns,
+        person::moved_to,
+      ))
       .filter(person::admin.eq(true))
       .order_by(person::published)
       .load::<PersonViewSafeTuple>(conn)?;
@@ -47,7 +63,11 @@ impl PersonViewSafe {
   pub fn banned(conn: &PgConnection) -> Result<Vec<Self>, Error> {
     let banned = person::table
       .inner_join(person_aggregates::table)
-      .select((Person::safe_columns_tuple(), person_aggregates::all_columns))
+      .select((
+        Person::safe_columns_tuple(),
+        person_aggregates::all_columns,
+        person::moved_to,
+      ))
       .filter(person::banned.eq(true))
       .load::<PersonViewSafeTuple>(conn)?;
 
@@ -59,6 +79,7 @@ pub struct PersonQueryBuilder<'a> {
   conn: &'a PgConnection,
   sort: Option<SortType>,
   search_term: Option<String>,
+  instance: Option<String>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -69,6 +90,7 @@ impl<'a> PersonQueryBuilder<'a> {
       conn,
       search_term: None,
       sort: None,
+      instance: None,
       page: None,
       limit: None,
     }
@@ -84,6 +106,12 @@ impl<'a> PersonQueryBuilder<'a> {
     self
   }
 
+  /// Only list persons from the given domain.
+  pub fn instance<T: MaybeOptional<String>>(mut self, instance: T) -> Self {
+    self.instance = instance.get_optional();
+    self
+  }
+
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -97,15 +125,23 @@ impl<'a> PersonQueryBuilder<'a> {
   pub fn list(self) -> Result<Vec<PersonViewSafe>, Error> {
     let mut query = person::table
       .inner_join(person_aggregates::table)
-      .select((Person::safe_columns_tuple(), person_aggregates::all_columns))
+      .select((
+        Person::safe_columns_tuple(),
+        person_aggregates::all_columns,
+        person::moved_to,
+      ))
       .into_boxed();
 
     if let Some(search_term) = self.search_term {
       query = query.filter(person::name.ilike(fuzzy_search(&search_term)));
     }
 
+    if let Some(instance) = self.instance {
+      query = query.filter(actor_domain(person::actor_id).eq(instance));
+    }
+
     query = match self.sort.unwrap_or(SortType::Hot) {
-      SortType::Hot => query
+      SortType::Hot | SortType::Relevance => query
         .order_by(person_aggregates::comment_score.desc())
         .then_order_by(person::published.desc()),
       SortType::Active => query
@@ -146,6 +182,7 @@ impl ViewToVec for PersonViewSafe {
       .map(|a| Self {
         person: a.0.to_owned(),
         counts: a.1.to_owned(),
+        moved_to: a.2.to_owned(),
       })
       .collect::<Vec<Self>>()
   }
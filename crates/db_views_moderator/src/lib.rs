@@ -1,3 +1,6 @@
+#[cfg(test)]
+extern crate serial_test;
+
 pub mod mod_add_community_view;
 pub mod mod_add_view;
 pub mod mod_ban_from_community_view;
@@ -8,3 +11,4 @@ pub mod mod_remove_community_view;
 pub mod mod_remove_post_view;
 pub mod mod_sticky_post_view;
 pub mod mod_transfer_community_view;
+pub mod modlog_combined_view;
@@ -1,12 +1,11 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{mod_add, person, person_alias_1},
   source::{
     moderator::ModAdd,
     person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
   },
-  PersonId,
 };
 use serde::Serialize;
 
@@ -20,13 +19,9 @@ pub struct ModAddView {
 type ModAddViewTuple = (ModAdd, PersonSafe, PersonSafeAlias1);
 
 impl ModAddView {
-  pub fn list(
-    conn: &PgConnection,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_add::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_add::table
       .inner_join(person::table.on(mod_add::mod_person_id.eq(person::id)))
       .inner_join(person_alias_1::table.on(mod_add::other_person_id.eq(person_alias_1::id)))
       .select((
@@ -34,17 +29,7 @@ impl ModAddView {
         Person::safe_columns_tuple(),
         PersonAlias1::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_add::mod_person_id.eq(mod_person_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_add::id.eq_any(ids))
       .order_by(mod_add::when_.desc())
       .load::<ModAddViewTuple>(conn)?;
 
@@ -1,12 +1,11 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{mod_ban, person, person_alias_1},
   source::{
     moderator::ModBan,
     person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
   },
-  PersonId,
 };
 use serde::Serialize;
 
@@ -20,13 +19,9 @@ pub struct ModBanView {
 type ModBanViewTuple = (ModBan, PersonSafe, PersonSafeAlias1);
 
 impl ModBanView {
-  pub fn list(
-    conn: &PgConnection,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_ban::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_ban::table
       .inner_join(person::table.on(mod_ban::mod_person_id.eq(person::id)))
       .inner_join(person_alias_1::table.on(mod_ban::other_person_id.eq(person_alias_1::id)))
       .select((
@@ -34,17 +29,7 @@ impl ModBanView {
         Person::safe_columns_tuple(),
         PersonAlias1::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_ban::mod_person_id.eq(mod_person_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_ban::id.eq_any(ids))
       .order_by(mod_ban::when_.desc())
       .load::<ModBanViewTuple>(conn)?;
 
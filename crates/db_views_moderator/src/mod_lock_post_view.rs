@@ -1,5 +1,5 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{community, mod_lock_post, person, post},
   source::{
@@ -8,8 +8,6 @@ use lemmy_db_schema::{
     person::{Person, PersonSafe},
     post::Post,
   },
-  CommunityId,
-  PersonId,
 };
 use serde::Serialize;
 
@@ -24,14 +22,9 @@ pub struct ModLockPostView {
 type ModLockPostViewTuple = (ModLockPost, PersonSafe, Post, CommunitySafe);
 
 impl ModLockPostView {
-  pub fn list(
-    conn: &PgConnection,
-    community_id: Option<CommunityId>,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_lock_post::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_lock_post::table
       .inner_join(person::table)
       .inner_join(post::table)
       .inner_join(community::table.on(post::community_id.eq(community::id)))
@@ -41,21 +34,7 @@ impl ModLockPostView {
         post::all_columns,
         Community::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(community_id) = community_id {
-      query = query.filter(post::community_id.eq(community_id));
-    };
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_lock_post::mod_person_id.eq(mod_person_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_lock_post::id.eq_any(ids))
       .order_by(mod_lock_post::when_.desc())
       .load::<ModLockPostViewTuple>(conn)?;
 
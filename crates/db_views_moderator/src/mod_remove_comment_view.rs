@@ -1,5 +1,5 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{comment, community, mod_remove_comment, person, person_alias_1, post},
   source::{
@@ -9,8 +9,6 @@ use lemmy_db_schema::{
     person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
     post::Post,
   },
-  CommunityId,
-  PersonId,
 };
 use serde::Serialize;
 
@@ -34,14 +32,9 @@ type ModRemoveCommentViewTuple = (
 );
 
 impl ModRemoveCommentView {
-  pub fn list(
-    conn: &PgConnection,
-    community_id: Option<CommunityId>,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_remove_comment::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_remove_comment::table
       .inner_join(person::table)
       .inner_join(comment::table)
       .inner_join(person_alias_1::table.on(comment::creator_id.eq(person_alias_1::id)))
@@ -55,21 +48,7 @@ impl ModRemoveCommentView {
         post::all_columns,
         Community::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(community_id) = community_id {
-      query = query.filter(post::community_id.eq(community_id));
-    };
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_remove_comment::mod_person_id.eq(mod_person_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_remove_comment::id.eq_any(ids))
       .order_by(mod_remove_comment::when_.desc())
       .load::<ModRemoveCommentViewTuple>(conn)?;
 
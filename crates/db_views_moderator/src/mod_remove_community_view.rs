@@ -1,5 +1,5 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{community, mod_remove_community, person},
   source::{
@@ -7,7 +7,6 @@ use lemmy_db_schema::{
     moderator::ModRemoveCommunity,
     person::{Person, PersonSafe},
   },
-  PersonId,
 };
 use serde::Serialize;
 
@@ -21,13 +20,9 @@ pub struct ModRemoveCommunityView {
 type ModRemoveCommunityTuple = (ModRemoveCommunity, PersonSafe, CommunitySafe);
 
 impl ModRemoveCommunityView {
-  pub fn list(
-    conn: &PgConnection,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_remove_community::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_remove_community::table
       .inner_join(person::table)
       .inner_join(community::table)
       .select((
@@ -35,17 +30,7 @@ impl ModRemoveCommunityView {
         Person::safe_columns_tuple(),
         Community::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_remove_community::mod_person_id.eq(mod_person_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_remove_community::id.eq_any(ids))
       .order_by(mod_remove_community::when_.desc())
       .load::<ModRemoveCommunityTuple>(conn)?;
 
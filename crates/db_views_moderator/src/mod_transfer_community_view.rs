@@ -1,5 +1,5 @@
 use diesel::{result::Error, *};
-use lemmy_db_queries::{limit_and_offset, ToSafe, ViewToVec};
+use lemmy_db_queries::{ToSafe, ViewToVec};
 use lemmy_db_schema::{
   schema::{community, mod_transfer_community, person, person_alias_1},
   source::{
@@ -7,8 +7,6 @@ use lemmy_db_schema::{
     moderator::ModTransferCommunity,
     person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
   },
-  CommunityId,
-  PersonId,
 };
 use serde::Serialize;
 
@@ -28,14 +26,9 @@ type ModTransferCommunityViewTuple = (
 );
 
 impl ModTransferCommunityView {
-  pub fn list(
-    conn: &PgConnection,
-    community_id: Option<CommunityId>,
-    mod_person_id: Option<PersonId>,
-    page: Option<i64>,
-    limit: Option<i64>,
-  ) -> Result<Vec<Self>, Error> {
-    let mut query = mod_transfer_community::table
+  /// Reads the mod actions with the given ids, newest first
+  pub fn for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_transfer_community::table
       .inner_join(person::table.on(mod_transfer_community::mod_person_id.eq(person::id)))
       .inner_join(community::table)
       .inner_join(
@@ -47,21 +40,7 @@ impl ModTransferCommunityView {
         Community::safe_columns_tuple(),
         PersonAlias1::safe_columns_tuple(),
       ))
-      .into_boxed();
-
-    if let Some(mod_person_id) = mod_person_id {
-      query = query.filter(mod_transfer_community::mod_person_id.eq(mod_person_id));
-    };
-
-    if let Some(community_id) = community_id {
-      query = query.filter(mod_transfer_community::community_id.eq(community_id));
-    };
-
-    let (limit, offset) = limit_and_offset(page, limit);
-
-    let res = query
-      .limit(limit)
-      .offset(offset)
+      .filter(mod_transfer_community::id.eq_any(ids))
       .order_by(mod_transfer_community::when_.desc())
       .load::<ModTransferCommunityViewTuple>(conn)?;
 
@@ -0,0 +1,371 @@
+use crate::{
+  mod_add_community_view::ModAddCommunityView,
+  mod_add_view::ModAddView,
+  mod_ban_from_community_view::ModBanFromCommunityView,
+  mod_ban_view::ModBanView,
+  mod_lock_post_view::ModLockPostView,
+  mod_remove_comment_view::ModRemoveCommentView,
+  mod_remove_community_view::ModRemoveCommunityView,
+  mod_remove_post_view::ModRemovePostView,
+  mod_sticky_post_view::ModStickyPostView,
+  mod_transfer_community_view::ModTransferCommunityView,
+};
+use diesel::{result::Error, *};
+use lemmy_db_queries::{limit_and_offset, MaybeOptional, ModlogActionType};
+use lemmy_db_schema::{schema::modlog_combined, CommunityId, PersonId};
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
+
+/// References a single mod action in the combined modlog. The details of the action have to be
+/// read from the view for its type, eg `ModRemovePostView` for `ModlogActionType::ModRemovePost`.
+#[derive(Queryable, Debug, PartialEq, Clone)]
+pub struct ModlogCombinedView {
+  pub kind: String,
+  pub id: i32,
+}
+
+impl ModlogCombinedView {
+  /// Ids of the entries with the given action type
+  pub fn ids_for(entries: &[Self], action_type: ModlogActionType) -> Vec<i32> {
+    let kind = action_type.to_string();
+    entries
+      .iter()
+      .filter(|e| e.kind == kind)
+      .map(|e| e.id)
+      .collect()
+  }
+}
+
+/// A mod action of any type with its details, tagged with the action type in `type_`
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type_")]
+pub enum ModlogView {
+  ModRemovePost(Box<ModRemovePostView>),
+  ModLockPost(Box<ModLockPostView>),
+  ModStickyPost(Box<ModStickyPostView>),
+  ModRemoveComment(Box<ModRemoveCommentView>),
+  ModRemoveCommunity(Box<ModRemoveCommunityView>),
+  ModBanFromCommunity(Box<ModBanFromCommunityView>),
+  ModBan(Box<ModBanView>),
+  ModAddCommunity(Box<ModAddCommunityView>),
+  ModTransferCommunity(Box<ModTransferCommunityView>),
+  ModAdd(Box<ModAddView>),
+}
+
+impl ModlogView {
+  /// Reads the details of the given modlog entries, keeping their order
+  pub fn for_entries(
+    conn: &PgConnection,
+    entries: &[ModlogCombinedView],
+  ) -> Result<Vec<Self>, Error> {
+    use ModlogActionType::*;
+    let ids = |action_type| ModlogCombinedView::ids_for(entries, action_type);
+
+    let mut views = HashMap::new();
+    views.extend(
+      ModRemovePostView::for_ids(conn, ids(ModRemovePost))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModRemovePost, v.mod_remove_post.id),
+            Self::ModRemovePost(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModLockPostView::for_ids(conn, ids(ModLockPost))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModLockPost, v.mod_lock_post.id),
+            Self::ModLockPost(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModStickyPostView::for_ids(conn, ids(ModStickyPost))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModStickyPost, v.mod_sticky_post.id),
+            Self::ModStickyPost(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModRemoveCommentView::for_ids(conn, ids(ModRemoveComment))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModRemoveComment, v.mod_remove_comment.id),
+            Self::ModRemoveComment(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModRemoveCommunityView::for_ids(conn, ids(ModRemoveCommunity))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModRemoveCommunity, v.mod_remove_community.id),
+            Self::ModRemoveCommunity(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModBanFromCommunityView::for_ids(conn, ids(ModBanFromCommunity))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModBanFromCommunity, v.mod_ban_from_community.id),
+            Self::ModBanFromCommunity(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModBanView::for_ids(conn, ids(ModBan))?
+        .into_iter()
+        .map(|v| ((ModBan, v.mod_ban.id), Self::ModBan(Box::new(v)))),
+    );
+    views.extend(
+      ModAddCommunityView::for_ids(conn, ids(ModAddCommunity))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModAddCommunity, v.mod_add_community.id),
+            Self::ModAddCommunity(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModTransferCommunityView::for_ids(conn, ids(ModTransferCommunity))?
+        .into_iter()
+        .map(|v| {
+          (
+            (ModTransferCommunity, v.mod_transfer_community.id),
+            Self::ModTransferCommunity(Box::new(v)),
+          )
+        }),
+    );
+    views.extend(
+      ModAddView::for_ids(conn, ids(ModAdd))?
+        .into_iter()
+        .map(|v| ((ModAdd, v.mod_add.id), Self::ModAdd(Box::new(v)))),
+    );
+
+    Ok(
+      entries
+        .iter()
+        .filter_map(|e| {
+          let action_type = ModlogActionType::from_str(&e.kind).ok()?;
+          views.remove(&(action_type, e.id))
+        })
+        .collect(),
+    )
+  }
+}
+
+pub struct ModlogCombinedQueryBuilder<'a> {
+  conn: &'a PgConnection,
+  action_type: Option<ModlogActionType>,
+  community_id: Option<CommunityId>,
+  mod_person_id: Option<PersonId>,
+  other_person_id: Option<PersonId>,
+  page: Option<i64>,
+  limit: Option<i64>,
+}
+
+impl<'a> ModlogCombinedQueryBuilder<'a> {
+  pub fn create(conn: &'a PgConnection) -> Self {
+    ModlogCombinedQueryBuilder {
+      conn,
+      action_type: None,
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      page: None,
+      limit: None,
+    }
+  }
+
+  pub fn action_type<T: MaybeOptional<ModlogActionType>>(mut self, action_type: T) -> Self {
+    self.action_type = action_type.get_optional();
+    self
+  }
+
+  pub fn community_id<T: MaybeOptional<CommunityId>>(mut self, community_id: T) -> Self {
+    self.community_id = community_id.get_optional();
+    self
+  }
+
+  pub fn mod_person_id<T: MaybeOptional<PersonId>>(mut self, mod_person_id: T) -> Self {
+    self.mod_person_id = mod_person_id.get_optional();
+    self
+  }
+
+  pub fn other_person_id<T: MaybeOptional<PersonId>>(mut self, other_person_id: T) -> Self {
+    self.other_person_id = other_person_id.get_optional();
+    self
+  }
+
+  pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
+    self.page = page.get_optional();
+    self
+  }
+
+  pub fn limit<T: MaybeOptional<i64>>(mut self, limit: T) -> Self {
+    self.limit = limit.get_optional();
+    self
+  }
+
+  pub fn list(self) -> Result<Vec<ModlogCombinedView>, Error> {
+    let mut query = modlog_combined::table
+      .select((modlog_combined::kind, modlog_combined::id))
+      .into_boxed();
+
+    match self.action_type {
+      None | Some(ModlogActionType::All) => {}
+      Some(action_type) => query = query.filter(modlog_combined::kind.eq(action_type.to_string())),
+    };
+
+    if let Some(community_id) = self.community_id {
+      query = query.filter(modlog_combined::community_id.eq(community_id));
+    };
+
+    if let Some(mod_person_id) = self.mod_person_id {
+      query = query.filter(modlog_combined::mod_person_id.eq(mod_person_id));
+    };
+
+    if let Some(other_person_id) = self.other_person_id {
+      query = query.filter(modlog_combined::other_person_id.eq(other_person_id));
+    };
+
+    let (limit, offset) = limit_and_offset(self.page, self.limit);
+
+    query
+      .limit(limit)
+      .offset(offset)
+      .order_by((modlog_combined::when_.desc(), modlog_combined::id.desc()))
+      .load::<ModlogCombinedView>(self.conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::modlog_combined_view::*;
+  use lemmy_db_queries::{establish_unpooled_connection, Crud};
+  use lemmy_db_schema::source::{
+    community::{Community, CommunityForm},
+    moderator::*,
+    person::{Person, PersonForm},
+    post::{Post, PostForm},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_combined_modlog() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = |name: &str| PersonForm {
+      name: name.into(),
+      ..PersonForm::default()
+    };
+    let inserted_mod = Person::create(&conn, &person_form("modlog_mod")).unwrap();
+    let inserted_person = Person::create(&conn, &person_form("modlog_person")).unwrap();
+
+    let new_community = CommunityForm {
+      name: "modlog_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A modlog post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let lock_form = ModLockPostForm {
+      mod_person_id: inserted_mod.id,
+      post_id: inserted_post.id,
+      locked: Some(true),
+      reason: None,
+    };
+    let lock = ModLockPost::create(&conn, &lock_form).unwrap();
+    let ban_form = ModBanForm {
+      mod_person_id: inserted_mod.id,
+      other_person_id: inserted_person.id,
+      reason: Some("spam".into()),
+      banned: Some(true),
+      expires: None,
+    };
+    let ban = ModBan::create(&conn, &ban_form).unwrap();
+    let remove_form = ModRemovePostForm {
+      mod_person_id: inserted_mod.id,
+      post_id: inserted_post.id,
+      reason: None,
+      removed: Some(true),
+    };
+    let remove = ModRemovePost::create(&conn, &remove_form).unwrap();
+
+    let list = |action_type| {
+      let entries = ModlogCombinedQueryBuilder::create(&conn)
+        .mod_person_id(inserted_mod.id)
+        .action_type(action_type)
+        .list()
+        .unwrap();
+      ModlogView::for_entries(&conn, &entries).unwrap()
+    };
+    let all = list(ModlogActionType::All);
+    let bans = list(ModlogActionType::ModBan);
+    // Removing and locking a post count as actions on its creator
+    let other_person_entries = ModlogCombinedQueryBuilder::create(&conn)
+      .other_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let other_mod_entries = ModlogCombinedQueryBuilder::create(&conn)
+      .other_person_id(inserted_mod.id)
+      .list()
+      .unwrap();
+    let first_page = ModlogCombinedQueryBuilder::create(&conn)
+      .mod_person_id(inserted_mod.id)
+      .limit(1)
+      .list()
+      .unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, inserted_mod.id).unwrap();
+
+    let expected_entries = vec![
+      ModlogCombinedView {
+        kind: "ModRemovePost".into(),
+        id: remove.id,
+      },
+      ModlogCombinedView {
+        kind: "ModBan".into(),
+        id: ban.id,
+      },
+      ModlogCombinedView {
+        kind: "ModLockPost".into(),
+        id: lock.id,
+      },
+    ];
+    assert!(matches!(
+      &all[..],
+      [
+        ModlogView::ModRemovePost(r),
+        ModlogView::ModBan(b),
+        ModlogView::ModLockPost(l),
+      ] if r.mod_remove_post == remove && b.mod_ban == ban && l.mod_lock_post == lock
+    ));
+    assert!(matches!(&bans[..], [ModlogView::ModBan(v)] if v.mod_ban == ban));
+    assert_eq!(expected_entries, other_person_entries);
+    assert!(other_mod_entries.is_empty());
+    assert_eq!(expected_entries[..1], first_page);
+  }
+}
@@ -1,16 +1,16 @@
 use actix_web::{error::ErrorBadRequest, *};
 use anyhow::anyhow;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use diesel::PgConnection;
 use lemmy_api_common::blocking;
 use lemmy_db_queries::{
-  source::{community::Community_, person::Person_},
+  source::{community::Community_, login_token::LoginToken_, person::Person_},
   Crud,
   ListingType,
   SortType,
 };
 use lemmy_db_schema::{
-  source::{community::Community, local_user::LocalUser, person::Person},
+  source::{community::Community, local_user::LocalUser, login_token::LoginToken, person::Person},
   LocalUserId,
 };
 use lemmy_db_views::{
@@ -19,7 +19,12 @@ use lemmy_db_views::{
   site_view::SiteView,
 };
 use lemmy_db_views_actor::person_mention_view::{PersonMentionQueryBuilder, PersonMentionView};
-use lemmy_utils::{claims::Claims, utils::markdown_to_html, LemmyError};
+use lemmy_utils::{
+  claims::Claims,
+  settings::structs::Settings,
+  utils::markdown_to_html,
+  LemmyError,
+};
 use lemmy_websocket::LemmyContext;
 use rss::{
   extension::dublincore::DublinCoreExtensionBuilder,
@@ -229,6 +234,20 @@ fn get_feed_community(
   Ok(channel_builder)
 }
 
+/// Feed readers can't refresh their access token, so an expired token is accepted here as long
+/// as its login session is still active.
+fn local_user_id_from_feed_token(
+  conn: &PgConnection,
+  jwt_secret: &str,
+  jwt: &str,
+) -> Result<LocalUserId, LemmyError> {
+  let claims = Claims::decode_allow_expired(jwt, jwt_secret)?.claims;
+  let local_user_id = LocalUserId(claims.sub);
+  let inactive_after = Duration::days(Settings::get().session.inactive_days.into());
+  LoginToken::read_active(conn, claims.sid, local_user_id, inactive_after)?;
+  Ok(local_user_id)
+}
+
 fn get_feed_front(
   conn: &PgConnection,
   jwt_secret: &str,
@@ -237,7 +256,7 @@ fn get_feed_front(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read(conn)?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
+  let local_user_id = local_user_id_from_feed_token(conn, jwt_secret, jwt)?;
   let local_user = LocalUser::read(conn, local_user_id)?;
 
   let posts = PostQueryBuilder::create(conn)
@@ -271,7 +290,7 @@ fn get_feed_inbox(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read(conn)?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
+  let local_user_id = local_user_id_from_feed_token(conn, jwt_secret, jwt)?;
   let local_user = LocalUser::read(conn, local_user_id)?;
   let person_id = local_user.person_id;
   let show_bot_accounts = local_user.show_bot_accounts;
@@ -1,5 +1,5 @@
-use crate::LemmyError;
-use chrono::Utc;
+use crate::{settings::structs::Settings, LemmyError};
+use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
 
@@ -12,23 +12,53 @@ pub struct Claims {
   pub iss: String,
   /// Time when this token was issued as UNIX-timestamp in seconds
   pub iat: i64,
+  /// Time when this token expires as UNIX-timestamp in seconds
+  pub exp: i64,
+  /// Id of the login session (`login_token`) which this token belongs to
+  pub sid: i32,
 }
 
 impl Claims {
   pub fn decode(jwt: &str, jwt_secret: &str) -> Result<TokenData<Claims>, LemmyError> {
+    Self::decode_with(jwt, jwt_secret, Validation::default())
+  }
+
+  /// Decodes the token even if it is expired. Only use this where the token can't be refreshed by
+  /// the client, like RSS feed urls, and make sure that its login session is still active.
+  pub fn decode_allow_expired(
+    jwt: &str,
+    jwt_secret: &str,
+  ) -> Result<TokenData<Claims>, LemmyError> {
     let v = Validation {
       validate_exp: false,
       ..Validation::default()
     };
+    Self::decode_with(jwt, jwt_secret, v)
+  }
+
+  fn decode_with(
+    jwt: &str,
+    jwt_secret: &str,
+    v: Validation,
+  ) -> Result<TokenData<Claims>, LemmyError> {
     let key = DecodingKey::from_secret(jwt_secret.as_ref());
     Ok(decode::<Claims>(jwt, &key, &v)?)
   }
 
-  pub fn jwt(local_user_id: i32, jwt_secret: &str, hostname: &str) -> Result<Jwt, LemmyError> {
+  pub fn jwt(
+    local_user_id: i32,
+    login_token_id: i32,
+    jwt_secret: &str,
+    settings: &Settings,
+  ) -> Result<Jwt, LemmyError> {
+    let now = Utc::now();
+    let expires = now + Duration::minutes(settings.session.access_token_minutes.into());
     let my_claims = Claims {
       sub: local_user_id,
-      iss: hostname.to_string(),
-      iat: Utc::now().timestamp(),
+      iss: settings.hostname.to_owned(),
+      iat: now.timestamp(),
+      exp: expires.timestamp(),
+      sid: login_token_id,
     };
 
     let key = EncodingKey::from_secret(jwt_secret.as_ref());
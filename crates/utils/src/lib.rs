@@ -27,6 +27,13 @@ pub type ConnectionId = usize;
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub struct IpAddr(pub String);
 
+/// Details about the client which made a request, which are shown in the list of login sessions
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+  pub ip: Option<String>,
+  pub user_agent: Option<String>,
+}
+
 impl fmt::Display for IpAddr {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     write!(f, "{}", self.0)
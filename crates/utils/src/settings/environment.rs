@@ -0,0 +1,185 @@
+use crate::{settings::structs::Settings, LemmyError};
+use anyhow::Context;
+use doku::{Document, Field, Fields, Type, TypeKind};
+use serde_json::{Map, Value};
+use std::env;
+
+static ENV_PREFIX: &str = "LEMMY_";
+
+/// Name of the environment variable which overrides the setting at `path`, eg
+/// `LEMMY_EMAIL__SMTP_PASSWORD` for `email.smtp_password`, or `LEMMY_HOSTNAME` for `hostname`.
+fn env_var_name(path: &[&str]) -> String {
+  format!("{}{}", ENV_PREFIX, path.join("__").to_uppercase())
+}
+
+/// Returns the fields of a (possibly optional) config section, or None if this is a plain value.
+fn section_fields(ty: &Type) -> Option<&[(&'static str, Field)]> {
+  match &ty.kind {
+    TypeKind::Optional { ty } => section_fields(ty),
+    TypeKind::Struct {
+      fields: Fields::Named { fields },
+      ..
+    } => Some(fields),
+    _ => None,
+  }
+}
+
+fn section_fields_mut(ty: &mut Type) -> Option<&mut [(&'static str, Field)]> {
+  match &mut ty.kind {
+    TypeKind::Optional { ty } => section_fields_mut(ty),
+    TypeKind::Struct {
+      fields: Fields::Named { fields },
+      ..
+    } => Some(fields),
+    _ => None,
+  }
+}
+
+/// Overwrites values in the parsed config file with those given in environment variables.
+pub(super) fn apply_env_overrides(config: &mut Value) -> Result<(), LemmyError> {
+  let mut overrides = vec![];
+  collect_env_overrides(&Settings::ty(), &mut vec![], &mut overrides)?;
+  for (path, value) in overrides {
+    set_value(config, &path, value);
+  }
+  Ok(())
+}
+
+fn collect_env_overrides(
+  ty: &Type,
+  path: &mut Vec<&'static str>,
+  overrides: &mut Vec<(Vec<&'static str>, Value)>,
+) -> Result<(), LemmyError> {
+  for (name, field) in section_fields(ty).unwrap_or_default() {
+    path.push(name);
+    if section_fields(&field.ty).is_some() {
+      collect_env_overrides(&field.ty, path, overrides)?;
+    } else {
+      let var = env_var_name(path);
+      if let Ok(value) = env::var(&var) {
+        overrides.push((path.clone(), parse_env_value(&field.ty, &var, value)?));
+      }
+    }
+    path.pop();
+  }
+  Ok(())
+}
+
+/// Strings are taken verbatim and lists are comma separated, everything else is parsed as json.
+fn parse_env_value(ty: &Type, var: &str, value: String) -> Result<Value, LemmyError> {
+  match &ty.kind {
+    TypeKind::Optional { ty } => parse_env_value(ty, var, value),
+    TypeKind::String => Ok(Value::String(value)),
+    TypeKind::Array { .. } => Ok(Value::Array(
+      value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| Value::String(v.to_string()))
+        .collect(),
+    )),
+    _ => Ok(
+      serde_json::from_str(&value)
+        .with_context(|| format!("Invalid value for environment variable {}", var))?,
+    ),
+  }
+}
+
+/// Sets the value at `path`, creating any config sections which are missing.
+fn set_value(config: &mut Value, path: &[&str], value: Value) {
+  if let Some((name, sections)) = path.split_last() {
+    let mut current = config;
+    for section in sections {
+      current = as_object(current)
+        .entry(section.to_string())
+        .or_insert(Value::Null);
+    }
+    as_object(current).insert(name.to_string(), value);
+  }
+}
+
+fn as_object(value: &mut Value) -> &mut Map<String, Value> {
+  if !value.is_object() {
+    *value = Value::Object(Map::new());
+  }
+  match value {
+    Value::Object(map) => map,
+    _ => unreachable!(),
+  }
+}
+
+/// The doku type of `Settings`, with the environment variable for each option added to its
+/// comment. Used for `--print-config-docs`.
+pub fn documented_settings_type() -> Type {
+  let mut ty = Settings::ty();
+  document_env_vars(&mut ty, &mut vec![]);
+  ty
+}
+
+fn document_env_vars(ty: &mut Type, path: &mut Vec<&'static str>) {
+  for (name, field) in section_fields_mut(ty).unwrap_or_default() {
+    path.push(name);
+    if section_fields(&field.ty).is_some() {
+      document_env_vars(&mut field.ty, path);
+    } else {
+      let env_comment = format!("Environment variable: {}", env_var_name(path));
+      let comment = match field.ty.comment {
+        Some(comment) => format!("{}\n{}", comment, env_comment),
+        None => env_comment,
+      };
+      // doku only takes static comments, this runs once when printing the docs
+      field.ty.comment = Some(Box::leak(comment.into_boxed_str()));
+    }
+    path.pop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn field_type<'a>(ty: &'a Type, name: &str) -> &'a Type {
+    let fields = section_fields(ty).unwrap();
+    &fields.iter().find(|(n, _)| *n == name).unwrap().1.ty
+  }
+
+  #[test]
+  fn test_env_overrides() {
+    let settings = Settings::ty();
+    let port = field_type(&settings, "port");
+    let smtp_password = field_type(field_type(&settings, "email"), "smtp_password");
+    let allowed_instances = field_type(field_type(&settings, "federation"), "allowed_instances");
+
+    let mut config = json!({ "hostname": "example.com", "email": null });
+    let overrides = [
+      (vec!["port"], parse_env_value(port, "", "1234".into())),
+      (
+        vec!["email", "smtp_password"],
+        parse_env_value(smtp_password, "", "1234".into()),
+      ),
+      (
+        vec!["federation", "allowed_instances"],
+        parse_env_value(allowed_instances, "", "a.tld, b.tld".into()),
+      ),
+    ];
+    for (path, value) in overrides {
+      set_value(&mut config, &path, value.unwrap());
+    }
+
+    assert_eq!(
+      "LEMMY_EMAIL__SMTP_PASSWORD",
+      env_var_name(&["email", "smtp_password"])
+    );
+    assert_eq!(
+      json!({
+        "hostname": "example.com",
+        "port": 1234,
+        "email": { "smtp_password": "1234" },
+        "federation": { "allowed_instances": ["a.tld", "b.tld"] },
+      }),
+      config
+    );
+    assert!(parse_env_value(port, "LEMMY_PORT", "abc".into()).is_err());
+  }
+}
@@ -1,11 +1,25 @@
-use crate::{location_info, settings::structs::Settings, LemmyError};
+use crate::{
+  location_info,
+  settings::structs::{RateLimitConfig, Settings},
+  LemmyError,
+};
 use anyhow::{anyhow, Context};
 use deser_hjson::from_str;
+use log::warn;
 use regex::{Regex, RegexBuilder};
-use std::{env, fs, io::Error, sync::RwLock};
+use serde_json::Value;
+use std::{
+  env,
+  fs,
+  io::{Error, ErrorKind},
+  sync::RwLock,
+};
 
+mod environment;
 pub mod structs;
 
+pub use environment::documented_settings_type;
+
 static DEFAULT_CONFIG_FILE: &str = "config/config.hjson";
 
 lazy_static! {
@@ -21,17 +35,33 @@ lazy_static! {
     Settings::get().hostname
   ))
   .expect("compile webfinger regex");
+  static ref SLUR_REGEX: Regex = RegexBuilder::new(r"(fag(g|got|tard)?\b|cock\s?sucker(s|ing)?|ni((g{2,}|q)+|[gq]{2,})[e3r]+(s|z)?|mudslime?s?|kikes?|\bspi(c|k)s?\b|\bchinks?|gooks?|bitch(es|ing|y)?|whor(es?|ing)|\btr(a|@)nn?(y|ies?)|\b(b|re|r)tard(ed)?s?)")
+    .case_insensitive(true)
+    .build()
+    .expect("compile slur regex");
 }
 
 impl Settings {
-  /// Reads config from configuration file.
+  /// Reads config from configuration file, and applies overrides from environment variables
+  /// like `LEMMY_EMAIL__SMTP_PASSWORD`. The config file may be missing if everything is set
+  /// through the environment.
   ///
   /// Note: The env var `LEMMY_DATABASE_URL` is parsed in
   /// `lemmy_db_queries/src/lib.rs::get_database_url_from_env()`
-  /// Warning: Only call this once.
   pub fn init() -> Result<Self, LemmyError> {
     // Read the config file
-    let config = from_str::<Settings>(&Self::read_config_file()?)?;
+    let config_file = match Self::read_config_file() {
+      Err(e) if e.kind() == ErrorKind::NotFound => "{}".to_string(),
+      c => c?,
+    };
+    Self::parse_config(&config_file)
+  }
+
+  /// Parses and validates the given hjson config, including environment overrides.
+  pub fn parse_config(config_file: &str) -> Result<Self, LemmyError> {
+    let mut config = from_str::<Value>(config_file)?;
+    environment::apply_env_overrides(&mut config)?;
+    let config = serde_json::from_value::<Settings>(config)?;
 
     if config.hostname == "unset" {
       return Err(anyhow!("Hostname variable is not set!").into());
@@ -45,6 +75,59 @@ impl Settings {
     SETTINGS.read().expect("read config").to_owned()
   }
 
+  /// Returns only the rate limits, without copying the rest of the config.
+  pub fn get_rate_limit() -> RateLimitConfig {
+    SETTINGS
+      .read()
+      .expect("read config")
+      .rate_limit
+      .to_owned()
+      .unwrap_or_default()
+  }
+
+  /// Re-reads the config file and makes the new settings active. If they are invalid, an error is
+  /// returned and the current settings are kept.
+  ///
+  /// The database, hostname, bind address, port, `tls_enabled`, `pictrs_url` and
+  /// `federation.enabled` are only read on startup. Changes to them are ignored until lemmy is
+  /// restarted.
+  pub fn reload() -> Result<(), LemmyError> {
+    Self::swap(Settings::init()?);
+    Ok(())
+  }
+
+  fn swap(new_settings: Settings) {
+    let mut settings = SETTINGS.write().expect("write config");
+    *settings = new_settings.keep_startup_settings(&settings);
+  }
+
+  /// Copies the settings which are only read on startup from the running config, so that the
+  /// active settings always match what is actually used.
+  fn keep_startup_settings(mut self, current: &Settings) -> Self {
+    if self.hostname != current.hostname
+      || self.bind != current.bind
+      || self.port != current.port
+      || self.tls_enabled != current.tls_enabled
+      || self.pictrs_url != current.pictrs_url
+      || self.federation.enabled != current.federation.enabled
+      || self.get_database_url() != current.get_database_url()
+      || self.database.pool_size != current.database.pool_size
+    {
+      warn!(
+        "Changes to database, hostname, bind, port, tls_enabled, pictrs_url or federation.enabled \
+        require a restart of lemmy"
+      );
+    }
+    self.hostname = current.hostname.to_owned();
+    self.bind = current.bind;
+    self.port = current.port;
+    self.tls_enabled = current.tls_enabled;
+    self.pictrs_url = current.pictrs_url.to_owned();
+    self.federation.enabled = current.federation.enabled;
+    self.database = current.database.to_owned();
+    self
+  }
+
   pub fn get_database_url(&self) -> String {
     let conf = &self.database;
     format!(
@@ -91,16 +174,12 @@ impl Settings {
     )
   }
 
+  /// Validates the new config, writes it to the config file and makes it active. Like for
+  /// `reload`, some settings only change after a restart.
   pub fn save_config_file(data: &str) -> Result<String, LemmyError> {
+    let new_settings = Settings::parse_config(data)?;
     fs::write(Settings::get_config_location(), data)?;
-
-    // Reload the new settings
-    // From https://stackoverflow.com/questions/29654927/how-do-i-assign-a-string-to-a-mutable-static-variable/47181804#47181804
-    let mut new_settings = SETTINGS.write().expect("write config");
-    *new_settings = match Settings::init() {
-      Ok(c) => c,
-      Err(e) => panic!("{}", e),
-    };
+    Self::swap(new_settings);
 
     Ok(Self::read_config_file()?)
   }
@@ -113,15 +192,109 @@ impl Settings {
     WEBFINGER_USER_REGEX.to_owned()
   }
 
+  /// The built-in slur regex. The slur filters of the site and communities are stored in the
+  /// database.
   pub fn slur_regex(&self) -> Regex {
-    let mut slurs = r"(fag(g|got|tard)?\b|cock\s?sucker(s|ing)?|ni((g{2,}|q)+|[gq]{2,})[e3r]+(s|z)?|mudslime?s?|kikes?|\bspi(c|k)s?\b|\bchinks?|gooks?|bitch(es|ing|y)?|whor(es?|ing)|\btr(a|@)nn?(y|ies?)|\b(b|re|r)tard(ed)?s?)".to_string();
-    if let Some(additional_slurs) = &self.additional_slurs {
-      slurs.push('|');
-      slurs.push_str(additional_slurs);
-    };
-    RegexBuilder::new(&slurs)
-      .case_insensitive(true)
-      .build()
-      .expect("compile regex")
+    SLUR_REGEX.to_owned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serial_test::serial;
+  use std::path::PathBuf;
+
+  /// Points the config location at a copy of the config file, and restores the previous location
+  /// and settings when dropped, even if the test fails.
+  struct TempConfig {
+    settings: Settings,
+    location: Option<String>,
+    path: PathBuf,
+  }
+
+  impl TempConfig {
+    fn new() -> Self {
+      let settings = Settings::get();
+      let location = env::var("LEMMY_CONFIG_LOCATION").ok();
+      let config_file = Settings::read_config_file().expect("read config file");
+      let path = env::temp_dir().join(format!("lemmy_test_config_{}.hjson", std::process::id()));
+      fs::write(&path, config_file).expect("write temp config file");
+      env::set_var("LEMMY_CONFIG_LOCATION", &path);
+      TempConfig {
+        settings,
+        location,
+        path,
+      }
+    }
+  }
+
+  impl Drop for TempConfig {
+    fn drop(&mut self) {
+      match &self.location {
+        Some(location) => env::set_var("LEMMY_CONFIG_LOCATION", location),
+        None => env::remove_var("LEMMY_CONFIG_LOCATION"),
+      }
+      let _ = fs::remove_file(&self.path);
+      let mut settings = SETTINGS.write().unwrap_or_else(|e| e.into_inner());
+      *settings = self.settings.to_owned();
+    }
+  }
+
+  #[test]
+  fn test_keep_startup_settings() {
+    let current =
+      Settings::parse_config(r#"{ hostname: "old.tld", port: 8536, actor_name_max_length: 20 }"#)
+        .unwrap();
+    let new_settings = Settings::parse_config(
+      r#"{
+        hostname: "new.tld"
+        port: 1234
+        tls_enabled: false
+        pictrs_url: "http://pictrs:8080"
+        actor_name_max_length: 30
+        database: { pool_size: 10 }
+      }"#,
+    )
+    .unwrap();
+
+    let kept = new_settings.keep_startup_settings(&current);
+    assert_eq!("old.tld", kept.hostname);
+    assert_eq!(8536, kept.port);
+    assert_eq!(current.tls_enabled, kept.tls_enabled);
+    assert_eq!(current.pictrs_url, kept.pictrs_url);
+    assert_eq!(current.database.pool_size, kept.database.pool_size);
+    assert_eq!(30, kept.actor_name_max_length);
+  }
+
+  #[test]
+  #[serial]
+  fn test_reload() {
+    let _temp_config = TempConfig::new();
+    let before = Settings::get();
+    let before_json = serde_json::to_value(&before).unwrap();
+
+    // Reloading the unchanged config file keeps everything as it is
+    Settings::reload().unwrap();
+    assert_eq!(before_json, serde_json::to_value(Settings::get()).unwrap());
+
+    // Invalid settings are neither written to the config file nor activated
+    let config_file = Settings::read_config_file().unwrap();
+    assert!(Settings::save_config_file(r#"{ hostname: "unset" }"#).is_err());
+    assert!(Settings::save_config_file(r#"{ hostname: "example.com", port: "none" }"#).is_err());
+    assert_eq!(config_file, Settings::read_config_file().unwrap());
+    assert_eq!(before_json, serde_json::to_value(Settings::get()).unwrap());
+
+    // Valid settings become active, except for those which need a restart
+    let mut new_settings = before.to_owned();
+    new_settings.actor_name_max_length = before.actor_name_max_length + 1;
+    new_settings.hostname = "changed.tld".into();
+    Settings::swap(new_settings);
+    let swapped = Settings::get();
+    assert_eq!(
+      before.actor_name_max_length + 1,
+      swapped.actor_name_max_length
+    );
+    assert_eq!(before.hostname, swapped.hostname);
   }
 }
@@ -16,6 +16,9 @@ pub struct Settings {
   pub federation: FederationConfig,
   #[default(CaptchaConfig::default())]
   pub captcha: CaptchaConfig,
+  /// Lifetime of login sessions and their tokens
+  #[default(SessionConfig::default())]
+  pub session: SessionConfig,
   /// Email sending configuration. All options except login/password are mandatory
   #[default(None)]
   pub email: Option<EmailConfig>,
@@ -41,6 +44,9 @@ pub struct Settings {
   #[doku(example = "http://localhost:8080")]
   pub pictrs_url: Option<String>,
   /// Regex for slurs which are prohibited. Example: `(\bThis\b)|(\bis\b)|(\bsample\b)`
+  ///
+  /// Deprecated: this is copied once into a site-wide slur filter on startup, and ignored
+  /// otherwise. Remove it here afterwards and manage the filter through the API.
   #[default(None)]
   pub additional_slurs: Option<String>,
   /// Maximum length of local community and user names
@@ -48,6 +54,18 @@ pub struct Settings {
   pub actor_name_max_length: usize,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default)]
+pub struct SessionConfig {
+  /// Minutes until an access token expires. After that, clients need to use their refresh token to
+  /// get a new access token.
+  #[default(60)]
+  pub access_token_minutes: u32,
+  /// Days after which a login session expires, if its refresh token isn't used in the meantime
+  #[default(30)]
+  pub inactive_days: u32,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
 #[serde(default)]
 pub struct CaptchaConfig {
@@ -120,6 +138,14 @@ pub struct FederationConfig {
   /// (meaning remote communities will show content from arbitrary instances).
   #[default(true)]
   pub strict_allowlist: bool,
+  /// Number of days after which an instance that can't be reached is considered dead. Activities
+  /// are no longer delivered to dead instances, except for occasional probes. Once a probe
+  /// succeeds, normal delivery resumes.
+  #[default(3)]
+  pub dead_instance_days: i32,
+  /// Minimum time between two probe deliveries to a dead instance, in minutes
+  #[default(60)]
+  pub dead_instance_probe_interval: i32,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
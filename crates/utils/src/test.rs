@@ -1,6 +1,7 @@
 use crate::{
   settings::structs::Settings,
   utils::{
+    escape_html,
     is_valid_actor_name,
     is_valid_display_name,
     is_valid_matrix_id,
@@ -11,6 +12,7 @@ use crate::{
     slurs_vec_to_str,
   },
 };
+use serial_test::serial;
 
 #[test]
 fn test_mentions_regex() {
@@ -23,6 +25,16 @@ fn test_mentions_regex() {
 }
 
 #[test]
+fn test_escape_html() {
+  assert_eq!(
+    escape_html("<b>\"Spam\" & 'eggs'</b>"),
+    "&lt;b&gt;&quot;Spam&quot; &amp; &#39;eggs&#39;&lt;/b&gt;"
+  );
+  assert_eq!(escape_html("no changes"), "no changes");
+}
+
+#[test]
+#[serial]
 fn test_valid_actor_name() {
   let actor_name_max_length = Settings::init().unwrap().actor_name_max_length;
   assert!(is_valid_actor_name("Hello_98", actor_name_max_length));
@@ -33,6 +45,7 @@ fn test_valid_actor_name() {
 }
 
 #[test]
+#[serial]
 fn test_valid_display_name() {
   let actor_name_max_length = Settings::init().unwrap().actor_name_max_length;
   assert!(is_valid_display_name("hello @there", actor_name_max_length));
@@ -64,6 +77,7 @@ fn test_valid_matrix_id() {
 }
 
 #[test]
+#[serial]
 fn test_slur_filter() {
   let slur_regex = Settings::init().unwrap().slur_regex();
   let test =
@@ -18,7 +18,6 @@ use lemmy_db_schema::{source::secret::Secret, CommunityId, LocalUserId, PostId};
 use lemmy_utils::{
   location_info,
   rate_limit::RateLimit,
-  settings::structs::Settings,
   ApiError,
   ConnectionId,
   IpAddr,
@@ -72,9 +71,6 @@ pub struct ChatServer {
   /// The DB Pool
   pub(super) pool: Pool<ConnectionManager<PgConnection>>,
 
-  /// The Settings
-  pub(super) settings: Settings,
-
   /// The Secrets
   pub(super) secret: Secret,
 
@@ -96,6 +92,7 @@ pub struct ChatServer {
 pub struct SessionInfo {
   pub addr: Recipient<WsMessage>,
   pub ip: IpAddr,
+  pub user_agent: Option<String>,
 }
 
 /// `ChatServer` is an actor. It maintains list of connection client session.
@@ -110,7 +107,6 @@ impl ChatServer {
     message_handler_crud: MessageHandlerCrudType,
     client: Client,
     activity_queue: QueueHandle,
-    settings: Settings,
     secret: Secret,
   ) -> ChatServer {
     ChatServer {
@@ -127,7 +123,6 @@ impl ChatServer {
       message_handler_crud,
       client,
       activity_queue,
-      settings,
       secret,
     }
   }
@@ -464,7 +459,6 @@ impl ChatServer {
       chat_server: ctx.address(),
       client: self.client.to_owned(),
       activity_queue: self.activity_queue.to_owned(),
-      settings: self.settings.to_owned(),
       secret: self.secret.to_owned(),
     };
     let message_handler_crud = self.message_handler_crud;
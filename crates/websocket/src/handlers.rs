@@ -3,9 +3,9 @@ use crate::{
   messages::*,
   OperationType,
 };
-use actix::{Actor, Context, Handler, ResponseFuture};
+use actix::{Actor, Context, Handler, MessageResult, ResponseFuture};
 use lemmy_db_schema::naive_now;
-use lemmy_utils::ConnectionId;
+use lemmy_utils::{ClientInfo, ConnectionId};
 use log::{error, info};
 use rand::Rng;
 use serde::Serialize;
@@ -33,6 +33,7 @@ impl Handler<Connect> for ChatServer {
       SessionInfo {
         addr: msg.addr,
         ip: msg.ip,
+        user_agent: msg.user_agent,
       },
     );
 
@@ -207,6 +208,22 @@ impl Handler<GetUsersOnline> for ChatServer {
   }
 }
 
+impl Handler<GetClientInfo> for ChatServer {
+  type Result = MessageResult<GetClientInfo>;
+
+  fn handle(&mut self, msg: GetClientInfo, _: &mut Context<Self>) -> Self::Result {
+    let client_info = self
+      .sessions
+      .get(&msg.id)
+      .map(|session| ClientInfo {
+        ip: Some(session.ip.to_string()),
+        user_agent: session.user_agent.to_owned(),
+      })
+      .unwrap_or_default();
+    MessageResult(client_info)
+  }
+}
+
 impl Handler<GetPostUsersOnline> for ChatServer {
   type Result = usize;
 
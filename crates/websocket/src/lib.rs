@@ -1,12 +1,12 @@
 #[macro_use]
 extern crate strum_macros;
 
-use crate::chat_server::ChatServer;
+use crate::{chat_server::ChatServer, messages::GetClientInfo};
 use actix::Addr;
 use background_jobs::QueueHandle;
 use lemmy_db_queries::DbPool;
 use lemmy_db_schema::source::secret::Secret;
-use lemmy_utils::{settings::structs::Settings, LemmyError};
+use lemmy_utils::{settings::structs::Settings, ClientInfo, ConnectionId, LemmyError};
 use reqwest::Client;
 use serde::Serialize;
 
@@ -21,7 +21,6 @@ pub struct LemmyContext {
   pub chat_server: Addr<ChatServer>,
   pub client: Client,
   pub activity_queue: QueueHandle,
-  pub settings: Settings,
   pub secret: Secret,
 }
 
@@ -31,7 +30,6 @@ impl LemmyContext {
     chat_server: Addr<ChatServer>,
     client: Client,
     activity_queue: QueueHandle,
-    settings: Settings,
     secret: Secret,
   ) -> LemmyContext {
     LemmyContext {
@@ -39,7 +37,6 @@ impl LemmyContext {
       chat_server,
       client,
       activity_queue,
-      settings,
       secret,
     }
   }
@@ -55,13 +52,25 @@ impl LemmyContext {
   pub fn activity_queue(&self) -> &QueueHandle {
     &self.activity_queue
   }
+  /// The currently active settings. These are reloaded after `SaveSiteConfig` and on SIGHUP.
   pub fn settings(&self) -> Settings {
-    // TODO hacky solution to be able to hotload the settings.
     Settings::get()
   }
   pub fn secret(&self) -> &Secret {
     &self.secret
   }
+  /// Details about the client which made the request. For websocket requests these are taken from
+  /// the connection, otherwise the given ones from the HTTP request are used.
+  pub async fn client_info(
+    &self,
+    websocket_id: Option<ConnectionId>,
+    from_request: &ClientInfo,
+  ) -> Result<ClientInfo, LemmyError> {
+    match websocket_id {
+      Some(id) => Ok(self.chat_server.send(GetClientInfo { id }).await?),
+      None => Ok(from_request.to_owned()),
+    }
+  }
 }
 
 impl Clone for LemmyContext {
@@ -71,7 +80,6 @@ impl Clone for LemmyContext {
       chat_server: self.chat_server.clone(),
       client: self.client.clone(),
       activity_queue: self.activity_queue.clone(),
-      settings: self.settings.clone(),
       secret: self.secret.clone(),
     }
   }
@@ -106,6 +114,7 @@ pub enum UserOperation {
   SaveComment,
   CreateCommentLike,
   CreateCommentReport,
+  GetCommentRevisions,
   ResolveCommentReport,
   ListCommentReports,
   CreatePostLike,
@@ -115,7 +124,18 @@ pub enum UserOperation {
   CreatePostReport,
   ResolvePostReport,
   ListPostReports,
+  GetPostRevisions,
+  ListScheduledPosts,
+  CancelScheduledPost,
   GetReportCount,
+  CreateInviteCode,
+  ListInviteCodes,
+  RevokeInviteCode,
+  GenerateTotpSecret,
+  UpdateTotp,
+  MoveAccount,
+  ExportUserData,
+  ImportUserSettings,
   FollowCommunity,
   GetReplies,
   GetPersonMentions,
@@ -129,10 +149,13 @@ pub enum UserOperation {
   ResolveObject,
   MarkAllAsRead,
   SaveUserSettings,
+  SaveKeywordFilters,
+  GetKeywordFilters,
   TransferCommunity,
   TransferSite,
   PasswordReset,
   PasswordChange,
+  VerifyEmail,
   MarkPrivateMessageAsRead,
   UserJoin,
   GetSiteConfig,
@@ -141,9 +164,22 @@ pub enum UserOperation {
   CommunityJoin,
   ModJoin,
   ChangePassword,
+  RefreshToken,
+  ListLoginTokens,
+  RevokeLoginToken,
   GetSiteMetadata,
   BlockCommunity,
   BlockPerson,
+  BlockInstance,
+  GetFederationQueue,
+  AddInstance,
+  RemoveInstance,
+  ListInstances,
+  GetSlurFilters,
+  SaveSlurFilters,
+  ListRegistrationApplications,
+  ApproveRegistrationApplication,
+  GetUnreadRegistrationApplicationCount,
 }
 
 #[derive(EnumString, ToString, Debug, Clone)]
@@ -2,7 +2,7 @@ use crate::UserOperation;
 use actix::{prelude::*, Recipient};
 use lemmy_api_common::{comment::CommentResponse, post::PostResponse};
 use lemmy_db_schema::{CommunityId, LocalUserId, PostId};
-use lemmy_utils::{ConnectionId, IpAddr};
+use lemmy_utils::{ClientInfo, ConnectionId, IpAddr};
 use serde::{Deserialize, Serialize};
 
 /// Chat server sends this messages to session
@@ -18,6 +18,7 @@ pub struct WsMessage(pub String);
 pub struct Connect {
   pub addr: Recipient<WsMessage>,
   pub ip: IpAddr,
+  pub user_agent: Option<String>,
 }
 
 /// Session is disconnected
@@ -121,6 +122,13 @@ pub struct JoinPostRoom {
 #[rtype(usize)]
 pub struct GetUsersOnline;
 
+/// Details about the client of a websocket connection
+#[derive(Message)]
+#[rtype(result = "ClientInfo")]
+pub struct GetClientInfo {
+  pub id: ConnectionId,
+}
+
 #[derive(Message)]
 #[rtype(usize)]
 pub struct GetPostUsersOnline {
@@ -27,6 +27,11 @@ pub async fn chat_route(
       id: 0,
       hb: Instant::now(),
       ip: get_ip(&req.connection_info()),
+      user_agent: req
+        .headers()
+        .get(http::header::USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .map(ToString::to_string),
     },
     &req,
     stream,
@@ -38,6 +43,7 @@ struct WsSession {
   /// unique session id
   id: ConnectionId,
   ip: IpAddr,
+  user_agent: Option<String>,
   /// Client must send ping at least once per 10 seconds (CLIENT_TIMEOUT),
   /// otherwise we drop connection.
   hb: Instant,
@@ -62,6 +68,7 @@ impl Actor for WsSession {
       .send(Connect {
         addr: addr.recipient(),
         ip: self.ip.to_owned(),
+        user_agent: self.user_agent.to_owned(),
       })
       .into_actor(self)
       .then(|res, act, ctx| {
@@ -0,0 +1 @@
+drop table activity_queue_job;
@@ -0,0 +1,16 @@
+-- Persistent storage for outgoing federation jobs, so that they survive restarts
+create table activity_queue_job (
+  id serial primary key,
+  job_id varchar(36) not null unique,
+  queue varchar not null,
+  status varchar not null,
+  queued boolean not null default false,
+  runner_id varchar(36),
+  retry_count int not null default 0,
+  next_queue timestamp,
+  data jsonb not null,
+  published timestamp not null default now(),
+  updated timestamp
+);
+
+create index idx_activity_queue_job_queued on activity_queue_job (queue, next_queue) where queued;
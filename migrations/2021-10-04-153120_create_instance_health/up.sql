@@ -0,0 +1,12 @@
+-- Delivery statistics per remote instance, used to stop sending activities to dead instances
+create table instance_health (
+  id serial primary key,
+  domain varchar(255) not null unique,
+  consecutive_failures int not null default 0,
+  first_failed_delivery timestamp,
+  last_failed_delivery timestamp,
+  last_successful_delivery timestamp,
+  last_probe timestamp,
+  published timestamp not null default now(),
+  updated timestamp
+);
@@ -0,0 +1,2 @@
+alter table person_ban drop column expires;
+alter table community_person_ban drop column expires;
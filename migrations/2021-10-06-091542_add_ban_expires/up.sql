@@ -0,0 +1,2 @@
+alter table person_ban add column expires timestamp;
+alter table community_person_ban add column expires timestamp;
@@ -0,0 +1,7 @@
+drop view modlog_combined;
+
+alter table mod_lock_post drop column reason;
+alter table mod_sticky_post drop column reason;
+alter table mod_add_community drop column reason;
+alter table mod_transfer_community drop column reason;
+alter table mod_add drop column reason;
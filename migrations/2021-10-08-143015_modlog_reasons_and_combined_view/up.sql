@@ -0,0 +1,47 @@
+alter table mod_lock_post add column reason text;
+alter table mod_sticky_post add column reason text;
+alter table mod_add_community add column reason text;
+alter table mod_transfer_community add column reason text;
+alter table mod_add add column reason text;
+
+-- All mod actions in a single timeline, so that the modlog can be filtered and paged as a whole.
+-- The details of each action are read from its own table.
+create view modlog_combined as
+select 'ModRemovePost' as kind, mrp.id, mrp.when_, mrp.mod_person_id,
+  p.creator_id as other_person_id, p.community_id
+from mod_remove_post mrp
+join post p on mrp.post_id = p.id
+union all
+select 'ModLockPost', mlp.id, mlp.when_, mlp.mod_person_id, p.creator_id, p.community_id
+from mod_lock_post mlp
+join post p on mlp.post_id = p.id
+union all
+select 'ModStickyPost', msp.id, msp.when_, msp.mod_person_id, p.creator_id, p.community_id
+from mod_sticky_post msp
+join post p on msp.post_id = p.id
+union all
+select 'ModRemoveComment', mrc.id, mrc.when_, mrc.mod_person_id, c.creator_id, p.community_id
+from mod_remove_comment mrc
+join comment c on mrc.comment_id = c.id
+join post p on c.post_id = p.id
+union all
+select 'ModRemoveCommunity', mrco.id, mrco.when_, mrco.mod_person_id, null, mrco.community_id
+from mod_remove_community mrco
+union all
+select 'ModBanFromCommunity', mbfc.id, mbfc.when_, mbfc.mod_person_id, mbfc.other_person_id,
+  mbfc.community_id
+from mod_ban_from_community mbfc
+union all
+select 'ModBan', mb.id, mb.when_, mb.mod_person_id, mb.other_person_id, null
+from mod_ban mb
+union all
+select 'ModAddCommunity', mac.id, mac.when_, mac.mod_person_id, mac.other_person_id,
+  mac.community_id
+from mod_add_community mac
+union all
+select 'ModTransferCommunity', mtc.id, mtc.when_, mtc.mod_person_id, mtc.other_person_id,
+  mtc.community_id
+from mod_transfer_community mtc
+union all
+select 'ModAdd', ma.id, ma.when_, ma.mod_person_id, ma.other_person_id, null
+from mod_add ma;
@@ -0,0 +1,10 @@
+-- Instances which admins have added to the federation allowlist or blocklist at runtime. These
+-- are used in addition to the lists in the config file.
+create table instance (
+  id serial primary key,
+  domain varchar(255) not null unique,
+  blocked boolean not null,
+  reason text,
+  published timestamp not null default now(),
+  updated timestamp
+);
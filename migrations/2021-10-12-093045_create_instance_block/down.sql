@@ -0,0 +1,2 @@
+drop table instance_block;
+drop function actor_domain;
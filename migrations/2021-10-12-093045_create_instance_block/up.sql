@@ -0,0 +1,14 @@
+-- Returns the domain of an actor id, eg `example.com` for `https://example.com/c/main`
+create or replace function actor_domain(actor_id text) returns text
+language sql immutable
+as $$
+  select lower(substring(actor_id from '^[a-z]+://([^/:]+)'))
+$$;
+
+create table instance_block (
+  id serial primary key,
+  person_id int references person on update cascade on delete cascade not null,
+  domain varchar(255) not null,
+  published timestamp not null default now(),
+  unique(person_id, domain)
+);
@@ -0,0 +1,4 @@
+drop function comment_keyword_filtered;
+drop function post_keyword_filtered;
+drop function keyword_filter_matches;
+drop table local_user_keyword_filter;
@@ -0,0 +1,59 @@
+-- Words or regular expressions which a user doesn't want to see. The action is 0 to hide matching
+-- content, and 1 to blur it.
+create table local_user_keyword_filter (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  phrase text not null,
+  regex boolean not null default false,
+  scope_title boolean not null default true,
+  scope_body boolean not null default true,
+  scope_comments boolean not null default true,
+  action smallint not null default 0,
+  published timestamp not null default now()
+);
+
+create index idx_local_user_keyword_filter_local_user on local_user_keyword_filter (local_user_id);
+
+-- Plain phrases are matched case insensitively anywhere in the text
+create or replace function keyword_filter_matches(phrase text, regex boolean, content text)
+returns boolean language sql immutable
+as $$
+  select coalesce(
+    case when regex then content ~* phrase
+    else strpos(lower(content), lower(phrase)) > 0 end,
+    false)
+$$;
+
+-- Whether one of the person's filters with the given action matches the post
+create or replace function post_keyword_filtered(
+  for_person_id int, filter_action smallint, title text, body text)
+returns boolean language sql stable
+as $$
+  select exists (
+    select 1
+    from local_user_keyword_filter f
+    join local_user lu on lu.id = f.local_user_id
+    where lu.person_id = for_person_id
+      and f.action = filter_action
+      and (
+        (f.scope_title and keyword_filter_matches(f.phrase, f.regex, title))
+        or (f.scope_body and keyword_filter_matches(f.phrase, f.regex, body))
+      )
+  )
+$$;
+
+-- Whether one of the person's filters with the given action matches the comment
+create or replace function comment_keyword_filtered(
+  for_person_id int, filter_action smallint, content text)
+returns boolean language sql stable
+as $$
+  select exists (
+    select 1
+    from local_user_keyword_filter f
+    join local_user lu on lu.id = f.local_user_id
+    where lu.person_id = for_person_id
+      and f.action = filter_action
+      and f.scope_comments
+      and keyword_filter_matches(f.phrase, f.regex, content)
+  )
+$$;
@@ -0,0 +1,12 @@
+-- Login sessions of local users. Only a hash of the refresh token is stored.
+create table login_token (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  token_encrypted text not null unique,
+  ip text,
+  user_agent text,
+  published timestamp not null default now(),
+  last_used timestamp not null default now()
+);
+
+create index idx_login_token_local_user on login_token (local_user_id);
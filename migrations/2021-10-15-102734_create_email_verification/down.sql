@@ -0,0 +1,3 @@
+drop table email_verification;
+alter table site drop column require_email_verification;
+alter table local_user drop column email_verified;
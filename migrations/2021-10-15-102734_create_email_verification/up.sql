@@ -0,0 +1,18 @@
+-- Existing addresses are treated as verified, so that enabling the site option doesn't lock out
+-- existing users.
+alter table local_user add column email_verified boolean not null default false;
+update local_user set email_verified = true where email is not null;
+
+alter table site add column require_email_verification boolean not null default false;
+
+-- Pending verifications of email addresses. The address is only stored in local_user once it was
+-- verified, except on registration. Only a hash of the token is stored.
+create table email_verification (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  email text not null,
+  verification_token text not null unique,
+  published timestamp not null default now()
+);
+
+create index idx_email_verification_local_user on email_verification (local_user_id);
@@ -0,0 +1,4 @@
+drop table registration_application;
+alter table site drop column application_question;
+alter table site drop column require_application;
+alter table local_user drop column accepted_application;
@@ -0,0 +1 @@
+drop table invite_code;
@@ -0,0 +1,12 @@
+-- Codes which let people register while registration is closed
+create table invite_code (
+  id serial primary key,
+  code text not null unique,
+  creator_id int references person on update cascade on delete cascade not null,
+  max_uses int,
+  uses int not null default 0,
+  expires timestamp,
+  published timestamp not null default now()
+);
+
+create index idx_invite_code_creator on invite_code (creator_id);
@@ -0,0 +1,4 @@
+drop table totp_2fa_recovery_code;
+alter table site drop column require_totp_2fa_for_admins;
+alter table local_user drop column totp_2fa_enabled;
+alter table local_user drop column totp_2fa_secret;
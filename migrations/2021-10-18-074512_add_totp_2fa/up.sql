@@ -0,0 +1,14 @@
+-- The secret is stored when enrollment starts, but only enabled once a first code was confirmed
+alter table local_user add column totp_2fa_secret text;
+alter table local_user add column totp_2fa_enabled boolean not null default false;
+
+alter table site add column require_totp_2fa_for_admins boolean not null default false;
+
+-- Single use codes to log in without the authenticator app. Only a hash of each code is stored.
+create table totp_2fa_recovery_code (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  code_encrypted text not null,
+  published timestamp not null default now(),
+  unique(local_user_id, code_encrypted)
+);
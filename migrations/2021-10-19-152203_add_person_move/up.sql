@@ -0,0 +1,7 @@
+-- Accounts can be moved to another instance. The new account lists the old one in also_known_as,
+-- and the old account points to the new one with moved_to.
+drop view person_alias_1, person_alias_2;
+alter table person add column moved_to varchar(255);
+alter table person add column also_known_as text[] not null default '{}';
+create view person_alias_1 as select * from person;
+create view person_alias_2 as select * from person;
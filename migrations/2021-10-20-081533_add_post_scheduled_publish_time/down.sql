@@ -0,0 +1,3 @@
+drop index idx_post_scheduled_publish_time;
+
+alter table post drop column scheduled_publish_time;
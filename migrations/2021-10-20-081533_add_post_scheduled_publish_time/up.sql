@@ -0,0 +1,3 @@
+alter table post add column scheduled_publish_time timestamp;
+
+create index idx_post_scheduled_publish_time on post (scheduled_publish_time);
@@ -0,0 +1,2 @@
+drop table post_revision;
+drop table comment_revision;
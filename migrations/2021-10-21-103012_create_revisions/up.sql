@@ -0,0 +1,20 @@
+-- Earlier versions of posts and comments, recorded whenever their content gets edited
+create table post_revision (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  url text,
+  body text,
+  published timestamp not null
+);
+
+create index idx_post_revision_post on post_revision (post_id);
+
+create table comment_revision (
+  id serial primary key,
+  comment_id int references comment on update cascade on delete cascade not null,
+  content text not null,
+  published timestamp not null
+);
+
+create index idx_comment_revision_comment on comment_revision (comment_id);
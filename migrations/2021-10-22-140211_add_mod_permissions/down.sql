@@ -0,0 +1,5 @@
+alter table community_moderator drop column can_remove;
+alter table community_moderator drop column can_ban;
+alter table community_moderator drop column can_manage_mods;
+alter table community_moderator drop column can_resolve_reports;
+alter table community_moderator drop column can_edit_community;
@@ -0,0 +1,6 @@
+-- Existing moderators keep all of their permissions
+alter table community_moderator add column can_remove boolean not null default true;
+alter table community_moderator add column can_ban boolean not null default true;
+alter table community_moderator add column can_manage_mods boolean not null default true;
+alter table community_moderator add column can_resolve_reports boolean not null default true;
+alter table community_moderator add column can_edit_community boolean not null default true;
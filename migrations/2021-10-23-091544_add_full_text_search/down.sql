@@ -0,0 +1,5 @@
+drop function search_query;
+
+alter table post drop column search_vector;
+alter table comment drop column search_vector;
+alter table community drop column search_vector;
@@ -0,0 +1,24 @@
+-- Full text search vectors, which are kept up to date by postgres
+alter table post add column search_vector tsvector generated always as (
+  setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+  setweight(to_tsvector('english', coalesce(body, '')), 'B')
+) stored;
+
+alter table comment add column search_vector tsvector generated always as (
+  to_tsvector('english', coalesce(content, ''))
+) stored;
+
+alter table community add column search_vector tsvector generated always as (
+  setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+  setweight(to_tsvector('english', coalesce(description, '')), 'B')
+) stored;
+
+create index idx_post_search_vector on post using gin (search_vector);
+create index idx_comment_search_vector on comment using gin (search_vector);
+create index idx_community_search_vector on community using gin (search_vector);
+
+-- Parses a search like `"quoted phrase" -excluded`, with the same config as the vectors above
+create function search_query(query text) returns tsquery
+language sql immutable as $$
+  select websearch_to_tsquery('english', query)
+$$;
@@ -0,0 +1,13 @@
+-- Words or regular expressions which are filtered from posts and comments, either site-wide
+-- (community_id is null) or in a single community. The mode is 0 to reject the content, 1 to
+-- replace matches with *removed*, and 2 to flag the content for review by the mods.
+create table slur_filter (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade,
+  phrase text not null,
+  regex boolean not null default false,
+  mode smallint not null default 0,
+  published timestamp not null default now()
+);
+
+create index idx_slur_filter_community on slur_filter (community_id);
@@ -0,0 +1,4 @@
+drop view person_outbox;
+
+drop index idx_post_creator_published;
+drop index idx_comment_creator_published;
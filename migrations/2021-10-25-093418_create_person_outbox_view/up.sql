@@ -0,0 +1,12 @@
+-- Local posts and comments of each person, newest first in the apub outbox
+create view person_outbox as
+select 'post' as kind, p.id, p.creator_id, p.published
+from post p
+where p.local and not p.deleted and not p.removed and p.scheduled_publish_time is null
+union all
+select 'comment', c.id, c.creator_id, c.published
+from comment c
+where c.local and not c.deleted and not c.removed;
+
+create index idx_post_creator_published on post (creator_id, published desc);
+create index idx_comment_creator_published on comment (creator_id, published desc);
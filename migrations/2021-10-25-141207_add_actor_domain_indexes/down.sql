@@ -0,0 +1,2 @@
+drop index idx_person_actor_domain;
+drop index idx_community_actor_domain;
@@ -0,0 +1,3 @@
+-- Used to filter by instance, and to hide content from instances which a user blocked
+create index idx_person_actor_domain on person (actor_domain(actor_id));
+create index idx_community_actor_domain on community (actor_domain(actor_id));
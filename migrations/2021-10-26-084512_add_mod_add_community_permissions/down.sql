@@ -0,0 +1,5 @@
+alter table mod_add_community drop column can_remove;
+alter table mod_add_community drop column can_ban;
+alter table mod_add_community drop column can_manage_mods;
+alter table mod_add_community drop column can_resolve_reports;
+alter table mod_add_community drop column can_edit_community;
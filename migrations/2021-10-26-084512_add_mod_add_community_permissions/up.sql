@@ -0,0 +1,7 @@
+-- The permissions which a mod was given, so that changes to them show up in the modlog. Not set
+-- for removed mods and older entries.
+alter table mod_add_community add column can_remove boolean;
+alter table mod_add_community add column can_ban boolean;
+alter table mod_add_community add column can_manage_mods boolean;
+alter table mod_add_community add column can_resolve_reports boolean;
+alter table mod_add_community add column can_edit_community boolean;
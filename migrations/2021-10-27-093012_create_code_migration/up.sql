@@ -0,0 +1,5 @@
+-- Code migrations which must only run once, e.g. because admins may change their result later.
+create table code_migration (
+  name text primary key,
+  published timestamp not null default now()
+);
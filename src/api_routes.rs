@@ -2,7 +2,7 @@ use actix_web::{error::ErrorBadRequest, *};
 use lemmy_api::Perform;
 use lemmy_api_common::{comment::*, community::*, person::*, post::*, site::*, websocket::*};
 use lemmy_api_crud::PerformCrud;
-use lemmy_utils::rate_limit::RateLimit;
+use lemmy_utils::{rate_limit::RateLimit, utils::get_ip, ClientInfo};
 use lemmy_websocket::{routes::chat_route, LemmyContext};
 use serde::Deserialize;
 
@@ -20,8 +20,41 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route("", web::post().to(route_post_crud::<CreateSite>))
           .route("", web::put().to(route_post_crud::<EditSite>))
           .route("/transfer", web::post().to(route_post::<TransferSite>))
+          .route("/block", web::post().to(route_post::<BlockInstance>))
           .route("/config", web::get().to(route_get::<GetSiteConfig>))
-          .route("/config", web::put().to(route_post::<SaveSiteConfig>)),
+          .route("/config", web::put().to(route_post::<SaveSiteConfig>))
+          .route(
+            "/federation_queue",
+            web::get().to(route_get::<GetFederationQueue>),
+          )
+          .route("/instance", web::get().to(route_get::<ListInstances>))
+          .route("/instance", web::post().to(route_post::<AddInstance>))
+          .route(
+            "/instance/remove",
+            web::post().to(route_post::<RemoveInstance>),
+          )
+          .route("/slur_filters", web::get().to(route_get::<GetSlurFilters>))
+          .route(
+            "/slur_filters",
+            web::put().to(route_post::<SaveSlurFilters>),
+          ),
+      )
+      // Registration applications
+      .service(
+        web::scope("/admin/registration_application")
+          .wrap(rate_limit.message())
+          .route(
+            "/list",
+            web::get().to(route_get::<ListRegistrationApplications>),
+          )
+          .route(
+            "/approve",
+            web::put().to(route_post::<ApproveRegistrationApplication>),
+          )
+          .route(
+            "/count",
+            web::get().to(route_get::<GetUnreadRegistrationApplicationCount>),
+          ),
       )
       .service(
         web::resource("/modlog")
@@ -95,6 +128,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::put().to(route_post::<ResolvePostReport>),
           )
           .route("/report/list", web::get().to(route_get::<ListPostReports>))
+          .route("/scheduled", web::get().to(route_get::<ListScheduledPosts>))
+          .route(
+            "/scheduled/cancel",
+            web::post().to(route_post::<CancelScheduledPost>),
+          )
           .route(
             "/site_metadata",
             web::get().to(route_get::<GetSiteMetadata>),
@@ -115,6 +153,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route("/like", web::post().to(route_post::<CreateCommentLike>))
           .route("/save", web::put().to(route_post::<SaveComment>))
           .route("/list", web::get().to(route_get_crud::<GetComments>))
+          .route(
+            "/revisions",
+            web::get().to(route_get::<GetCommentRevisions>),
+          )
           .route("/report", web::post().to(route_post::<CreateCommentReport>))
           .route(
             "/report/resolve",
@@ -148,7 +190,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
         web::resource("/user/register")
           .guard(guard::Post())
           .wrap(rate_limit.register())
-          .route(web::post().to(route_post_crud::<Register>)),
+          .route(web::post().to(route_post_crud_with_client_info::<Register>)),
       )
       // User actions
       .service(
@@ -166,7 +208,19 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route("/ban", web::post().to(route_post::<BanPerson>))
           .route("/block", web::post().to(route_post::<BlockPerson>))
           // Account actions. I don't like that they're in /user maybe /accounts
-          .route("/login", web::post().to(route_post::<Login>))
+          .route(
+            "/login",
+            web::post().to(route_post_with_client_info::<Login>),
+          )
+          .route(
+            "/refresh_token",
+            web::post().to(route_post_with_client_info::<RefreshToken>),
+          )
+          .route("/login_tokens", web::get().to(route_get::<ListLoginTokens>))
+          .route(
+            "/login_tokens/revoke",
+            web::post().to(route_post::<RevokeLoginToken>),
+          )
           .route("/get_captcha", web::get().to(route_get::<GetCaptcha>))
           .route(
             "/delete_account",
@@ -178,8 +232,9 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           )
           .route(
             "/password_change",
-            web::post().to(route_post::<PasswordChange>),
+            web::post().to(route_post_with_client_info::<PasswordChange>),
           )
+          .route("/verify_email", web::post().to(route_post::<VerifyEmail>))
           // mark_all_as_read feels off being in this section as well
           .route(
             "/mark_all_as_read",
@@ -189,11 +244,36 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             "/save_user_settings",
             web::put().to(route_post::<SaveUserSettings>),
           )
+          .route(
+            "/keyword_filters",
+            web::get().to(route_get::<GetKeywordFilters>),
+          )
+          .route(
+            "/keyword_filters",
+            web::put().to(route_post::<SaveKeywordFilters>),
+          )
           .route(
             "/change_password",
-            web::put().to(route_post::<ChangePassword>),
+            web::put().to(route_post_with_client_info::<ChangePassword>),
+          )
+          .route("/report_count", web::get().to(route_get::<GetReportCount>))
+          .route("/invite_code", web::get().to(route_get::<ListInviteCodes>))
+          .route(
+            "/invite_code",
+            web::post().to(route_post::<CreateInviteCode>),
+          )
+          .route(
+            "/invite_code/revoke",
+            web::post().to(route_post::<RevokeInviteCode>),
           )
-          .route("/report_count", web::get().to(route_get::<GetReportCount>)),
+          .route(
+            "/totp/generate",
+            web::post().to(route_post::<GenerateTotpSecret>),
+          )
+          .route("/totp/update", web::post().to(route_post::<UpdateTotp>))
+          .route("/move", web::post().to(route_post::<MoveAccount>))
+          .route("/export", web::get().to(route_get::<ExportUserData>))
+          .route("/import", web::post().to(route_post::<ImportUserSettings>)),
       )
       // Admin Actions
       .service(
@@ -240,6 +320,19 @@ where
   perform::<Data>(data.0, context).await
 }
 
+async fn route_post_with_client_info<'a, Data>(
+  data: web::Json<Data>,
+  req: HttpRequest,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error>
+where
+  Data: Deserialize<'a> + Send + 'static + Perform + SetClientInfo,
+{
+  let mut data = data.0;
+  data.set_client_info(client_info(&req));
+  perform::<Data>(data, context).await
+}
+
 async fn perform_crud<Request>(
   data: Request,
   context: web::Data<LemmyContext>,
@@ -275,3 +368,27 @@ where
 {
   perform_crud::<Data>(data.0, context).await
 }
+
+async fn route_post_crud_with_client_info<'a, Data>(
+  data: web::Json<Data>,
+  req: HttpRequest,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error>
+where
+  Data: Deserialize<'a> + Send + 'static + PerformCrud + SetClientInfo,
+{
+  let mut data = data.0;
+  data.set_client_info(client_info(&req));
+  perform_crud::<Data>(data, context).await
+}
+
+fn client_info(req: &HttpRequest) -> ClientInfo {
+  ClientInfo {
+    ip: Some(get_ip(&req.connection_info()).0),
+    user_agent: req
+      .headers()
+      .get("User-Agent")
+      .and_then(|h| h.to_str().ok())
+      .map(|h| h.to_string()),
+  }
+}
@@ -13,6 +13,7 @@ use lemmy_apub::{
 use lemmy_db_queries::{
   source::{comment::Comment_, post::Post_, private_message::PrivateMessage_},
   Crud,
+  SlurFilterMode,
 };
 use lemmy_db_schema::{
   naive_now,
@@ -22,15 +23,14 @@ use lemmy_db_schema::{
     person::{Person, PersonForm},
     post::Post,
     private_message::PrivateMessage,
+    slur_filter::SlurFilterForm,
   },
 };
-use lemmy_utils::{apub::generate_actor_keypair, LemmyError};
-use log::info;
+use lemmy_utils::{apub::generate_actor_keypair, settings::structs::Settings, LemmyError};
+use log::{info, warn};
 
-pub fn run_advanced_migrations(
-  conn: &PgConnection,
-  protocol_and_hostname: &str,
-) -> Result<(), LemmyError> {
+pub fn run_advanced_migrations(conn: &PgConnection, settings: &Settings) -> Result<(), LemmyError> {
+  let protocol_and_hostname = &settings.get_protocol_and_hostname();
   user_updates_2020_04_02(conn, protocol_and_hostname)?;
   community_updates_2020_04_02(conn, protocol_and_hostname)?;
   post_updates_2020_04_03(conn, protocol_and_hostname)?;
@@ -38,6 +38,7 @@ pub fn run_advanced_migrations(
   private_message_updates_2020_05_05(conn, protocol_and_hostname)?;
   post_thumbnail_url_updates_2020_07_27(conn, protocol_and_hostname)?;
   apub_columns_2021_02_02(conn)?;
+  additional_slurs_2021_10_26(conn, &settings.additional_slurs)?;
 
   Ok(())
 }
@@ -287,3 +288,46 @@ fn apub_columns_2021_02_02(conn: &PgConnection) -> Result<(), LemmyError> {
 
   Ok(())
 }
+
+/// Copies `additional_slurs` from the config into a site-wide slur filter, so that admins can
+/// manage it together with the other filters. This only happens once, so that later changes to the
+/// filter aren't undone on restart.
+fn additional_slurs_2021_10_26(
+  conn: &PgConnection,
+  additional_slurs: &Option<String>,
+) -> Result<(), LemmyError> {
+  use lemmy_db_schema::schema::{code_migration, slur_filter};
+
+  let additional_slurs = match additional_slurs {
+    Some(additional_slurs) => additional_slurs,
+    None => return Ok(()),
+  };
+  warn!("The additional_slurs setting is deprecated and ignored, use the site-wide slur filters");
+
+  let migration_name = "additional_slurs_2021_10_26";
+  let already_run =
+    select(dsl::exists(code_migration::table.find(migration_name))).get_result(conn)?;
+  if already_run {
+    return Ok(());
+  }
+
+  info!("Running additional_slurs_2021_10_26");
+  conn.transaction::<_, LemmyError, _>(|| {
+    let form = SlurFilterForm {
+      community_id: None,
+      phrase: additional_slurs.to_owned(),
+      regex: true,
+      mode: SlurFilterMode::Reject as i16,
+    };
+    insert_into(slur_filter::table)
+      .values(&form)
+      .execute(conn)?;
+    insert_into(code_migration::table)
+      .values(code_migration::name.eq(migration_name))
+      .execute(conn)?;
+    Ok(())
+  })?;
+  info!("additional_slurs copied into the site-wide slur filters.");
+
+  Ok(())
+}
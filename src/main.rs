@@ -2,15 +2,20 @@
 extern crate diesel_migrations;
 
 use actix::prelude::*;
-use actix_web::{web::Data, *};
+use actix_web::{
+  rt::signal::unix::{signal, SignalKind},
+  web::Data,
+  *,
+};
 use diesel::{
   r2d2::{ConnectionManager, Pool},
   PgConnection,
 };
 use doku::json::{AutoComments, Formatting};
 use lemmy_api::match_websocket_operation;
-use lemmy_api_common::blocking;
+use lemmy_api_common::{blocking, rebuild_instance_lists, refresh_instance_lists};
 use lemmy_api_crud::match_websocket_operation_crud;
+use lemmy_apub::activity_queue::{ActivityQueueStorage, InstanceHealthTracker};
 use lemmy_apub_lib::activity_queue::create_activity_queue;
 use lemmy_db_queries::{get_database_url_from_env, source::secret::Secret_};
 use lemmy_db_schema::source::secret::Secret;
@@ -19,10 +24,11 @@ use lemmy_server::{api_routes, code_migrations::run_advanced_migrations, schedul
 use lemmy_utils::{
   rate_limit::{rate_limiter::RateLimiter, RateLimit},
   request::build_user_agent,
-  settings::structs::Settings,
+  settings::{documented_settings_type, structs::Settings},
   LemmyError,
 };
 use lemmy_websocket::{chat_server::ChatServer, LemmyContext};
+use log::{error, info};
 use reqwest::Client;
 use std::{env, sync::Arc, thread};
 use tokio::sync::Mutex;
@@ -37,7 +43,11 @@ async fn main() -> Result<(), LemmyError> {
       auto_comments: AutoComments::none(),
       ..Default::default()
     };
-    println!("{}", doku::to_json_fmt_val(&fmt, &Settings::default()));
+    let docs = doku::json::Printer::default()
+      .with_formatting(&fmt)
+      .with_value(&doku::Value::from(&Settings::default()))
+      .print(&documented_settings_type());
+    println!("{}", docs);
     return Ok(());
   }
 
@@ -56,25 +66,40 @@ async fn main() -> Result<(), LemmyError> {
     .unwrap_or_else(|_| panic!("Error connecting to {}", db_url));
 
   // Run the migrations from code
-  let protocol_and_hostname = settings.get_protocol_and_hostname();
+  let migration_settings = settings.clone();
   blocking(&pool, move |conn| {
     embedded_migrations::run(conn)?;
-    run_advanced_migrations(conn, &protocol_and_hostname)?;
+    run_advanced_migrations(conn, &migration_settings)?;
     Ok(()) as Result<(), LemmyError>
   })
   .await??;
 
-  let pool2 = pool.clone();
-  thread::spawn(move || {
-    scheduled_tasks::setup(pool2);
-  });
+  // Load the federation allowlist and blocklist entries which were added by admins
+  refresh_instance_lists(&pool).await?;
 
   // Set up the rate limiter
   let rate_limiter = RateLimit {
     rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
-    rate_limit_config: settings.rate_limit.to_owned().unwrap_or_default(),
   };
 
+  // Reload the config file on SIGHUP. Rate limits and slur filters pick up the new values directly,
+  // the federation lists are rebuilt.
+  let mut hangup = signal(SignalKind::hangup())?;
+  rt::spawn(async move {
+    while hangup.recv().await.is_some() {
+      match Settings::reload() {
+        Ok(()) => {
+          rebuild_instance_lists();
+          info!("Reloaded settings")
+        }
+        Err(e) => error!(
+          "Failed to reload settings, keeping the previous ones: {}",
+          e
+        ),
+      }
+    }
+  });
+
   // Initialize the secrets
   let conn = pool.get()?;
   let secret = Secret::init(&conn).expect("Couldn't initialize secrets.");
@@ -88,7 +113,11 @@ async fn main() -> Result<(), LemmyError> {
     .user_agent(build_user_agent(&settings))
     .build()?;
 
-  let activity_queue = create_activity_queue();
+  let activity_queue_storage = ActivityQueueStorage::init(pool.clone()).await?;
+  let activity_queue = create_activity_queue(
+    activity_queue_storage,
+    InstanceHealthTracker::new(pool.clone()),
+  );
 
   let chat_server = ChatServer::startup(
     pool.clone(),
@@ -97,11 +126,24 @@ async fn main() -> Result<(), LemmyError> {
     |c, i, o, d| Box::pin(match_websocket_operation_crud(c, i, o, d)),
     client.clone(),
     activity_queue.clone(),
-    settings.clone(),
     secret.clone(),
   )
   .start();
 
+  let scheduled_tasks_context = LemmyContext::create(
+    pool.clone(),
+    chat_server.to_owned(),
+    client.clone(),
+    activity_queue.to_owned(),
+    secret.to_owned(),
+  );
+  rt::spawn(scheduled_tasks::run_federated_tasks(
+    scheduled_tasks_context.clone(),
+  ));
+  thread::spawn(move || {
+    scheduled_tasks::setup(scheduled_tasks_context);
+  });
+
   // Create Http server with websocket support
   let settings_bind = settings.clone();
   HttpServer::new(move || {
@@ -110,7 +152,6 @@ async fn main() -> Result<(), LemmyError> {
       chat_server.to_owned(),
       client.clone(),
       activity_queue.to_owned(),
-      settings.to_owned(),
       secret.to_owned(),
     );
     let rate_limiter = rate_limiter.clone();
@@ -1,17 +1,59 @@
 // Scheduler, and trait for .seconds(), .minutes(), etc.
+use anyhow::Context;
 use clokwerk::{Scheduler, TimeUnits};
 // Import week days and WeekDay
-use diesel::{sql_query, PgConnection, RunQueryDsl};
-use lemmy_db_queries::{source::activity::Activity_, DbPool};
-use lemmy_db_schema::source::activity::Activity;
-use log::info;
+use diesel::{sql_query, PgConnection, QueryDsl, RunQueryDsl};
+use lemmy_api_common::blocking;
+use lemmy_apub::{
+  activities::{
+    community::undo_block_user::UndoBlockUserFromCommunity,
+    post::create_or_update::CreateOrUpdatePost,
+    voting::vote::{Vote, VoteType},
+    CreateOrUpdateType,
+  },
+  fetcher::post_or_comment::PostOrComment,
+};
+use lemmy_db_queries::{
+  source::{
+    activity::Activity_,
+    community::CommunityPersonBan_,
+    login_token::LoginToken_,
+    person::Person_,
+    person_ban::PersonBan_,
+    post::Post_,
+  },
+  Bannable,
+  Crud,
+};
+use lemmy_db_schema::{
+  schema::person,
+  source::{
+    activity::Activity,
+    community::{Community, CommunityPersonBan, CommunityPersonBanForm},
+    login_token::LoginToken,
+    moderator::{ModBan, ModBanForm, ModBanFromCommunity, ModBanFromCommunityForm},
+    person::Person,
+    person_ban::{PersonBan, PersonBanForm},
+    post::Post,
+  },
+};
+use lemmy_db_views_actor::{
+  community_person_ban_view::CommunityPersonBanView,
+  person_view::PersonViewSafe,
+};
+use lemmy_utils::{location_info, LemmyError};
+use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
+use log::{error, info};
 use std::{thread, time::Duration};
 
+/// Modlog reason for bans which were lifted automatically
+const BAN_EXPIRED_REASON: &str = "Ban expired";
+
 /// Schedules various cleanup tasks for lemmy in a background thread
-pub fn setup(pool: DbPool) {
+pub fn setup(context: LemmyContext) {
   let mut scheduler = Scheduler::new();
 
-  let conn = pool.get().unwrap();
+  let conn = context.pool().get().unwrap();
   active_counts(&conn);
 
   // On startup, reindex the tables non-concurrently
@@ -22,12 +64,19 @@ pub fn setup(pool: DbPool) {
     reindex_aggregates_tables(&conn, true);
   });
 
-  let conn = pool.get().unwrap();
+  let conn = context.pool().get().unwrap();
   clear_old_activities(&conn);
   scheduler.every(1.weeks()).run(move || {
     clear_old_activities(&conn);
   });
 
+  let conn = context.pool().get().unwrap();
+  let inactive_days = context.settings().session.inactive_days;
+  clear_inactive_login_tokens(&conn, inactive_days);
+  scheduler.every(1.days()).run(move || {
+    clear_inactive_login_tokens(&conn, inactive_days);
+  });
+
   // Manually run the scheduler in an event loop
   loop {
     scheduler.run_pending();
@@ -35,6 +84,21 @@ pub fn setup(pool: DbPool) {
   }
 }
 
+/// Runs the tasks which federate activities once a minute. Unlike `setup()`, this has to be
+/// spawned on the actix runtime of the server.
+pub async fn run_federated_tasks(context: LemmyContext) {
+  let mut interval = actix_rt::time::interval(Duration::from_secs(60));
+  loop {
+    interval.tick().await;
+    if let Err(e) = lift_expired_bans(&context).await {
+      error!("Failed to lift expired bans: {}", e);
+    }
+    if let Err(e) = publish_scheduled_posts(&context).await {
+      error!("Failed to publish scheduled posts: {}", e);
+    }
+  }
+}
+
 /// Reindex the aggregates tables every one hour
 /// This is necessary because hot_rank is actually a mutable function:
 /// https://dba.stackexchange.com/questions/284052/how-to-create-an-index-based-on-a-time-based-function-in-postgres?noredirect=1#comment555727_284052
@@ -63,6 +127,14 @@ fn clear_old_activities(conn: &PgConnection) {
   info!("Done.");
 }
 
+/// Clear login sessions which haven't been used for a while
+fn clear_inactive_login_tokens(conn: &PgConnection, inactive_days: u32) {
+  info!("Clearing inactive login tokens...");
+  LoginToken::delete_inactive(conn, chrono::Duration::days(inactive_days.into()))
+    .expect("clear inactive login tokens");
+  info!("Done.");
+}
+
 /// Re-calculate the site and community active counts every 12 hours
 fn active_counts(conn: &PgConnection) {
   info!("Updating active site and community aggregates ...");
@@ -91,3 +163,172 @@ fn active_counts(conn: &PgConnection) {
 
   info!("Done.");
 }
+
+/// Lifts site and community bans whose expiry time has passed. The unban is logged in the modlog
+/// under the name of the top admin, and community unbans are federated. A ban which can't be
+/// lifted is logged and skipped, so that it doesn't hold up the others.
+async fn lift_expired_bans(context: &LemmyContext) -> Result<(), LemmyError> {
+  let site_bans = blocking(context.pool(), PersonBan::read_expired).await??;
+  let community_bans = blocking(context.pool(), CommunityPersonBan::read_expired).await??;
+  if site_bans.is_empty() && community_bans.is_empty() {
+    return Ok(());
+  }
+
+  let top_admin = blocking(context.pool(), PersonViewSafe::admins)
+    .await??
+    .into_iter()
+    .next()
+    .context(location_info!())?;
+  let admin_id = top_admin.person.id;
+  let admin = blocking(context.pool(), move |conn| Person::read(conn, admin_id)).await??;
+
+  for ban in site_bans {
+    let person_id = ban.person_id;
+    match lift_site_ban(ban, &admin, context).await {
+      Ok(()) => info!("Lifted expired site ban of person {}", person_id.0),
+      Err(e) => error!(
+        "Failed to lift expired site ban of person {}: {}",
+        person_id.0, e
+      ),
+    }
+  }
+
+  for ban in community_bans {
+    let (person_id, community_id) = (ban.person_id, ban.community_id);
+    match lift_community_ban(ban, &admin, context).await {
+      Ok(()) => info!(
+        "Lifted expired ban of person {} from community {}",
+        person_id.0, community_id.0
+      ),
+      Err(e) => error!(
+        "Failed to lift expired ban of person {} from community {}: {}",
+        person_id.0, community_id.0, e
+      ),
+    }
+  }
+
+  Ok(())
+}
+
+async fn lift_site_ban(
+  ban: PersonBan,
+  admin: &Person,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let person_id = ban.person_id;
+  let form = ModBanForm {
+    mod_person_id: admin.id,
+    other_person_id: person_id,
+    reason: Some(BAN_EXPIRED_REASON.to_string()),
+    banned: Some(false),
+    expires: None,
+  };
+  blocking(context.pool(), move |conn| {
+    let person_ban_form = PersonBanForm {
+      person_id,
+      expires: None,
+    };
+    Person::ban_person(conn, person_id, false)?;
+    PersonBan::unban(conn, &person_ban_form)?;
+    ModBan::create(conn, &form)
+  })
+  .await??;
+  Ok(())
+}
+
+async fn lift_community_ban(
+  ban: CommunityPersonBan,
+  admin: &Person,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let form = ModBanFromCommunityForm {
+    mod_person_id: admin.id,
+    other_person_id: ban.person_id,
+    community_id: ban.community_id,
+    reason: Some(BAN_EXPIRED_REASON.to_string()),
+    banned: Some(false),
+    expires: None,
+  };
+  let (community, person) = blocking(context.pool(), move |conn| {
+    let community_person_ban_form = CommunityPersonBanForm {
+      community_id: ban.community_id,
+      person_id: ban.person_id,
+      expires: None,
+    };
+    CommunityPersonBan::unban(conn, &community_person_ban_form)?;
+    ModBanFromCommunity::create(conn, &form)?;
+    Ok((
+      Community::read(conn, ban.community_id)?,
+      Person::read(conn, ban.person_id)?,
+    )) as Result<_, diesel::result::Error>
+  })
+  .await??;
+  UndoBlockUserFromCommunity::send(&community, &person, admin, context).await
+}
+
+/// Publish scheduled posts whose time has come, by federating and broadcasting them like newly
+/// created posts. A failure only affects the post in question, the others are still published.
+async fn publish_scheduled_posts(context: &LemmyContext) -> Result<(), LemmyError> {
+  let posts = blocking(context.pool(), Post::list_due_scheduled).await??;
+
+  for post in posts {
+    let post_id = post.id;
+    if let Err(e) = publish_scheduled_post(post, context).await {
+      error!("Failed to publish scheduled post {}: {}", post_id.0, e);
+    }
+  }
+
+  Ok(())
+}
+
+async fn publish_scheduled_post(post: Post, context: &LemmyContext) -> Result<(), LemmyError> {
+  let post_id = post.id;
+  let creator_id = post.creator_id;
+  let community_id = post.community_id;
+  // Unlike `Person::read`, this also finds deleted accounts
+  let creator = blocking(context.pool(), move |conn| {
+    person::table.find(creator_id).first::<Person>(conn)
+  })
+  .await??;
+
+  // Deleting an account deletes its posts, so that these aren't due anymore. This only catches a
+  // deletion which happened while the post was being read.
+  if creator.deleted {
+    blocking(context.pool(), move |conn| Post::delete(conn, post_id)).await??;
+    info!(
+      "Cancelled scheduled post {}, because its creator was deleted",
+      post_id.0
+    );
+    return Ok(());
+  }
+
+  // Bans can be lifted again, temporary ones even do so on their own. So the post stays scheduled,
+  // and is published by the first run after the ban ends.
+  let is_banned_from_community = blocking(context.pool(), move |conn| {
+    CommunityPersonBanView::get(conn, creator_id, community_id).is_ok()
+  })
+  .await?;
+  if creator.banned || is_banned_from_community {
+    return Ok(());
+  }
+
+  let post = blocking(context.pool(), move |conn| {
+    Post::publish_scheduled(conn, post_id)
+  })
+  .await??;
+
+  CreateOrUpdatePost::send(&post, &creator, CreateOrUpdateType::Create, context).await?;
+  let object = PostOrComment::Post(Box::new(post));
+  Vote::send(&object, &creator, community_id, VoteType::Like, context).await?;
+
+  send_post_ws_message(
+    post_id,
+    UserOperationCrud::CreatePost,
+    None,
+    Some(creator_id),
+    context,
+  )
+  .await?;
+  info!("Published scheduled post {}", post_id.0);
+  Ok(())
+}